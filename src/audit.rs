@@ -0,0 +1,420 @@
+//! Security and support status module
+//!
+//! Derives whether a Go version is still supported from the release index (only the two
+//! newest minor series are), and which known vulnerabilities affect its standard library
+//! from a Go vulnerability database snapshot in OSV format.
+
+use crate::downloader::fetch_text;
+use crate::error::GvmError;
+use crate::releases::ReleaseIndex;
+use crate::version::{GoVersion, PreRelease};
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Filename of the cached vulnerability database in the cache directory
+pub const VULN_DB_CACHE_FILE: &str = "vulndb.json";
+
+/// Number of minor series the Go project supports at a time
+const SUPPORTED_SERIES: usize = 2;
+
+/// OSV package names of the Go standard library and toolchain
+const STDLIB_PACKAGES: [&str; 2] = ["stdlib", "toolchain"];
+
+/// Support status of a version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SupportStatus {
+    /// A release of one of the two newest minor series
+    Supported,
+    /// A release of an older minor series, no longer receiving security fixes
+    Unsupported,
+    /// A beta or release candidate
+    Prerelease,
+    /// Not a release (e.g. a tip or source build)
+    Unknown,
+}
+
+/// When `gvm audit` fails
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum FailOn {
+    /// Vulnerable or unsupported versions
+    #[default]
+    Any,
+    /// Vulnerable versions only
+    Vulnerable,
+    /// Unsupported versions only
+    Unsupported,
+    /// Never; only report
+    Never,
+}
+
+/// A known vulnerability affecting a version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Vulnerability {
+    /// OSV identifier (e.g. "GO-2024-2687")
+    pub id: String,
+    /// CVE and other identifiers
+    pub aliases: Vec<String>,
+    pub summary: Option<String>,
+    /// First release of the series that fixes it
+    pub fixed: Option<String>,
+}
+
+/// Support and vulnerability status of a version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VersionAudit {
+    pub version: String,
+    pub support: SupportStatus,
+    /// Newest release of the series, if newer than the version
+    pub latest_patch: Option<String>,
+    pub vulnerabilities: Vec<Vulnerability>,
+}
+
+impl VersionAudit {
+    /// Audits a version against the release index and vulnerability database, when available
+    pub fn new(version: &str, index: Option<&ReleaseIndex>, db: Option<&VulnDb>) -> Self {
+        let parsed = GoVersion::parse(version);
+        let support = match (&parsed, index) {
+            (None, _) | (_, None) => SupportStatus::Unknown,
+            (Some(parsed), Some(index)) => support_status(parsed, index),
+        };
+        let latest_patch = parsed.as_ref().zip(index).and_then(|(parsed, index)| {
+            let latest = index.latest_in_series(parsed.major, parsed.minor)?;
+            let latest = GoVersion::parse(latest.version_number())?;
+            (latest > *parsed).then(|| latest.to_string())
+        });
+        let vulnerabilities =
+            parsed.as_ref().zip(db).map(|(parsed, db)| db.affecting(parsed)).unwrap_or_default();
+        Self { version: version.to_string(), support, latest_patch, vulnerabilities }
+    }
+
+    pub fn is_vulnerable(&self) -> bool {
+        !self.vulnerabilities.is_empty()
+    }
+
+    pub fn is_unsupported(&self) -> bool {
+        self.support == SupportStatus::Unsupported
+    }
+
+    /// Summarizes the problems, e.g. "unsupported, 3 known vulnerabilities"
+    pub fn problems(&self) -> Option<String> {
+        let mut problems = Vec::new();
+        if self.is_unsupported() {
+            problems.push("unsupported".to_string());
+        }
+        match self.vulnerabilities.len() {
+            0 => {}
+            1 => problems.push("1 known vulnerability".to_string()),
+            n => problems.push(format!("{n} known vulnerabilities")),
+        }
+        (!problems.is_empty()).then(|| problems.join(", "))
+    }
+}
+
+/// Gets the minor series the Go project currently supports, newest first
+pub fn supported_series(index: &ReleaseIndex) -> Vec<(u32, u32)> {
+    let mut series: Vec<(u32, u32)> = Vec::new();
+    for release in index.releases.iter().filter(|r| r.stable) {
+        let Some(version) = GoVersion::parse(release.version_number()) else {
+            continue;
+        };
+        if !series.contains(&version.series()) {
+            series.push(version.series());
+        }
+    }
+    series.sort_unstable_by(|a, b| b.cmp(a));
+    series.truncate(SUPPORTED_SERIES);
+    series
+}
+
+fn support_status(version: &GoVersion, index: &ReleaseIndex) -> SupportStatus {
+    if !version.is_stable() {
+        return SupportStatus::Prerelease;
+    }
+    if supported_series(index).contains(&version.series()) {
+        SupportStatus::Supported
+    } else {
+        SupportStatus::Unsupported
+    }
+}
+
+/// A Go vulnerability database snapshot
+#[derive(Debug, Clone, Default)]
+pub struct VulnDb {
+    entries: Vec<OsvEntry>,
+}
+
+/// An OSV vulnerability entry, reduced to the fields gvm uses
+#[derive(Debug, Clone, Deserialize)]
+struct OsvEntry {
+    id: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    summary: Option<String>,
+    #[serde(default)]
+    affected: Vec<OsvAffected>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct OsvAffected {
+    package: OsvPackage,
+    #[serde(default)]
+    ranges: Vec<OsvRange>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct OsvPackage {
+    name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct OsvRange {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<OsvEvent>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct OsvEvent {
+    introduced: Option<String>,
+    fixed: Option<String>,
+}
+
+/// A snapshot is an array of entries or a single entry
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OsvDocument {
+    Entries(Vec<OsvEntry>),
+    Entry(Box<OsvEntry>),
+}
+
+impl VulnDb {
+    /// Parses an OSV snapshot: a JSON array of entries or a single entry
+    ///
+    /// # Errors
+    /// Returns an error if the content is not OSV JSON.
+    pub fn parse(content: &str) -> Result<Self> {
+        let entries = match serde_json::from_str(content)? {
+            OsvDocument::Entries(entries) => entries,
+            OsvDocument::Entry(entry) => vec![*entry],
+        };
+        Ok(Self { entries })
+    }
+
+    /// Loads a snapshot from a file, or from a URL that is fetched and cached
+    ///
+    /// A URL falls back to the cached copy when it cannot be fetched.
+    ///
+    /// # Errors
+    /// Returns an error if the snapshot cannot be read, fetched or parsed.
+    pub async fn load(source: &str, cache_dir: &Path) -> Result<Self> {
+        if !is_url(source) {
+            return Self::read(Path::new(source));
+        }
+
+        let cache_file = Self::cache_file(cache_dir);
+        match fetch_text(source, "vulnerability database").await {
+            Ok(content) => {
+                let db = Self::parse(&content)
+                    .with_context(|| format!("Invalid vulnerability database from {source}"))?;
+                if let Err(e) = std::fs::create_dir_all(cache_dir)
+                    .and_then(|()| std::fs::write(&cache_file, content))
+                {
+                    warn!("Failed to cache vulnerability database: {e}");
+                }
+                Ok(db)
+            }
+            Err(e) if cache_file.exists() => {
+                warn!("Failed to fetch vulnerability database, using cached copy: {e}");
+                Self::read(&cache_file)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Loads a snapshot without contacting the network: the file itself, or the cached copy
+    /// of a URL
+    pub fn load_offline(source: &str, cache_dir: &Path) -> Option<Self> {
+        let path = if is_url(source) { Self::cache_file(cache_dir) } else { PathBuf::from(source) };
+        Self::read(&path).map_err(|e| debug!("No vulnerability database: {e}")).ok()
+    }
+
+    /// Gets the cache file path for a cache directory
+    pub fn cache_file(cache_dir: &Path) -> PathBuf {
+        cache_dir.join(VULN_DB_CACHE_FILE)
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(GvmError::io("read", path))?;
+        Self::parse(&content)
+            .with_context(|| format!("Invalid vulnerability database {}", path.display()))
+    }
+
+    /// Finds the vulnerabilities affecting the standard library or toolchain of a version
+    pub fn affecting(&self, version: &GoVersion) -> Vec<Vulnerability> {
+        let mut found: Vec<Vulnerability> = Vec::new();
+        for entry in &self.entries {
+            let affected = entry
+                .affected
+                .iter()
+                .filter(|a| STDLIB_PACKAGES.contains(&a.package.name.as_str()))
+                .flat_map(|a| &a.ranges)
+                .filter(|range| range.kind == "SEMVER")
+                .find_map(|range| affected_range(&range.events, version));
+            if let Some(fixed) = affected {
+                if found.iter().all(|v| v.id != entry.id) {
+                    found.push(Vulnerability {
+                        id: entry.id.clone(),
+                        aliases: entry.aliases.clone(),
+                        summary: entry.summary.clone(),
+                        fixed: fixed.map(|v| v.to_string()),
+                    });
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Checks a version against the ordered events of a range
+///
+/// Returns `Some(fixed)` if the version is affected, with the release that fixes it.
+fn affected_range(events: &[OsvEvent], version: &GoVersion) -> Option<Option<GoVersion>> {
+    let mut affected = false;
+    for event in events {
+        if let Some(introduced) = event.introduced.as_deref().and_then(parse_semver) {
+            if *version >= introduced {
+                affected = true;
+            }
+        }
+        if let Some(fixed) = event.fixed.as_deref().and_then(parse_semver) {
+            if affected && *version < fixed {
+                return Some(Some(fixed));
+            }
+            if *version >= fixed {
+                affected = false;
+            }
+        }
+    }
+    affected.then_some(None)
+}
+
+/// Parses the semantic versions the Go vulnerability database uses for Go releases
+///
+/// e.g. "1.21.5", "1.22.0-rc.1" (go1.22rc1), "1.21.0-0" (before any 1.21 pre-release) and
+/// "0" (the first version).
+fn parse_semver(version: &str) -> Option<GoVersion> {
+    if version == "0" {
+        return Some(GoVersion { major: 0, minor: 0, patch: None, pre: Some(PreRelease::Beta(0)) });
+    }
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+    let mut parts = core.split('.').map(str::parse::<u32>);
+    let (major, minor, patch) = (parts.next()?.ok()?, parts.next()?.ok()?, parts.next()?.ok()?);
+
+    let pre = match pre {
+        None => return Some(GoVersion { major, minor, patch: Some(patch), pre: None }),
+        Some("0") => PreRelease::Beta(0),
+        Some(pre) => {
+            let (kind, number) = pre.split_once('.')?;
+            let number = number.parse().ok()?;
+            match kind {
+                "rc" => PreRelease::Rc(number),
+                "beta" => PreRelease::Beta(number),
+                _ => return None,
+            }
+        }
+    };
+    Some(GoVersion { major, minor, patch: None, pre: Some(pre) })
+}
+
+fn is_url(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::releases::GoRelease;
+
+    fn index() -> ReleaseIndex {
+        let release = |version: &str, stable| GoRelease {
+            version: format!("go{version}"),
+            stable,
+            files: Vec::new(),
+        };
+        ReleaseIndex::new(vec![
+            release("1.23rc1", false),
+            release("1.22.5", true),
+            release("1.21.12", true),
+            release("1.20.14", true),
+        ])
+    }
+
+    const DB: &str = r#"[
+        {
+            "id": "GO-2024-0001",
+            "aliases": ["CVE-2024-0001"],
+            "summary": "Bug in net/http",
+            "affected": [{
+                "package": {"name": "stdlib", "ecosystem": "Go"},
+                "ranges": [{"type": "SEMVER", "events": [
+                    {"introduced": "0"}, {"fixed": "1.21.11"},
+                    {"introduced": "1.22.0-0"}, {"fixed": "1.22.4"}
+                ]}]
+            }]
+        },
+        {
+            "id": "GO-2024-0002",
+            "affected": [{
+                "package": {"name": "golang.org/x/net", "ecosystem": "Go"},
+                "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}]}]
+            }]
+        }
+    ]"#;
+
+    #[test]
+    fn test_support_status() {
+        let index = index();
+        assert_eq!(supported_series(&index), [(1, 22), (1, 21)]);
+
+        let audit = VersionAudit::new("1.21.3", Some(&index), None);
+        assert_eq!(audit.support, SupportStatus::Supported);
+        assert_eq!(audit.latest_patch.as_deref(), Some("1.21.12"));
+        assert_eq!(
+            VersionAudit::new("1.20.14", Some(&index), None).support,
+            SupportStatus::Unsupported
+        );
+        assert_eq!(
+            VersionAudit::new("1.23rc1", Some(&index), None).support,
+            SupportStatus::Prerelease
+        );
+        assert_eq!(
+            VersionAudit::new("tip-abc1234", Some(&index), None).support,
+            SupportStatus::Unknown
+        );
+    }
+
+    #[test]
+    fn test_vulnerabilities() {
+        let db = VulnDb::parse(DB).unwrap();
+        let affecting = |version: &str| db.affecting(&GoVersion::parse(version).unwrap());
+
+        let found = affecting("1.22.3");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "GO-2024-0001");
+        assert_eq!(found[0].fixed.as_deref(), Some("1.22.4"));
+        assert_eq!(affecting("1.20.14")[0].fixed.as_deref(), Some("1.21.11"));
+        assert_eq!(affecting("1.22rc1").len(), 1);
+        assert!(affecting("1.21.11").is_empty());
+        assert!(affecting("1.22.5").is_empty());
+
+        let audit = VersionAudit::new("1.20.14", Some(&index()), Some(&db));
+        assert_eq!(audit.problems().as_deref(), Some("unsupported, 1 known vulnerability"));
+    }
+}
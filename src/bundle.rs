@@ -0,0 +1,226 @@
+//! Offline bundle module
+//!
+//! A bundle is a tar file carrying Go archives for several versions and platforms, their
+//! checksums and a snapshot of the release index, so that machines without network access
+//! can install from it:
+//!
+//! ```text
+//! gvm-bundle.json           manifest with the versions and archive checksums
+//! releases.json             release index snapshot
+//! archives/<filename>       Go archives
+//! ```
+
+use crate::error::GvmError;
+use crate::lockfile::{LockedArchive, LockedVersion};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// Manifest entry name
+pub const BUNDLE_MANIFEST: &str = "gvm-bundle.json";
+
+/// Release index snapshot entry name
+pub const BUNDLE_INDEX: &str = "releases.json";
+
+/// Directory of the archive entries
+const ARCHIVES_DIR: &str = "archives";
+
+/// Format version written to new bundles
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Contents of a bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub bundle_version: u32,
+    pub created_at: DateTime<Utc>,
+    /// Bundled versions with the checksums of their bundled archives
+    pub versions: Vec<LockedVersion>,
+}
+
+impl BundleManifest {
+    /// Creates a manifest in the current format
+    pub fn new(versions: Vec<LockedVersion>) -> Self {
+        Self { bundle_version: BUNDLE_VERSION, created_at: Utc::now(), versions }
+    }
+
+    /// Finds the bundled archive of a version for a platform
+    pub fn archive_for(&self, version: &str, os: &str, arch: &str) -> Option<&LockedArchive> {
+        self.versions.iter().find(|v| v.version == version)?.archive_for(os, arch)
+    }
+}
+
+/// Writes a bundle
+///
+/// `archives` are the paths of the archive files listed in the manifest.
+///
+/// # Errors
+/// Returns an error if the bundle or an archive cannot be written or read.
+pub fn create(
+    path: &Path,
+    manifest: &BundleManifest,
+    index_snapshot: &[u8],
+    archives: &[PathBuf],
+) -> Result<()> {
+    let file = File::create(path).map_err(GvmError::io("create", path))?;
+    let mut builder = tar::Builder::new(file);
+
+    let manifest = serde_json::to_vec_pretty(manifest)?;
+    append_data(&mut builder, BUNDLE_MANIFEST, &manifest)?;
+    append_data(&mut builder, BUNDLE_INDEX, index_snapshot)?;
+    for archive in archives {
+        let name = archive.file_name().ok_or_else(|| anyhow!("Invalid archive path"))?;
+        let entry = Path::new(ARCHIVES_DIR).join(name);
+        builder
+            .append_path_with_name(archive, entry)
+            .with_context(|| format!("Failed to add {} to the bundle", archive.display()))?;
+    }
+    builder.into_inner()?.sync_all().map_err(GvmError::io("write", path))?;
+    Ok(())
+}
+
+fn append_data(builder: &mut tar::Builder<File>, name: &str, data: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(u64::try_from(Utc::now().timestamp()).unwrap_or_default());
+    header.set_cksum();
+    builder.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+/// A bundle opened for installing
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    path: PathBuf,
+    manifest: BundleManifest,
+}
+
+impl Bundle {
+    /// Opens a bundle and reads its manifest
+    ///
+    /// # Errors
+    /// Returns an error if the file is not a bundle or was written in a newer format.
+    pub fn open(path: &Path) -> Result<Self> {
+        let data = read_entry(path, BUNDLE_MANIFEST)?;
+        let manifest: BundleManifest = serde_json::from_slice(&data)
+            .with_context(|| format!("Invalid bundle manifest in {}", path.display()))?;
+        if manifest.bundle_version > BUNDLE_VERSION {
+            return Err(GvmError::Usage(format!(
+                "{} uses bundle version {}, but this gvm supports up to {BUNDLE_VERSION}",
+                path.display(),
+                manifest.bundle_version
+            ))
+            .into());
+        }
+        Ok(Self { path: path.to_path_buf(), manifest })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn manifest(&self) -> &BundleManifest {
+        &self.manifest
+    }
+
+    /// Reads the release index snapshot
+    ///
+    /// # Errors
+    /// Returns an error if the bundle has no snapshot.
+    pub fn read_index(&self) -> Result<Vec<u8>> {
+        read_entry(&self.path, BUNDLE_INDEX)
+    }
+
+    /// Extracts a bundled archive to `dest`, verifying it against the manifest checksum
+    ///
+    /// # Errors
+    /// Returns an error if the archive is not in the bundle or its checksum differs.
+    pub fn extract_archive(&self, archive: &LockedArchive, dest: &Path) -> Result<()> {
+        let entry_name = Path::new(ARCHIVES_DIR).join(&archive.filename);
+        let mut tar = tar::Archive::new(BufReader::new(open(&self.path)?));
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            if entry.path()? != entry_name {
+                continue;
+            }
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent).map_err(GvmError::io("create", parent))?;
+            }
+            entry.unpack(dest).map_err(GvmError::io("extract", dest))?;
+
+            let actual =
+                crate::downloader::sha256_file(dest).map_err(GvmError::io("checksum", dest))?;
+            if !actual.eq_ignore_ascii_case(&archive.sha256) {
+                let _ = std::fs::remove_file(dest);
+                return Err(GvmError::ChecksumMismatch {
+                    file: archive.filename.clone(),
+                    expected: archive.sha256.clone(),
+                    actual,
+                }
+                .into());
+            }
+            return Ok(());
+        }
+        Err(anyhow!("{} is missing from bundle {}", archive.filename, self.path.display()))
+    }
+}
+
+fn open(path: &Path) -> Result<File> {
+    Ok(File::open(path).map_err(GvmError::io("open", path))?)
+}
+
+/// Reads a small entry of a bundle into memory
+fn read_entry(path: &Path, name: &str) -> Result<Vec<u8>> {
+    let mut tar = tar::Archive::new(BufReader::new(open(path)?));
+    for entry in tar.entries().with_context(|| format!("{} is not a bundle", path.display()))? {
+        let mut entry = entry.with_context(|| format!("{} is not a bundle", path.display()))?;
+        if entry.path()?.as_ref() == Path::new(name) {
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            return Ok(data);
+        }
+    }
+    Err(anyhow!("{} is not a bundle: {name} is missing", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("go1.22.5.linux-arm64.tar.gz");
+        std::fs::write(&archive_path, b"archive").unwrap();
+        let archive = LockedArchive {
+            os: "linux".into(),
+            arch: "arm64".into(),
+            filename: "go1.22.5.linux-arm64.tar.gz".into(),
+            sha256: crate::downloader::sha256_file(&archive_path).unwrap(),
+            size: 7,
+        };
+        let manifest = BundleManifest::new(vec![LockedVersion {
+            version: "1.22.5".into(),
+            archives: vec![archive.clone()],
+        }]);
+
+        let bundle_path = dir.path().join("go-bundle.tar");
+        create(&bundle_path, &manifest, b"[]", &[archive_path]).unwrap();
+        let bundle = Bundle::open(&bundle_path).unwrap();
+        assert_eq!(bundle.manifest(), &manifest);
+        assert_eq!(bundle.read_index().unwrap(), b"[]");
+        assert!(bundle.manifest().archive_for("1.22.5", "linux", "amd64").is_none());
+
+        let dest = dir.path().join("cache").join(&archive.filename);
+        bundle.extract_archive(&archive, &dest).unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"archive");
+
+        let corrupt = LockedArchive { sha256: "0".repeat(64), ..archive };
+        assert!(bundle.extract_archive(&corrupt, &dest).is_err());
+        assert!(!dest.exists());
+        assert!(Bundle::open(&dest).is_err());
+    }
+}
@@ -0,0 +1,473 @@
+//! Download cache module
+//!
+//! Lists, verifies and trims the archives kept in the cache directory, and selects what
+//! the automatic cache policy (`GVM_CACHE_KEEP`, `GVM_CACHE_MAX_SIZE`) removes after each
+//! installation.
+
+use crate::error::GvmError;
+use crate::releases::ReleaseIndex;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Which archives the cache policy keeps
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheKeep {
+    /// Every archive
+    #[default]
+    All,
+    /// Only archives of installed versions
+    Installed,
+}
+
+/// Cache policy applied after each installation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CachePolicy {
+    pub keep: CacheKeep,
+    /// Size the cache is trimmed to, removing the oldest archives first
+    pub max_size: Option<u64>,
+}
+
+impl CachePolicy {
+    /// Parses the policy from `GVM_CACHE_KEEP` ("all" or "installed") and `GVM_CACHE_MAX_SIZE`
+    ///
+    /// # Errors
+    /// Returns an error if a value is invalid.
+    pub fn from_values(keep: Option<&str>, max_size: Option<&str>) -> Result<Self> {
+        let keep = match keep.map(str::trim) {
+            None | Some("" | "all") => CacheKeep::All,
+            Some("installed") => CacheKeep::Installed,
+            Some(other) => {
+                return Err(GvmError::Usage(format!(
+                    "Invalid GVM_CACHE_KEEP '{other}': expected 'all' or 'installed'"
+                ))
+                .into())
+            }
+        };
+        let max_size = match max_size.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(value) => Some(
+                parse_size(value)
+                    .map_err(|e| GvmError::Usage(format!("Invalid GVM_CACHE_MAX_SIZE: {e}")))?,
+            ),
+            None => None,
+        };
+        Ok(Self { keep, max_size })
+    }
+
+    /// Selects the archives the policy removes, given the installed version names
+    pub fn select<'a>(
+        &self,
+        archives: &'a [CachedArchive],
+        installed: &[String],
+    ) -> Vec<&'a CachedArchive> {
+        let (mut selected, kept): (Vec<_>, Vec<_>) = archives.iter().partition(|archive| {
+            self.keep == CacheKeep::Installed && !archive.is_installed(installed)
+        });
+        if let Some(max_size) = self.max_size {
+            selected.extend(over_budget(kept, max_size));
+        }
+        selected
+    }
+}
+
+/// Filters for `gvm cache clean`; all given filters must match
+#[derive(Debug, Clone, Default)]
+pub struct CleanFilter {
+    /// Versions or minor series (e.g. "1.21") to remove
+    pub versions: Vec<String>,
+    pub older_than: Option<Duration>,
+    pub not_installed: bool,
+    /// Remove matching archives, oldest first, only until the cache fits this size
+    pub max_size: Option<u64>,
+}
+
+impl CleanFilter {
+    /// Selects the archives to remove, given the installed version names
+    pub fn select<'a>(
+        &self,
+        archives: &'a [CachedArchive],
+        installed: &[String],
+    ) -> Vec<&'a CachedArchive> {
+        let (matching, kept): (Vec<_>, Vec<_>) =
+            archives.iter().partition(|archive| self.matches(archive, installed));
+        match self.max_size {
+            Some(max_size) => {
+                let kept_size: u64 = kept.iter().map(|a| a.size).sum();
+                over_budget(matching, max_size.saturating_sub(kept_size))
+            }
+            None => matching,
+        }
+    }
+
+    fn matches(&self, archive: &CachedArchive, installed: &[String]) -> bool {
+        let version_matches = self.versions.is_empty()
+            || archive.version.as_deref().is_some_and(|version| {
+                self.versions.iter().any(|v| version == v || version.starts_with(&format!("{v}.")))
+            });
+        version_matches
+            && self.older_than.map_or(true, |age| archive.age() >= age)
+            && (!self.not_installed || !archive.is_installed(installed))
+    }
+}
+
+/// Picks the oldest archives until the rest fit within `max_size`
+fn over_budget(mut archives: Vec<&CachedArchive>, max_size: u64) -> Vec<&CachedArchive> {
+    archives.sort_by_key(|archive| archive.modified);
+    let mut total: u64 = archives.iter().map(|a| a.size).sum();
+    let mut selected = Vec::new();
+    for archive in archives {
+        if total <= max_size {
+            break;
+        }
+        total -= archive.size;
+        selected.push(archive);
+    }
+    selected
+}
+
+/// A Go archive in the cache directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CachedArchive {
+    pub filename: String,
+    pub path: PathBuf,
+    /// Version number (e.g. "1.21.3"), if the filename follows the official naming
+    pub version: Option<String>,
+    /// Target operating system; `None` for source archives
+    pub os: Option<String>,
+    /// Target architecture; `None` for source archives
+    pub arch: Option<String>,
+    pub size: u64,
+    /// Last modification (download) time
+    pub modified: DateTime<Utc>,
+}
+
+impl CachedArchive {
+    /// Gets how long ago the archive was downloaded
+    pub fn age(&self) -> Duration {
+        (Utc::now() - self.modified).to_std().unwrap_or_default()
+    }
+
+    /// Checks whether the archive's version is among the installed version names
+    pub fn is_installed(&self, installed: &[String]) -> bool {
+        self.version.as_ref().is_some_and(|version| installed.contains(version))
+    }
+
+    /// Describes the platform, e.g. "linux-amd64" or "source"
+    pub fn platform(&self) -> String {
+        match (&self.os, &self.arch) {
+            (Some(os), Some(arch)) => format!("{os}-{arch}"),
+            _ => "source".to_string(),
+        }
+    }
+}
+
+/// Result of checking a cached archive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "problem", rename_all = "snake_case")]
+pub enum ArchiveCheck {
+    /// Matches the published size and checksum
+    Verified,
+    /// Not in the release index, but a readable archive
+    Readable,
+    /// Damaged or incomplete
+    Corrupt(String),
+}
+
+/// The cache directory
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Lists the cached archives, sorted by version
+    ///
+    /// # Errors
+    /// Returns an error if the cache directory exists but cannot be read.
+    pub fn archives(&self) -> Result<Vec<CachedArchive>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut archives = Vec::new();
+        for entry in std::fs::read_dir(&self.dir).map_err(GvmError::io("read", &self.dir))? {
+            let entry = entry.map_err(GvmError::io("read", &self.dir))?;
+            let filename = entry.file_name().to_string_lossy().to_string();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() || !is_archive_name(&filename) {
+                continue;
+            }
+
+            let (version, platform) = match parse_archive_filename(&filename) {
+                Some((version, platform)) => (Some(version), platform),
+                None => (None, None),
+            };
+            let (os, arch) = platform.unzip();
+            archives.push(CachedArchive {
+                path: entry.path(),
+                filename,
+                version,
+                os,
+                arch,
+                size: metadata.len(),
+                modified: metadata
+                    .modified()
+                    .map(DateTime::<Utc>::from)
+                    .unwrap_or_else(|_| Utc::now()),
+            });
+        }
+
+        archives.sort_by(|a, b| match (&a.version, &b.version) {
+            (Some(x), Some(y)) => {
+                crate::version::compare_versions(x, y).then_with(|| a.filename.cmp(&b.filename))
+            }
+            _ => a.filename.cmp(&b.filename),
+        });
+        Ok(archives)
+    }
+
+    /// Gets the size of everything in the cache directory, including the release index
+    ///
+    /// # Errors
+    /// Returns an error if the cache directory exists but cannot be read.
+    pub fn total_size(&self) -> Result<u64> {
+        if !self.dir.exists() {
+            return Ok(0);
+        }
+        let mut total = 0;
+        for entry in std::fs::read_dir(&self.dir).map_err(GvmError::io("read", &self.dir))? {
+            let entry = entry.map_err(GvmError::io("read", &self.dir))?;
+            if let Ok(metadata) = entry.metadata() {
+                if metadata.is_file() {
+                    total += metadata.len();
+                }
+            }
+        }
+        Ok(total)
+    }
+
+    /// Deletes a cached archive
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be deleted.
+    pub fn remove(&self, archive: &CachedArchive) -> Result<()> {
+        std::fs::remove_file(&archive.path).map_err(GvmError::io("delete", &archive.path))?;
+        Ok(())
+    }
+}
+
+/// Checks whether a filename is a Go archive
+pub fn is_archive_name(name: &str) -> bool {
+    name.ends_with(".tar.gz") || name.ends_with(".zip")
+}
+
+/// Splits an official archive filename into the version and, for binary archives, the
+/// operating system and architecture
+///
+/// e.g. "go1.21.3.linux-amd64.tar.gz" -> ("1.21.3", Some(("linux", "amd64"))),
+/// "go1.21.3.src.tar.gz" -> ("1.21.3", None)
+pub fn parse_archive_filename(name: &str) -> Option<(String, Option<(String, String)>)> {
+    let stem = name.strip_prefix("go")?;
+    let stem = stem.strip_suffix(".tar.gz").or_else(|| stem.strip_suffix(".zip"))?;
+    if let Some(version) = stem.strip_suffix(".src") {
+        return Some((version.to_string(), None));
+    }
+    let (version, platform) = stem.rsplit_once('.')?;
+    let (os, arch) = platform.split_once('-')?;
+    Some((version.to_string(), Some((os.to_string(), arch.to_string()))))
+}
+
+/// Checks a cached archive
+///
+/// Archives listed in the release index are checked against their published size and
+/// checksum; others only have to be readable archives.
+pub fn check_archive(path: &Path, index: Option<&ReleaseIndex>) -> ArchiveCheck {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    if size == 0 {
+        return ArchiveCheck::Corrupt("the file is empty".to_string());
+    }
+
+    if let Some(file) = index.and_then(|index| index.find_file(&name)) {
+        if size != file.size {
+            return ArchiveCheck::Corrupt(format!("expected {} bytes, found {size}", file.size));
+        }
+        return match crate::downloader::sha256_file(path) {
+            Ok(actual) if actual.eq_ignore_ascii_case(&file.sha256) => ArchiveCheck::Verified,
+            Ok(actual) => {
+                ArchiveCheck::Corrupt(format!("checksum {actual} does not match {}", file.sha256))
+            }
+            Err(e) => ArchiveCheck::Corrupt(format!("cannot be read: {e}")),
+        };
+    }
+
+    match readable_archive(path, &name) {
+        Ok(()) => ArchiveCheck::Readable,
+        Err(e) => ArchiveCheck::Corrupt(format!("cannot be read: {e}")),
+    }
+}
+
+/// Reads an archive to the end to check that it is intact
+fn readable_archive(path: &Path, name: &str) -> std::io::Result<()> {
+    let file = std::fs::File::open(path)?;
+    if name.ends_with(".zip") {
+        zip::ZipArchive::new(file)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    } else {
+        #[cfg(not(target_os = "windows"))]
+        std::io::copy(&mut flate2::read::GzDecoder::new(file), &mut std::io::sink())?;
+    }
+    Ok(())
+}
+
+/// Parses a size such as "500M", "2G", "1.5GB" or a plain number of bytes
+///
+/// # Errors
+/// Returns a description of the problem if the size is invalid.
+pub fn parse_size(value: &str) -> std::result::Result<u64, String> {
+    let value = value.trim();
+    let upper = value.to_ascii_uppercase();
+    let number = upper.trim_end_matches(['B', 'I']);
+    let (number, multiplier) = match number.chars().last() {
+        Some('K') => (&number[..number.len() - 1], 1u64 << 10),
+        Some('M') => (&number[..number.len() - 1], 1 << 20),
+        Some('G') => (&number[..number.len() - 1], 1 << 30),
+        Some('T') => (&number[..number.len() - 1], 1 << 40),
+        _ => (number, 1),
+    };
+    let number: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid size '{value}' (expected e.g. 500M or 2G)"))?;
+    if number < 0.0 {
+        return Err(format!("invalid size '{value}'"));
+    }
+    Ok((number * multiplier as f64) as u64)
+}
+
+/// Parses an age such as "30d", "12h", "2w" or "45m"
+///
+/// # Errors
+/// Returns a description of the problem if the age is invalid.
+pub fn parse_age(value: &str) -> std::result::Result<Duration, String> {
+    let value = value.trim();
+    let invalid = || format!("invalid age '{value}' (expected e.g. 30d, 12h or 2w)");
+    let unit = value.chars().last().ok_or_else(invalid)?;
+    let seconds = match unit {
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        'w' => 7 * 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+    let count: u64 = value[..value.len() - 1].parse().map_err(|_| invalid())?;
+    Ok(Duration::from_secs(count * seconds))
+}
+
+/// Formats an age coarsely, e.g. "3d" or "5h"
+pub fn format_age(age: Duration) -> String {
+    let hours = age.as_secs() / 3600;
+    match hours {
+        0 => "<1h".to_string(),
+        1..=47 => format!("{hours}h"),
+        _ => format!("{}d", hours / 24),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archive(version: &str, size: u64, days_old: i64) -> CachedArchive {
+        CachedArchive {
+            filename: format!("go{version}.linux-amd64.tar.gz"),
+            path: PathBuf::new(),
+            version: Some(version.to_string()),
+            os: Some("linux".to_string()),
+            arch: Some("amd64".to_string()),
+            size,
+            modified: Utc::now() - chrono::Duration::days(days_old),
+        }
+    }
+
+    fn versions(selected: &[&CachedArchive]) -> Vec<String> {
+        selected.iter().filter_map(|a| a.version.clone()).collect()
+    }
+
+    #[test]
+    fn test_parse_archive_filename() {
+        assert_eq!(
+            parse_archive_filename("go1.21.3.linux-amd64.tar.gz"),
+            Some(("1.21.3".to_string(), Some(("linux".to_string(), "amd64".to_string()))))
+        );
+        assert_eq!(
+            parse_archive_filename("go1.22rc1.windows-386.zip"),
+            Some(("1.22rc1".to_string(), Some(("windows".to_string(), "386".to_string()))))
+        );
+        assert_eq!(
+            parse_archive_filename("go1.21.3.src.tar.gz"),
+            Some(("1.21.3".to_string(), None))
+        );
+        assert_eq!(parse_archive_filename("releases.json"), None);
+    }
+
+    #[test]
+    fn test_parse_size_and_age() {
+        assert_eq!(parse_size("500M"), Ok(500 << 20));
+        assert_eq!(parse_size("2GB"), Ok(2 << 30));
+        assert_eq!(parse_size("1.5g"), Ok(3 << 29));
+        assert_eq!(parse_size("1024"), Ok(1024));
+        assert!(parse_size("lots").is_err());
+
+        assert_eq!(parse_age("30d"), Ok(Duration::from_secs(30 * 86400)));
+        assert_eq!(parse_age("2w"), Ok(Duration::from_secs(14 * 86400)));
+        assert!(parse_age("30").is_err());
+    }
+
+    #[test]
+    fn test_clean_filter() {
+        let archives = vec![
+            archive("1.20.14", 100, 40),
+            archive("1.21.3", 100, 20),
+            archive("1.21.4", 100, 1),
+        ];
+        let installed = vec!["1.21.4".to_string()];
+
+        let filter = CleanFilter { versions: vec!["1.21".to_string()], ..Default::default() };
+        assert_eq!(versions(&filter.select(&archives, &installed)), ["1.21.3", "1.21.4"]);
+
+        let filter =
+            CleanFilter { older_than: Some(Duration::from_secs(10 * 86400)), ..Default::default() };
+        assert_eq!(versions(&filter.select(&archives, &installed)), ["1.20.14", "1.21.3"]);
+
+        let filter = CleanFilter { not_installed: true, max_size: Some(250), ..Default::default() };
+        assert_eq!(versions(&filter.select(&archives, &installed)), ["1.20.14"]);
+    }
+
+    #[test]
+    fn test_cache_policy() {
+        let archives = vec![
+            archive("1.20.14", 100, 40),
+            archive("1.21.3", 100, 20),
+            archive("1.21.4", 100, 1),
+        ];
+        let installed = vec!["1.21.3".to_string(), "1.21.4".to_string()];
+
+        assert!(CachePolicy::default().select(&archives, &installed).is_empty());
+
+        let policy = CachePolicy::from_values(Some("installed"), None).unwrap();
+        assert_eq!(versions(&policy.select(&archives, &installed)), ["1.20.14"]);
+
+        let policy = CachePolicy::from_values(None, Some("150")).unwrap();
+        assert_eq!(versions(&policy.select(&archives, &installed)), ["1.20.14", "1.21.3"]);
+
+        assert!(CachePolicy::from_values(Some("some"), None).is_err());
+    }
+}
@@ -1,32 +1,103 @@
 //! Command line interface definition
+use crate::audit::FailOn;
+use crate::cache::{parse_age, parse_size, CleanFilter};
+use crate::commands::{UninstallOptions, UpgradeOptions};
+use crate::dedup::DuSort;
+use crate::error::GvmError;
+use crate::lockfile::LOCKFILE_NAME;
+use crate::output::OutputFormat;
+use crate::platform::parse_platform;
+use crate::releases::{parse_series, ReleaseFilter, Stability};
+use crate::terminal::{set_color_choice, ColorChoice};
+use crate::ui_flat::{set_verbosity, Verbosity};
+use crate::version::VersionSelector;
 use crate::{commands, config::Config};
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand};
+use log::LevelFilter;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// Tidepool GVM - A high-performance Go Version Manager
 #[derive(Parser, Debug)]
 #[command(author, version, about = "A high-performance Go version management tool")]
 pub struct Cli {
-    /// Verbose mode
-    #[arg(short, long, global = true)]
-    pub verbose: bool,
+    /// Verbose mode; repeat for more detail (-v info, -vv debug, -vvv trace)
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
 
     /// Quiet mode (only output errors)
-    #[arg(short, long, global = true)]
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
     pub quiet: bool,
 
+    /// When to use colors
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
+    /// Write debug logs to a file (for bug reports)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Output format
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
-    /// Install a specific Go version
+    /// Install one or more Go versions
     Install {
-        /// The Go version to install (e.g., 1.21.3)
-        version: String,
+        /// Go versions to install (e.g., 1.21.3, 1.22, latest, tip), or the source to build
+        /// with --from-source
+        #[arg(required = true, num_args = 1..)]
+        versions: Vec<String>,
         /// Force re-installation
         #[arg(short, long)]
         force: bool,
+        /// Build from source: a release version, git ref, source directory or archive
+        #[arg(long)]
+        from_source: bool,
+        /// Version name for a source build (derived from the source by default)
+        #[arg(long, requires = "from_source")]
+        name: Option<String>,
+        /// Installed version to bootstrap source and tip builds with
+        #[arg(long)]
+        bootstrap: Option<String>,
+        /// Branch or commit to build for tip (default: master)
+        #[arg(long, conflicts_with = "from_source")]
+        commit: Option<String>,
+        /// Run the installed toolchain to verify it, rolling back on failure
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Upgrade installed versions to the newest patch release of their series, or tip
+    Upgrade {
+        /// Series to upgrade (e.g. 1.22) or tip; all installed series when omitted
+        version: Option<String>,
+        /// Installed version to bootstrap tip builds with
+        #[arg(long)]
+        bootstrap: Option<String>,
+        /// Run the installed toolchain to verify it, rolling back on failure
+        #[arg(long)]
+        verify: bool,
+        /// Activate the new version if the upgraded one was active
+        #[arg(long)]
+        switch: bool,
+        /// Update .go-version files that pinned the upgraded version
+        #[arg(long)]
+        update_pins: bool,
+        /// Also update pins of the project in this directory (repeatable)
+        #[arg(long = "project", value_name = "DIR")]
+        projects: Vec<PathBuf>,
+        /// Uninstall the upgraded version
+        #[arg(long)]
+        remove_old: bool,
+        /// Show the planned changes without making them
+        #[arg(long)]
+        dry_run: bool,
     },
     /// Switch to a specific Go version
     Use {
@@ -36,16 +107,74 @@ pub enum Commands {
         #[arg(short, long)]
         global: bool,
     },
-    /// Uninstall a specific Go version
+    /// Uninstall Go versions
     Uninstall {
-        /// The Go version to uninstall (e.g., 1.21.3)
-        version: String,
+        /// Versions to uninstall: exact (1.21.3), series (1.20) or ranges ('<1.21', '>=1.19,<1.21')
+        #[arg(
+            required_unless_present = "all_except_current",
+            value_parser = VersionSelector::parse
+        )]
+        versions: Vec<VersionSelector>,
+        /// Uninstall every version except the active one
+        #[arg(long, conflicts_with = "versions")]
+        all_except_current: bool,
+        /// Keep the cached archives of the removed versions
+        #[arg(long, conflicts_with = "purge_cache")]
+        keep_cache: bool,
+        /// Delete the cached archives of the removed versions
+        #[arg(long)]
+        purge_cache: bool,
+        /// Show what would be removed without removing it
+        #[arg(long)]
+        dry_run: bool,
+        /// Remove several versions without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Remove releases superseded by newer patches of the same minor series
+    Prune {
+        /// Newest releases to keep per minor series
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        keep: u64,
+        /// Also keep versions pinned by the project in this directory (repeatable)
+        #[arg(long = "project", value_name = "DIR")]
+        projects: Vec<PathBuf>,
+        /// Show what would be removed without removing it
+        #[arg(long)]
+        dry_run: bool,
+        /// Remove without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
     },
     /// List Go versions
     List {
         /// List all available remote versions
         #[arg(short, long)]
         all: bool,
+        /// Only stable releases
+        #[arg(long, requires = "all", conflicts_with = "unstable")]
+        stable: bool,
+        /// Only release candidates and betas
+        #[arg(long, requires = "all")]
+        unstable: bool,
+        /// Only releases of series older than the supported ones
+        #[arg(long, requires = "all")]
+        archived: bool,
+        /// Only releases of a minor series (e.g. 1.21)
+        #[arg(long, requires = "all", value_parser = parse_series)]
+        series: Option<(u32, u32)>,
+        /// Only the newest release of each series
+        #[arg(long, requires = "all")]
+        latest_per_series: bool,
+        /// Show the archives of this platform, omitting releases without one (e.g. linux/arm64)
+        #[arg(long, requires = "all", value_name = "OS/ARCH", value_parser = parse_platform)]
+        platform: Option<(String, String)>,
+        /// Only installed releases
+        #[arg(long, requires = "all", conflicts_with = "not_installed")]
+        installed: bool,
+        /// Only releases that are not installed
+        #[arg(long, requires = "all")]
+        not_installed: bool,
     },
     /// Show the current Go version status
     Status,
@@ -54,21 +183,395 @@ pub enum Commands {
         /// The Go version to show information for (e.g., 1.21.3)
         version: String,
     },
+    /// List the releases between two versions with their release notes
+    Diff {
+        /// Older version (e.g. 1.21.8)
+        from: String,
+        /// Newer version (e.g. 1.22.3)
+        to: String,
+    },
+    /// Import Go installations made outside of gvm
+    Import {
+        /// Go root directories to import (discovered automatically when omitted)
+        paths: Vec<PathBuf>,
+        /// Copy into the gvm versions directory instead of referencing in place
+        #[arg(long)]
+        copy: bool,
+        /// Version name for a single imported installation
+        #[arg(long)]
+        name: Option<String>,
+        /// Replace an installed version with the same name
+        #[arg(short, long)]
+        force: bool,
+        /// Only list the installations that were found
+        #[arg(long, conflicts_with = "paths")]
+        list: bool,
+    },
+    /// Write the installed versions and their archive checksums to a lockfile
+    Export {
+        /// Lockfile to write
+        #[arg(default_value = LOCKFILE_NAME)]
+        path: PathBuf,
+        /// Only lock the archives of these platforms (e.g. linux/amd64); all when omitted
+        #[arg(long = "platform", value_name = "OS/ARCH", value_parser = parse_platform)]
+        platforms: Vec<(String, String)>,
+    },
+    /// Install the versions of a lockfile, verify their checksums and set the default
+    Sync {
+        /// Lockfile to read
+        #[arg(default_value = LOCKFILE_NAME)]
+        path: PathBuf,
+        /// Uninstall versions that are not in the lockfile
+        #[arg(long)]
+        remove_extras: bool,
+    },
+    /// Diagnose problems with the gvm setup and the shell environment
+    Doctor {
+        /// Fix the problems that can be fixed automatically
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Check versions for end of support and known vulnerabilities
+    Audit {
+        /// Versions to audit (all installed versions when omitted)
+        versions: Vec<String>,
+        /// Go vulnerability database snapshot: an OSV JSON file or URL
+        #[arg(long, value_name = "FILE|URL")]
+        vuln_db: Option<String>,
+        /// Which findings make the command fail
+        #[arg(long, value_enum, default_value_t = FailOn::Any)]
+        fail_on: FailOn,
+    },
+    /// Manage the download cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+    /// Create offline bundles of Go archives and install from them
+    Bundle {
+        #[command(subcommand)]
+        command: BundleCommands,
+    },
+    /// Replace identical files of installed versions with hard links to a shared store
+    Dedup,
+    /// Show the disk usage of the installed versions, the cache and the gvm root
+    Du {
+        /// Order of the versions
+        #[arg(long, value_enum, default_value_t = DuSort::Version)]
+        sort: DuSort,
+    },
+    /// Serve the download cache as a mirror for other gvm instances on the network
+    Serve {
+        /// Address to listen on (e.g. 0.0.0.0:8080 to accept other machines)
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: SocketAddr,
+        /// Download archives that are not cached from the upstream mirror on first request
+        #[arg(long)]
+        pull_through: bool,
+        /// Upstream mirror for --pull-through (defaults to the configured mirror)
+        #[arg(long, value_name = "URL", requires = "pull_through")]
+        upstream: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommands {
+    /// List cached archives with their version, platform, size and age
+    List,
+    /// Show how much space the cache uses
+    Size,
+    /// Verify cached archives against the published checksums
+    Verify {
+        /// Delete corrupt archives
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Delete cached archives (all of them when no filter is given)
+    Clean {
+        /// Versions or minor series (e.g. 1.21) to delete
+        versions: Vec<String>,
+        /// Only delete archives downloaded longer ago than this (e.g. 30d, 12h, 2w)
+        #[arg(long, value_name = "AGE", value_parser = parse_age)]
+        older_than: Option<Duration>,
+        /// Delete the oldest archives until the cache fits this size (e.g. 500M, 2G)
+        #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+        max_size: Option<u64>,
+        /// Only delete archives of versions that are not installed
+        #[arg(long)]
+        not_installed: bool,
+        /// Show what would be deleted without deleting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum BundleCommands {
+    /// Package archives, their checksums and the release index into a bundle
+    Create {
+        /// Bundle file to write (e.g. go-bundle.tar)
+        #[arg(short = 'o', long, value_name = "FILE")]
+        file: PathBuf,
+        /// Versions to bundle (e.g. 1.21.13,1.22.5, a series such as 1.22, or latest)
+        #[arg(long, required = true, value_delimiter = ',')]
+        versions: Vec<String>,
+        /// Platforms to bundle (e.g. linux/amd64,linux/arm64); this platform when omitted
+        #[arg(long, value_delimiter = ',', value_name = "OS/ARCH", value_parser = parse_platform)]
+        platforms: Vec<(String, String)>,
+    },
+    /// Install versions from a bundle without network access
+    Install {
+        /// Bundle file to install from
+        path: PathBuf,
+        /// Versions to install; all bundled versions when omitted
+        versions: Vec<String>,
+        /// Reinstall versions that are already installed
+        #[arg(short, long)]
+        force: bool,
+        /// Run the installed toolchain to verify it, rolling back on failure
+        #[arg(long)]
+        verify: bool,
+    },
 }
 
 impl Cli {
     pub async fn run(&self) -> anyhow::Result<()> {
+        self.init_logging()?;
+        set_verbosity(self.verbosity());
+        set_color_choice(self.color);
         let config = Config::new()?;
 
         match &self.command {
-            Commands::Install { version, force } => {
-                commands::install(version, &config, *force).await
+            Commands::Install { versions, force, from_source, name, bootstrap, commit, verify } => {
+                match versions.as_slice() {
+                    [source] if *from_source => {
+                        commands::install_from_source(
+                            source,
+                            &config,
+                            name.as_deref(),
+                            bootstrap.as_deref(),
+                            *force,
+                            *verify,
+                            self.output,
+                        )
+                        .await
+                    }
+                    [version] if version == crate::go::TIP_PREFIX => {
+                        commands::install_tip(
+                            &config,
+                            commit.as_deref(),
+                            bootstrap.as_deref(),
+                            *force,
+                            *verify,
+                            self.output,
+                        )
+                        .await
+                    }
+                    _ if *from_source => {
+                        Err(GvmError::Usage("--from-source takes exactly one source".into()).into())
+                    }
+                    _ if bootstrap.is_some() || commit.is_some() => Err(GvmError::Usage(
+                        "--bootstrap and --commit only apply to tip and --from-source builds"
+                            .into(),
+                    )
+                    .into()),
+                    _ => commands::install(versions, &config, *force, *verify, self.output).await,
+                }
+            }
+            Commands::Upgrade {
+                version,
+                bootstrap,
+                verify,
+                switch,
+                update_pins,
+                projects,
+                remove_old,
+                dry_run,
+            } => {
+                let options = UpgradeOptions {
+                    bootstrap: bootstrap.clone(),
+                    verify: *verify,
+                    switch: *switch,
+                    update_pins: *update_pins,
+                    projects: projects.clone(),
+                    remove_old: *remove_old,
+                    dry_run: *dry_run,
+                };
+                commands::upgrade(version.as_deref(), &config, &options, self.output).await
+            }
+            Commands::Use { version, global } => {
+                commands::switch(version, &config, *global, false, self.output)
+            }
+            Commands::Uninstall {
+                versions,
+                all_except_current,
+                keep_cache,
+                purge_cache,
+                dry_run,
+                yes,
+            } => {
+                let options = UninstallOptions {
+                    all_except_current: *all_except_current,
+                    purge_cache: match (keep_cache, purge_cache) {
+                        (true, _) => Some(false),
+                        (_, true) => Some(true),
+                        _ => None,
+                    },
+                    dry_run: *dry_run,
+                    yes: *yes,
+                };
+                commands::uninstall(versions, &options, &config, self.output)
+            }
+            Commands::Prune { keep, projects, dry_run, yes } => {
+                let keep = usize::try_from(*keep).unwrap_or(usize::MAX);
+                commands::prune(&config, keep, projects, *dry_run, *yes, self.output)
+            }
+            Commands::List {
+                all,
+                stable,
+                unstable,
+                archived,
+                series,
+                latest_per_series,
+                platform,
+                installed,
+                not_installed,
+            } => {
+                let stability = match (stable, unstable) {
+                    (true, _) => Some(Stability::Stable),
+                    (_, true) => Some(Stability::Unstable),
+                    _ => None,
+                };
+                let installed = match (installed, not_installed) {
+                    (true, _) => Some(true),
+                    (_, true) => Some(false),
+                    _ => None,
+                };
+                let filter = ReleaseFilter {
+                    stability,
+                    archived: *archived,
+                    series: *series,
+                    latest_per_series: *latest_per_series,
+                    platform: platform.clone(),
+                    installed,
+                };
+                commands::list(&config, *all, &filter, self.output).await
             }
-            Commands::Use { version, global } => commands::switch(version, &config, *global, false),
-            Commands::Uninstall { version } => commands::uninstall(version, &config),
-            Commands::List { all } => commands::list(&config, *all),
-            Commands::Status => commands::status(&config),
-            Commands::Info { version } => commands::info(version, &config),
+            Commands::Status => commands::status(&config, self.output),
+            Commands::Info { version } => commands::info(version, &config, self.output).await,
+            Commands::Diff { from, to } => commands::diff(from, to, &config, self.output).await,
+            Commands::Import { paths, copy, name, force, list } => {
+                commands::import(paths, &config, name.as_deref(), *copy, *force, *list, self.output)
+            }
+            Commands::Export { path, platforms } => {
+                commands::export(path, platforms, &config, self.output).await
+            }
+            Commands::Sync { path, remove_extras } => {
+                commands::sync(path, *remove_extras, &config, self.output).await
+            }
+            Commands::Doctor { fix } => commands::doctor(&config, *fix, self.output),
+            Commands::Audit { versions, vuln_db, fail_on } => {
+                commands::audit(versions, &config, vuln_db.as_deref(), *fail_on, self.output).await
+            }
+            Commands::Cache { command } => match command {
+                CacheCommands::List => commands::cache_list(&config, self.output),
+                CacheCommands::Size => commands::cache_size(&config, self.output),
+                CacheCommands::Verify { remove } => {
+                    commands::cache_verify(&config, *remove, self.output).await
+                }
+                CacheCommands::Clean { versions, older_than, max_size, not_installed, dry_run } => {
+                    let filter = CleanFilter {
+                        versions: versions.clone(),
+                        older_than: *older_than,
+                        not_installed: *not_installed,
+                        max_size: *max_size,
+                    };
+                    commands::cache_clean(&config, &filter, *dry_run, self.output)
+                }
+            },
+            Commands::Bundle { command } => match command {
+                BundleCommands::Create { file, versions, platforms } => {
+                    commands::bundle_create(file, versions, platforms, &config, self.output).await
+                }
+                BundleCommands::Install { path, versions, force, verify } => {
+                    commands::bundle_install(path, versions, &config, *force, *verify, self.output)
+                        .await
+                }
+            },
+            Commands::Dedup => commands::dedup(&config, self.output),
+            Commands::Du { sort } => commands::du(&config, *sort, self.output),
+            Commands::Serve { listen, pull_through, upstream } => {
+                let upstream = pull_through.then(|| upstream.as_deref().unwrap_or(&config.mirror));
+                commands::serve(*listen, upstream, &config, self.output).await
+            }
+        }
+    }
+
+    /// Gets the UI verbosity selected by `--quiet` and `--verbose`
+    pub fn verbosity(&self) -> Verbosity {
+        if self.quiet {
+            Verbosity::Quiet
+        } else if self.verbose > 0 {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
         }
     }
+
+    /// Gets the level of gvm's own logs selected by `--verbose` and `--log-file`
+    ///
+    /// `None` leaves the level to `RUST_LOG`.
+    pub fn log_level(&self) -> Option<LevelFilter> {
+        let level = match self.verbose {
+            0 => None,
+            1 => Some(LevelFilter::Info),
+            2 => Some(LevelFilter::Debug),
+            _ => Some(LevelFilter::Trace),
+        };
+        if self.log_file.is_some() {
+            level.max(Some(LevelFilter::Debug))
+        } else {
+            level
+        }
+    }
+
+    /// Initializes logging from `RUST_LOG`, `--verbose` and `--log-file`
+    ///
+    /// Logs go to stderr, or only to the log file when one is given.
+    fn init_logging(&self) -> anyhow::Result<()> {
+        let mut builder = env_logger::Builder::from_default_env();
+        if let Some(level) = self.log_level() {
+            builder.filter_module("tidepool_gvm", level);
+        }
+        if let Some(path) = &self.log_file {
+            let file =
+                std::fs::File::create(path).map_err(GvmError::io("create log file", path))?;
+            builder.target(env_logger::Target::Pipe(Box::new(file)));
+        }
+
+        // Logging may already be initialized when running several commands in one process
+        let _ = builder.try_init();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbosity_flags() {
+        let cli = Cli::try_parse_from(["gvm", "-vv", "status"]).unwrap();
+        assert_eq!(cli.verbosity(), Verbosity::Verbose);
+        assert_eq!(cli.log_level(), Some(LevelFilter::Debug));
+
+        let cli = Cli::try_parse_from(["gvm", "status", "--quiet"]).unwrap();
+        assert_eq!(cli.verbosity(), Verbosity::Quiet);
+        assert_eq!(cli.log_level(), None);
+
+        let cli = Cli::try_parse_from(["gvm", "--log-file", "gvm.log", "status"]).unwrap();
+        assert_eq!(cli.log_level(), Some(LevelFilter::Debug));
+
+        assert!(Cli::try_parse_from(["gvm", "-q", "-v", "status"]).is_err());
+    }
 }
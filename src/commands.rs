@@ -1,39 +1,484 @@
+use crate::audit::{supported_series, FailOn, SupportStatus, VersionAudit, VulnDb};
+use crate::bundle::{self, Bundle, BundleManifest};
+use crate::cache::{
+    check_archive, format_age, ArchiveCheck, Cache, CacheKeep, CachePolicy, CachedArchive,
+    CleanFilter,
+};
 use crate::config::Config;
+use crate::dedup::{disk_usage, DuSort, Store};
 
-use crate::ui_flat::SimpleUI;
+use crate::doctor::{Doctor, DoctorEnv, Severity};
+use crate::downloader::Downloader;
+use crate::error::{ErrorUtils, FailureKind, GvmError};
+use crate::go::{is_tip_version, is_version_dir_name, short_commit, TIP_DEFAULT_REF, TIP_PREFIX};
+use crate::history::{ReleaseHistory, ReleaseKind};
+use crate::lockfile::{LockedArchive, LockedVersion, Lockfile};
+use crate::manifest::{BuildInfo, InstallManifest, InstallMethod};
+use crate::output::{
+    AuditReport, BundleCreateReport, BundleInstallReport, CacheCleanReport, CacheListReport,
+    CacheSizeReport, CacheVerifyReport, DedupReport, DiffReport, DiskUsageReport, DoctorReport,
+    ErrorInfo, ExportReport, FailedInstall, ImportReport, InfoReport, InstallReport, KeptVersion,
+    ListedRelease, OutputFormat, PruneReport, PrunedVersion, ReleaseDetails, ReleaseListReport,
+    ReleaseSummary, SeriesUpgrade, SkippedImport, SwitchReport, SyncReport, UninstallReport,
+    UpgradeReport, VerifiedArchive, VersionDiskUsage,
+};
+use crate::platform::PlatformInfo;
+use crate::project::{find_pins, write_go_version_file, Pin, GO_VERSION_FILE};
+use crate::releases::{ReleaseFilter, ReleaseIndex};
+use crate::reporter::{MultiProgressReporter, Reporter};
+use crate::serve::Server;
+use crate::symlink::is_symlink;
+use crate::ui_flat::{format_size, SimpleUI};
+use crate::version::{superseded, GoVersion, VersionSelector};
 use crate::{
-    GoManager, ListInstalledRequest, Result, StatusRequest, SwitchRequest, UninstallRequest,
+    GoManager, GoVersionInfo, ImportRequest, InstallRequest, ListInstalledRequest, Result,
+    SourceInstallRequest, StatusRequest, SwitchRequest, UninstallRequest,
 };
+use anyhow::Context;
+use log::debug;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 
-/// Install a Go version using simplified installation system.
+/// Connections shared by all downloads of a batch install
+const BATCH_CONNECTION_BUDGET: usize = 8;
+
+/// Install one or more Go versions using simplified installation system.
+///
+/// Versions may be exact ("1.21.3"), a minor series ("1.21") or "latest". Several versions
+/// are downloaded and extracted concurrently.
 ///
 /// # Errors
-/// Returns an error if the installation fails, network issues occur, or file system operations fail.
-pub async fn install(version: &str, config: &Config, force: bool) -> Result<()> {
-    // The actual installation logic (requires network download)
-    let manager = GoManager::new();
-    let install_request = crate::InstallRequest {
+/// Returns an error if any installation fails, network issues occur, or file system operations fail.
+pub async fn install(
+    versions: &[String],
+    config: &Config,
+    force: bool,
+    verify: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
+
+    let resolved = manager
+        .resolve_versions(versions, &config.mirror, config.cache())
+        .await
+        .context("Failed to resolve Go versions")?;
+    for (spec, version) in versions.iter().zip(&resolved) {
+        if spec != version && versions.len() == resolved.len() {
+            ui.info(&format!("Resolved {spec} to Go {version}"));
+        }
+    }
+
+    match resolved.as_slice() {
+        [version] => install_one(version, config, force, verify, output).await,
+        _ => install_batch(resolved, config, force, verify, output).await,
+    }
+}
+
+/// Builds an install request from the configuration
+fn install_request(version: &str, config: &Config, force: bool, verify: bool) -> InstallRequest {
+    InstallRequest {
         version: version.to_string(),
         install_dir: config.versions().clone(),
         download_dir: config.cache().clone(),
         force,
+        verify,
+        mirror: config.mirror.clone(),
+        bundle: None,
+    }
+}
+
+/// Install a single Go version with a progress bar.
+async fn install_one(
+    version: &str,
+    config: &Config,
+    force: bool,
+    verify: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    // The actual installation logic (requires network download)
+    let manager = output.manager();
+    let install_request = install_request(version, config, force, verify);
+
+    let version_info = manager
+        .install_with(install_request, &Downloader::new())
+        .await
+        .with_context(|| format!("Failed to install Go {version}"))?;
+
+    let ui = output.ui();
+    ui.success(&format!("Go {} installed successfully", version_info.version));
+    if let Some(install_path) = &version_info.install_path {
+        ui.info(&format!("Installation path: {}", install_path.display()));
+    }
+    after_install(&ui, config);
+    ui.hint(&format!("Use 'gvm use {version}' to activate this version"));
+    output.emit("install", &InstallReport { installed: vec![version_info], failed: Vec::new() })
+}
+
+/// Install several Go versions concurrently under a shared connection budget.
+async fn install_batch(
+    versions: Vec<String>,
+    config: &Config,
+    force: bool,
+    verify: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    ui.info(&format!("Installing {} Go versions: {}", versions.len(), versions.join(", ")));
+
+    // Structured output must not be interleaved with a progress display
+    let progress =
+        (!output.is_structured()).then(|| Arc::new(MultiProgressReporter::new(&versions)));
+    let budget = Arc::new(Semaphore::new(BATCH_CONNECTION_BUDGET));
+
+    let tasks = versions.iter().map(|version| {
+        let request = install_request(version, config, force, verify);
+        let manager = match &progress {
+            Some(progress) => GoManager::with_reporter(progress.clone() as Arc<dyn Reporter>),
+            None => output.manager(),
+        };
+        let progress = progress.clone();
+        let downloader = Downloader::new().with_budget(budget.clone());
+
+        tokio::spawn(async move {
+            let version = request.version.clone();
+            let result = manager.install_with(request, &downloader).await;
+            if let (Some(progress), Err(_)) = (&progress, &result) {
+                progress.set(&version, "failed");
+            }
+            result
+        })
+    });
+    let results = futures::future::join_all(tasks).await;
+
+    let mut report = InstallReport::default();
+    for (version, result) in versions.iter().zip(results) {
+        match result.map_err(anyhow::Error::from).and_then(|result| result) {
+            Ok(version_info) => {
+                ui.success(&format!("Go {} installed", version_info.version));
+                report.installed.push(version_info);
+            }
+            Err(e) => {
+                ui.error(&format!("Go {version} failed: {}", ErrorUtils::message(&e)));
+                report
+                    .failed
+                    .push(FailedInstall { version: version.clone(), error: ErrorInfo::new(&e) });
+            }
+        }
+    }
+    if !report.installed.is_empty() {
+        after_install(&ui, config);
+    }
+    output.emit("install", &report)?;
+
+    if let Some(first) = report.failed.first() {
+        // Report the common cause when all installations failed the same way
+        let kind = first.error.kind;
+        let kind = if report.failed.iter().all(|f| f.error.kind == kind) {
+            kind
+        } else {
+            FailureKind::General
+        };
+        return Err(GvmError::BatchFailed {
+            failed: report.failed.len(),
+            total: versions.len(),
+            kind,
+        }
+        .into());
+    }
+    ui.hint("Use 'gvm use <version>' to activate a version");
+    Ok(())
+}
+
+/// Build and install a Go version from source.
+///
+/// # Errors
+/// Returns an error if fetching the source, bootstrapping or building fails.
+pub async fn install_from_source(
+    source: &str,
+    config: &Config,
+    name: Option<&str>,
+    bootstrap: Option<&str>,
+    force: bool,
+    verify: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
+    let request = SourceInstallRequest {
+        source: source.to_string(),
+        name: name.map(String::from),
+        bootstrap: bootstrap.map(String::from),
+        git_remote: config.source_repo.clone(),
+        install_dir: config.versions().clone(),
+        download_dir: config.cache().clone(),
+        force,
+        verify,
+        mirror: config.mirror.clone(),
     };
 
-    match manager.install(install_request).await {
-        Ok(version_info) => {
-            let ui = SimpleUI::new();
-            ui.success(&format!("Go {} installed successfully", version_info.version));
-            if let Some(install_path) = &version_info.install_path {
-                ui.info(&format!("Installation path: {}", install_path.display()));
+    ui.info(&format!("Building Go from source: {source}"));
+
+    let version_info = manager
+        .install_from_source(request)
+        .await
+        .with_context(|| format!("Failed to build Go from {source}"))?;
+
+    ui.success(&format!("Go {} built and installed successfully", version_info.version));
+    if let Some(install_path) = &version_info.install_path {
+        ui.info(&format!("Installation path: {}", install_path.display()));
+    }
+    after_install(&ui, config);
+    ui.hint(&format!("Use 'gvm use {}' to activate this version", version_info.version));
+    output.emit("install", &InstallReport { installed: vec![version_info], failed: Vec::new() })
+}
+
+/// Build and install a development snapshot (tip) of Go.
+///
+/// # Errors
+/// Returns an error if fetching the source, bootstrapping or building fails.
+pub async fn install_tip(
+    config: &Config,
+    reference: Option<&str>,
+    bootstrap: Option<&str>,
+    force: bool,
+    verify: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
+    let request = tip_request(config, reference, bootstrap, force, verify);
+
+    ui.info(&format!("Building Go tip from {} ({})", request.git_remote, request.source));
+
+    let version_info = manager.install_tip(request).await.context("Failed to build Go tip")?;
+
+    ui.success(&format!("Go {} built and installed successfully", version_info.version));
+    prune_tip_builds(&ui, &manager, config, &version_info.version);
+    after_install(&ui, config);
+    ui.hint(&format!("Use 'gvm use {}' to activate this version", version_info.version));
+    output.emit("install", &InstallReport { installed: vec![version_info], failed: Vec::new() })
+}
+
+/// Options of `gvm upgrade`
+#[derive(Debug, Clone, Default)]
+pub struct UpgradeOptions {
+    /// Installed version to bootstrap tip builds with
+    pub bootstrap: Option<String>,
+    /// Run the installed toolchain to verify it, rolling back on failure
+    pub verify: bool,
+    /// Activate the new version when the upgraded one was active
+    pub switch: bool,
+    /// Update `.go-version` files that pinned the upgraded version
+    pub update_pins: bool,
+    /// Project directories searched for pins besides the current directory and its parents
+    pub projects: Vec<PathBuf>,
+    /// Uninstall the upgraded version
+    pub remove_old: bool,
+    /// Only show the planned changes
+    pub dry_run: bool,
+}
+
+/// Upgrade installed versions to the newest release of their minor series.
+///
+/// Without a version, every installed series is upgraded; a series ("1.22") or a version
+/// ("1.22.4") limits the upgrade to that series. `tip` rebuilds the newest commit and, if
+/// a tip build was active, activates the new build in its place.
+///
+/// # Errors
+/// Returns an error if the version cannot be upgraded, the release index cannot be loaded
+/// or an installation fails.
+pub async fn upgrade(
+    version: Option<&str>,
+    config: &Config,
+    options: &UpgradeOptions,
+    output: OutputFormat,
+) -> Result<()> {
+    if version == Some(TIP_PREFIX) {
+        return upgrade_tip(config, options, output).await;
+    }
+
+    let ui = output.ui();
+    let manager = output.manager();
+    let installed = list_installed(config)?;
+    let series = match version {
+        Some(spec) => match GoVersion::parse(spec) {
+            Some(version) => Some(version.series()),
+            None if installed.iter().any(|name| name == spec) => {
+                return Err(GvmError::NotUpgradable(spec.to_string()).into())
             }
-            ui.hint(&format!("Use 'gvm use {version}' to activate this version"));
-            Ok(())
+            None => return Err(GvmError::InvalidVersion(spec.to_string()).into()),
+        },
+        None => None,
+    };
+    if let (Some(spec), Some(series)) = (version, series) {
+        let installed_in_series = installed
+            .iter()
+            .filter_map(|name| GoVersion::parse(name))
+            .any(|version| version.series() == series);
+        if !installed_in_series {
+            return Err(GvmError::VersionNotInstalled(spec.to_string()).into());
         }
-        Err(e) => {
-            let ui = SimpleUI::new();
-            ui.error(&format!("Failed to install Go {version}: {e}"));
-            Err(e)
+    }
+
+    let index = ReleaseIndex::load(&config.mirror, config.cache())
+        .await
+        .context("Failed to load the release index")?;
+    let current = manager.get_current_version(config.versions());
+    let cwd = std::env::current_dir().context("Failed to get the current directory")?;
+    let pins: Vec<Pin> = std::iter::once(cwd.as_path())
+        .chain(options.projects.iter().map(PathBuf::as_path))
+        .flat_map(find_pins)
+        .filter(|pin| pin.file.file_name().is_some_and(|name| name == GO_VERSION_FILE))
+        .collect();
+
+    let mut report = UpgradeReport { dry_run: options.dry_run, ..UpgradeReport::default() };
+    for upgrade in index.upgrades(&installed, series) {
+        let is_current = current.as_deref() == Some(upgrade.from.as_str());
+        let pinned: Vec<PathBuf> = pins
+            .iter()
+            .filter(|pin| pin.version == upgrade.from)
+            .map(|pin| pin.file.clone())
+            .collect();
+        report.upgrades.push(SeriesUpgrade {
+            switched: options.switch && is_current,
+            updated_pins: if options.update_pins { pinned } else { Vec::new() },
+            removed: options.remove_old && (!is_current || options.switch),
+            from: upgrade.from,
+            to: upgrade.to,
+        });
+    }
+
+    if report.upgrades.is_empty() {
+        ui.success("All installed versions are up to date");
+        return output.emit("upgrade", &report);
+    }
+    for upgrade in &report.upgrades {
+        ui.list_item(&format!("{} -> {}", upgrade.from, upgrade.to), false);
+        if upgrade.switched {
+            ui.info(&format!("  activate Go {}", upgrade.to));
+        }
+        for file in &upgrade.updated_pins {
+            ui.info(&format!("  update {}", file.display()));
+        }
+        if upgrade.removed {
+            ui.info(&format!("  uninstall Go {}", upgrade.from));
+        } else if options.remove_old {
+            ui.warning(&format!(
+                "Go {} is active and is kept; pass --switch to replace it",
+                upgrade.from
+            ));
+        }
+    }
+    if options.dry_run {
+        ui.info(&format!("{} versions would be upgraded", report.upgrades.len()));
+        return output.emit("upgrade", &report);
+    }
+
+    for upgrade in &report.upgrades {
+        let request = install_request(&upgrade.to, config, false, options.verify);
+        let version_info = manager
+            .install_with(request, &Downloader::new())
+            .await
+            .with_context(|| format!("Failed to install Go {}", upgrade.to))?;
+        ui.success(&format!("Go {} upgraded to {}", upgrade.from, upgrade.to));
+        report.installed.push(version_info);
+
+        if upgrade.switched {
+            manager.switch_version(&upgrade.to, config.versions())?;
+            ui.info(&format!("Switched to Go {}", upgrade.to));
+        }
+        for file in &upgrade.updated_pins {
+            write_go_version_file(file, &upgrade.to).map_err(GvmError::io("update", file))?;
+            ui.info(&format!("Updated {}", file.display()));
+        }
+        if upgrade.removed {
+            manager.uninstall(UninstallRequest {
+                version: upgrade.from.clone(),
+                base_dir: config.versions().clone(),
+            })?;
+            ui.info(&format!("Removed Go {}", upgrade.from));
+        }
+    }
+    prune_store(&ui, config);
+
+    after_install(&ui, config);
+    output.emit("upgrade", &report)
+}
+
+/// Rebuild tip at the newest commit.
+async fn upgrade_tip(
+    config: &Config,
+    options: &UpgradeOptions,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
+
+    let request = tip_request(config, None, options.bootstrap.as_deref(), false, options.verify);
+    if let Some(name) = manager.installed_tip(&request)? {
+        ui.success(&format!("Go tip is already up to date ({name})"));
+        return output.emit("upgrade", &UpgradeReport::default());
+    }
+    if options.dry_run {
+        ui.info(&format!("Would build Go tip from {} ({})", request.git_remote, request.source));
+        return output
+            .emit("upgrade", &UpgradeReport { dry_run: true, ..UpgradeReport::default() });
+    }
+
+    ui.info(&format!("Building Go tip from {} ({})", request.git_remote, request.source));
+
+    let version_info = manager.install_tip(request).await.context("Failed to upgrade Go tip")?;
+    ui.success(&format!("Go tip upgraded to {}", version_info.version));
+
+    let current = manager.get_current_version(config.versions());
+    if current.as_deref().is_some_and(is_tip_version) {
+        manager.switch_version(&version_info.version, config.versions())?;
+        ui.info(&format!("Switched to Go {}", version_info.version));
+    }
+
+    prune_tip_builds(&ui, &manager, config, &version_info.version);
+    after_install(&ui, config);
+    output.emit(
+        "upgrade",
+        &UpgradeReport { installed: vec![version_info], ..UpgradeReport::default() },
+    )
+}
+
+/// Builds a tip source request from the configuration
+fn tip_request(
+    config: &Config,
+    reference: Option<&str>,
+    bootstrap: Option<&str>,
+    force: bool,
+    verify: bool,
+) -> SourceInstallRequest {
+    SourceInstallRequest {
+        source: reference.unwrap_or(TIP_DEFAULT_REF).to_string(),
+        name: None,
+        bootstrap: bootstrap.map(String::from),
+        git_remote: config.source_repo.clone(),
+        install_dir: config.versions().clone(),
+        download_dir: config.cache().clone(),
+        force,
+        verify,
+        mirror: config.mirror.clone(),
+    }
+}
+
+/// Removes tip builds superseded by `keep`, reporting what was removed
+fn prune_tip_builds(ui: &SimpleUI, manager: &GoManager, config: &Config, keep: &str) {
+    match manager.prune_tip_builds(config.versions(), keep) {
+        Ok(removed) => {
+            for version in removed {
+                ui.info(&format!("Removed old tip build {version}"));
+            }
+            prune_store(ui, config);
         }
+        Err(e) => ui.warning(&format!("Failed to remove old tip builds: {e}")),
     }
 }
 
@@ -48,7 +493,7 @@ pub fn list_installed(config: &Config) -> Result<Vec<String>> {
     if let Ok(entries) = std::fs::read_dir(base_dir) {
         for entry in entries.flatten() {
             if let Some(name) = entry.file_name().to_str() {
-                if entry.path().is_dir() && !name.starts_with('.') && name != "current" {
+                if entry.path().is_dir() && is_version_dir_name(name) {
                     versions.push(name.to_string());
                 }
             }
@@ -59,103 +504,296 @@ pub fn list_installed(config: &Config) -> Result<Vec<String>> {
     Ok(versions)
 }
 
-/// Uninstall a Go version.
+/// Options of `gvm uninstall`
+#[derive(Debug, Clone, Default)]
+pub struct UninstallOptions {
+    /// Select every installed version except the active one
+    pub all_except_current: bool,
+    /// Delete the cached archives of the removed versions; `None` follows `GVM_CACHE_KEEP`
+    pub purge_cache: Option<bool>,
+    /// Only show what would be removed
+    pub dry_run: bool,
+    /// Remove several versions without asking for confirmation
+    pub yes: bool,
+}
+
+/// Uninstall Go versions.
+///
+/// Exact names must be installed and not active. Series, ranges and `--all-except-current`
+/// skip the active version. Removing more than one version asks for confirmation.
 ///
 /// # Errors
-/// Returns an error if the uninstallation fails or file system operations fail.
-pub fn uninstall(version: &str, config: &Config) -> Result<()> {
-    let ui = SimpleUI::new();
-    let manager = GoManager::new();
+/// Returns an error if an exact version is not installed or active, confirmation is needed
+/// but cannot be asked, or removal fails.
+pub fn uninstall(
+    selectors: &[VersionSelector],
+    options: &UninstallOptions,
+    config: &Config,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
     let base_dir = config.versions();
+    let current = manager.get_current_version(base_dir);
+    let mut installed = list_installed(config)?;
+    installed.sort_by(|a, b| crate::version::compare_versions(a, b));
+
+    let mut report = UninstallReport { dry_run: options.dry_run, ..UninstallReport::default() };
+    let mut selected: Vec<String> = Vec::new();
+    for selector in selectors {
+        if let VersionSelector::Exact(name) = selector {
+            // External versions whose target is gone are still registered
+            if !installed.contains(name) && !is_symlink(&base_dir.join(name)) {
+                return Err(GvmError::VersionNotInstalled(name.clone()).into());
+            }
+            if current.as_ref() == Some(name) {
+                return Err(GvmError::VersionActive(name.clone()).into());
+            }
+        }
+        let matching: Vec<&String> = match selector {
+            VersionSelector::Exact(name) => vec![name],
+            _ => installed.iter().filter(|name| selector.matches(name)).collect(),
+        };
+        if matching.is_empty() {
+            ui.warning(&format!("No installed version matches '{selector}'"));
+        }
+        selected.extend(matching.into_iter().cloned());
+    }
+    if options.all_except_current {
+        selected.extend(installed.iter().cloned());
+    }
+    let mut seen = HashSet::new();
+    selected.retain(|version| seen.insert(version.clone()));
+    if let Some(current) = current.as_ref().filter(|current| selected.contains(current)) {
+        ui.info(&format!("Keeping Go {current} (active)"));
+        selected.retain(|version| version != current);
+        report.kept.push(KeptVersion { version: current.clone(), reason: "active".to_string() });
+    }
 
-    ui.info(&format!("Uninstalling Go {version}"));
+    if selected.is_empty() {
+        ui.success("No versions to uninstall");
+        return output.emit("uninstall", &report);
+    }
+
+    let purge_cache =
+        options.purge_cache.unwrap_or(config.cache_policy.keep == CacheKeep::Installed);
+    let cache = Cache::new(config.cache());
+    let archives: Vec<CachedArchive> = if purge_cache {
+        cache
+            .archives()?
+            .into_iter()
+            .filter(|archive| archive.version.as_ref().is_some_and(|v| selected.contains(v)))
+            .collect()
+    } else {
+        Vec::new()
+    };
 
-    let uninstall_request =
-        UninstallRequest { version: version.to_string(), base_dir: base_dir.clone() };
+    // With deduplication, only files no other version links to are freed
+    let stored = Store::new(&config.store_path).stored_files();
+    for version in &selected {
+        let size = stored.version_usage(&base_dir.join(version)).on_disk;
+        ui.list_item(&format!("{version} ({})", format_size(size)), false);
+        report.reclaimed += size;
+    }
+    for archive in &archives {
+        ui.list_item(&format!("{} ({})", archive.filename, format_size(archive.size)), false);
+        report.reclaimed += archive.size;
+    }
+    report.uninstalled = selected.clone();
+    report.purged_archives = archives.iter().map(|a| a.filename.clone()).collect();
 
-    match manager.uninstall(uninstall_request) {
-        Ok(()) => {
-            ui.success(&format!("Go {version} has been successfully uninstalled"));
+    let summary = match archives.len() {
+        0 => format!("{} versions, {}", selected.len(), format_size(report.reclaimed)),
+        count => format!(
+            "{} versions and {count} cached archives, {}",
+            selected.len(),
+            format_size(report.reclaimed)
+        ),
+    };
+    if options.dry_run {
+        ui.info(&format!("Would remove {summary}"));
+        return output.emit("uninstall", &report);
+    }
+    if selected.len() > 1 && !options.yes {
+        match ui.confirm(&format!("Remove {summary}?")) {
+            Some(true) => {}
+            Some(false) => {
+                ui.info("Cancelled");
+                let report = UninstallReport { kept: report.kept, ..UninstallReport::default() };
+                return output.emit("uninstall", &report);
+            }
+            None => return Err(GvmError::ConfirmationRequired.into()),
         }
-        Err(e) => {
-            if e.to_string().contains("not installed") {
-                ui.warning(&format!("Go {version} is not installed"));
-            } else if e.to_string().contains("currently active") {
-                ui.error(&format!("Cannot uninstall Go {version} - it is currently in use"));
-                ui.hint("Please switch to another version or remove the symbolic link manually");
-            } else {
-                ui.error(&format!("Failed to uninstall Go {version}: {e}"));
+    }
+
+    for version in &selected {
+        manager
+            .uninstall(UninstallRequest { version: version.clone(), base_dir: base_dir.clone() })?;
+        ui.info(&format!("Removed Go {version}"));
+    }
+    for archive in &archives {
+        cache.remove(archive)?;
+    }
+    prune_store(&ui, config);
+    ui.success(&format!("Removed {summary}"));
+    output.emit("uninstall", &report)
+}
+
+/// Remove releases superseded by newer patches of the same minor series.
+///
+/// The active version and versions pinned by `.go-version` or `go.mod` files in the
+/// current directory, its parents or the given project directories are always kept.
+///
+/// # Errors
+/// Returns an error if confirmation is needed but cannot be asked, or removal fails.
+pub fn prune(
+    config: &Config,
+    keep: usize,
+    projects: &[PathBuf],
+    dry_run: bool,
+    yes: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
+    let base_dir = config.versions();
+    let current = manager.get_current_version(base_dir);
+    let cwd = std::env::current_dir().context("Failed to get the current directory")?;
+    let pins: Vec<Pin> = std::iter::once(cwd.as_path())
+        .chain(projects.iter().map(PathBuf::as_path))
+        .flat_map(find_pins)
+        .collect();
+
+    let mut report = PruneReport { dry_run, ..PruneReport::default() };
+    // With deduplication, only files no other version links to are freed
+    let stored = Store::new(&config.store_path).stored_files();
+    for version in superseded(&list_installed(config)?, keep) {
+        let reason = if current.as_deref() == Some(version.as_str()) {
+            Some("active".to_string())
+        } else {
+            pins.iter()
+                .find(|pin| pin.version == version)
+                .map(|pin| format!("pinned by {}", pin.file.display()))
+        };
+        match reason {
+            Some(reason) => {
+                ui.info(&format!("Keeping Go {version} ({reason})"));
+                report.kept.push(KeptVersion { version, reason });
+            }
+            None => {
+                let size = stored.version_usage(&base_dir.join(&version)).on_disk;
+                report.reclaimed += size;
+                report.removed.push(PrunedVersion { version, size });
             }
         }
     }
 
-    Ok(())
+    if report.removed.is_empty() {
+        ui.success("No superseded versions to remove");
+        return output.emit("prune", &report);
+    }
+    for pruned in &report.removed {
+        ui.list_item(&format!("{} ({})", pruned.version, format_size(pruned.size)), false);
+    }
+    let summary = format!("{} versions, {}", report.removed.len(), format_size(report.reclaimed));
+    if dry_run {
+        ui.info(&format!("Would remove {summary}"));
+        return output.emit("prune", &report);
+    }
+    if !yes {
+        match ui.confirm(&format!("Remove {summary}?")) {
+            Some(true) => {}
+            Some(false) => {
+                ui.info("Cancelled");
+                let report = PruneReport { kept: report.kept, ..PruneReport::default() };
+                return output.emit("prune", &report);
+            }
+            None => return Err(GvmError::ConfirmationRequired.into()),
+        }
+    }
+
+    for pruned in &report.removed {
+        manager.uninstall(UninstallRequest {
+            version: pruned.version.clone(),
+            base_dir: base_dir.clone(),
+        })?;
+        ui.info(&format!("Removed Go {}", pruned.version));
+    }
+    prune_store(&ui, config);
+    ui.success(&format!("Removed {summary}"));
+    output.emit("prune", &report)
 }
 
 /// Switch to a specific Go version.
 ///
 /// # Errors
 /// Returns an error if the switch operation fails.
-pub fn switch(version: &str, config: &Config, global: bool, force: bool) -> Result<()> {
-    let ui = SimpleUI::new();
-    let manager = GoManager::new();
+pub fn switch(
+    version: &str,
+    config: &Config,
+    global: bool,
+    force: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
     let base_dir = config.versions();
 
     let switch_request =
         SwitchRequest { version: version.to_string(), base_dir: base_dir.clone(), global, force };
 
-    match manager.switch_to(switch_request) {
-        Ok(_) => {
-            ui.success(&format!(
-                "Switched to Go {} {}",
-                version,
-                if global { "(global)" } else { "(local)" }
-            ));
-        }
-        Err(e) => {
-            ui.error(&format!("Failed to switch to Go {version}: {e}"));
-            ui.hint("Ensure the version is installed and you have the correct permissions");
-        }
-    }
-
-    Ok(())
+    manager
+        .switch_to(switch_request)
+        .with_context(|| format!("Failed to switch to Go {version}"))?;
+    ui.success(&format!(
+        "Switched to Go {} {}",
+        version,
+        if global { "(global)" } else { "(local)" }
+    ));
+    output.emit("use", &SwitchReport { version: version.to_string(), global })
 }
 
 /// Show the current Go version status.
 ///
 /// # Errors
 /// Returns an error if the status operation fails.
-pub fn status(config: &Config) -> Result<()> {
-    let ui = SimpleUI::new();
-    let manager = GoManager::new();
+pub fn status(config: &Config, output: OutputFormat) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
     let base_dir = config.versions();
 
     let status_request = StatusRequest { base_dir: Some(base_dir.clone()) };
 
-    match manager.status(status_request) {
-        Ok(status) => {
-            // Simplified output, showing only the most important information
-            if let Some(current_version) = status.current_version {
-                ui.success(&format!("Current version: Go {current_version}"));
-
-                // Show only GOROOT, not the full PATH
-                if let Some(goroot) = status.environment_vars.get("GOROOT") {
-                    ui.key_value("Installation path", goroot);
-                }
+    let mut status = manager.status(status_request).context("Failed to get status")?;
+    let (index, db) = offline_audit_sources(config);
+    if index.is_some() || db.is_some() {
+        status.audit = status
+            .current_version
+            .as_deref()
+            .map(|version| VersionAudit::new(version, index.as_ref(), db.as_ref()));
+    }
+    output.emit("status", &status)?;
 
-                // Show simplified status information
-                ui.info("Go environment is configured");
-                ui.hint("Use 'go version' to verify the installation");
-            } else {
-                ui.warning("No active Go version found");
-                ui.hint("Use 'gvm list' to see installed versions");
-                ui.hint("Use 'gvm use <version>' to activate a version");
-            }
+    // Simplified output, showing only the most important information
+    if let Some(current_version) = status.current_version {
+        ui.success(&format!("Current version: Go {current_version}"));
+        if let Some(problems) = status.audit.as_ref().and_then(VersionAudit::problems) {
+            ui.warning(&format!("Go {current_version}: {problems}"));
+            ui.hint("Use 'gvm audit' for details");
         }
-        Err(e) => {
-            ui.error(&format!("Failed to get status: {e}"));
-            ui.hint("Please check if Go is installed correctly");
+
+        // Show only GOROOT, not the full PATH
+        if let Some(goroot) = status.environment_vars.get("GOROOT") {
+            ui.key_value("Installation path", goroot);
         }
+
+        // Show simplified status information
+        ui.info("Go environment is configured");
+        ui.hint("Use 'go version' to verify the installation");
+    } else {
+        ui.warning("No active Go version found");
+        ui.hint("Use 'gvm list' to see installed versions");
+        ui.hint("Use 'gvm use <version>' to activate a version");
     }
 
     Ok(())
@@ -163,105 +801,1323 @@ pub fn status(config: &Config) -> Result<()> {
 
 /// List Go versions, either installed or available online.
 ///
+/// Available versions come from the release index and are narrowed down by `filter`.
+///
 /// # Errors
 /// Returns an error if the listing operation fails.
-pub fn list(config: &Config, all: bool) -> Result<()> {
+pub async fn list(
+    config: &Config,
+    all: bool,
+    filter: &ReleaseFilter,
+    output: OutputFormat,
+) -> Result<()> {
     if all {
-        list_available_versions()
+        list_available_versions(config, filter, output).await
     } else {
-        list_installed_versions(config)
+        list_installed_versions(config, output)
     }
 }
 
-/// List installed Go versions.
-fn list_installed_versions(config: &Config) -> Result<()> {
-    let ui = SimpleUI::new();
-    let manager = GoManager::new();
-    let base_dir = config.versions();
+/// Import Go installations made outside of gvm.
+///
+/// Without paths, installations are discovered in the usual places (official installer,
+/// `~/sdk`, Homebrew, distribution packages and PATH) and all that gvm does not manage yet
+/// are imported. With `list`, discovered installations are only shown.
+///
+/// # Errors
+/// Returns an error if a given installation cannot be imported.
+pub fn import(
+    paths: &[PathBuf],
+    config: &Config,
+    name: Option<&str>,
+    copy: bool,
+    force: bool,
+    list: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
+    if name.is_some() && paths.len() != 1 {
+        return Err(GvmError::Usage("--name requires exactly one path".into()).into());
+    }
 
-    let list_request = ListInstalledRequest { base_dir: base_dir.clone() };
+    let request = |source: &Path| ImportRequest {
+        source: source.to_path_buf(),
+        name: name.map(String::from),
+        install_dir: config.versions().clone(),
+        copy,
+        force,
+    };
+    let mut report = ImportReport::default();
+
+    if !paths.is_empty() {
+        for path in paths {
+            let version_info = manager
+                .import(request(path))
+                .with_context(|| format!("Failed to import {}", path.display()))?;
+            ui.success(&format!("Imported Go {} from {}", version_info.version, path.display()));
+            report.imported.push(version_info);
+        }
+    } else {
+        let managed = imported_sources(&manager, config)?;
+        report.discovered = crate::import::discover(config.versions());
+        if report.discovered.is_empty() {
+            ui.info("No Go installations found outside of gvm");
+        }
 
-    match manager.list_installed(list_request) {
-        Ok(list) => {
-            if list.versions.is_empty() {
-                ui.warning("No installed Go versions found");
-                ui.hint("Use 'gvm install <version>' to install a new version");
+        for install in &report.discovered {
+            let label = format!(
+                "{} in {} ({})",
+                install.go_version,
+                install.path.display(),
+                install.origin
+            );
+            if list {
+                ui.list_item(&label, false);
+            } else if managed.contains(&install.path) {
+                ui.info(&format!("Already imported: {label}"));
+                report.skipped.push(SkippedImport {
+                    path: install.path.clone(),
+                    reason: "already imported".to_string(),
+                });
             } else {
-                // List versions directly without a title
-                for version in &list.versions {
-                    ui.list_item(&version.version, version.is_current);
-                }
-                // Show total count only if there are multiple versions
-                if list.versions.len() > 1 {
-                    ui.info(&format!("Total: {} versions", list.versions.len()));
+                match manager.import(request(&install.path)) {
+                    Ok(version_info) => {
+                        ui.success(&format!("Imported Go {} from {label}", version_info.version));
+                        report.imported.push(version_info);
+                    }
+                    Err(e) => {
+                        let reason = ErrorUtils::message(&e);
+                        ui.warning(&format!("Skipped {label}: {reason}"));
+                        report.skipped.push(SkippedImport { path: install.path.clone(), reason });
+                    }
                 }
             }
         }
-        Err(e) => {
-            ui.error(&format!("Failed to list versions: {e}"));
+        if list && !report.discovered.is_empty() {
+            ui.hint("Use 'gvm import' to import them, or 'gvm import <path>' for one");
         }
     }
 
-    Ok(())
+    if !report.imported.is_empty() {
+        ui.hint("Use 'gvm use <version>' to activate an imported version");
+    }
+    output.emit("import", &report)
 }
 
-/// List available Go versions from remote.
-fn list_available_versions() -> Result<()> {
-    let ui = SimpleUI::new();
-    let manager = GoManager::new();
+/// Collects the Go roots that installed versions were imported from or link to
+fn imported_sources(manager: &GoManager, config: &Config) -> Result<HashSet<PathBuf>> {
+    let list = manager
+        .list_installed(ListInstalledRequest { base_dir: config.versions().clone() })
+        .context("Failed to list versions")?;
+    Ok(list
+        .versions
+        .iter()
+        .filter_map(|version| version.install_path.as_deref())
+        .flat_map(|path| {
+            let manifest = InstallManifest::read(path).ok().flatten();
+            [manifest.and_then(|m| m.imported_from), path.canonicalize().ok()]
+        })
+        .flatten()
+        .collect())
+}
 
-    match manager.list_available() {
-        Ok(list) => {
-            if list.versions.is_empty() {
-                ui.warning("No available Go versions found");
-            } else {
-                ui.section("Available Go Versions");
-                for version in &list.versions {
-                    ui.list_item(&version.version, version.is_current);
-                }
-                ui.newline();
-                ui.info(&format!("Total: {} versions", list.total_count));
+/// Write the installed releases with their archive checksums to a lockfile.
+///
+/// Archives of all platforms are locked unless `platforms` is given, and the active
+/// version becomes the default. Installed versions that are not releases, such as tip
+/// and source builds, are left out.
+///
+/// # Errors
+/// Returns an error if the release index cannot be loaded, no installed version is a
+/// release, or the lockfile cannot be written.
+pub async fn export(
+    path: &Path,
+    platforms: &[(String, String)],
+    config: &Config,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
+    let index = ReleaseIndex::load(&config.mirror, config.cache())
+        .await
+        .context("Failed to load the release index")?;
+
+    let mut installed = list_installed(config)?;
+    installed.sort_by(|a, b| crate::version::compare_versions(a, b));
+    let mut report = ExportReport { path: path.to_path_buf(), ..ExportReport::default() };
+    let mut versions = Vec::new();
+    for name in installed {
+        let locked =
+            index.find(&name).map(|release| LockedVersion::from_release(release, platforms));
+        match locked {
+            Some(locked) if !locked.archives.is_empty() => {
+                report.versions.push(name);
+                versions.push(locked);
+            }
+            Some(_) => {
+                ui.warning(&format!("Skipping Go {name}: no archives for the given platforms"));
+                report.skipped.push(name);
+            }
+            None => {
+                ui.warning(&format!("Skipping Go {name}: not a release"));
+                report.skipped.push(name);
             }
-            ui.newline();
-            ui.info("Visit https://go.dev/dl/ for a full list of versions");
-            ui.hint("Use 'gvm install <version>' to install");
         }
-        Err(e) => {
-            ui.error(&format!("Failed to fetch available versions: {e}"));
+    }
+    if versions.is_empty() {
+        return Err(GvmError::Usage("No installed Go release to export".to_string()).into());
+    }
+
+    report.default = manager
+        .get_current_version(config.versions())
+        .filter(|current| report.versions.contains(current));
+    Lockfile::new(versions, report.default.clone()).write(path)?;
+    ui.success(&format!("Locked {} versions in {}", report.versions.len(), path.display()));
+    if let Some(default) = &report.default {
+        ui.info(&format!("Default version: Go {default}"));
+    }
+    ui.hint("Use 'gvm sync' to install the locked versions on another machine");
+    output.emit("export", &report)
+}
+
+/// Install the versions of a lockfile and set its default version.
+///
+/// The checksums of the lockfile are compared with the release index of the mirror before
+/// anything is installed, with downloaded and cached archives before they are extracted, and
+/// with the archives that installed versions came from.
+///
+/// # Errors
+/// Returns an error if the lockfile cannot be read, a checksum diverges from the lockfile,
+/// a version is not available for this platform, or an installation fails.
+pub async fn sync(
+    path: &Path,
+    remove_extras: bool,
+    config: &Config,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
+    let lockfile = Lockfile::read(path)?;
+    let platform = PlatformInfo::detect();
+    let index = ReleaseIndex::load(&config.mirror, config.cache())
+        .await
+        .context("Failed to load the release index")?;
+
+    // Check the whole lockfile against the mirror before changing anything
+    let mut locked = Vec::new();
+    for version in &lockfile.versions {
+        let archive = version.archive_for(&platform.os, &platform.arch).ok_or_else(|| {
+            GvmError::UnsupportedPlatform {
+                version: version.version.clone(),
+                os: platform.os.clone(),
+                arch: platform.arch.clone(),
+            }
+        })?;
+        let published = index
+            .find(&version.version)
+            .and_then(|release| release.archive_for(&platform.os, &platform.arch))
+            .ok_or_else(|| GvmError::ReleaseNotFound(version.version.clone()))?;
+        if published.sha256 != archive.sha256 {
+            return Err(GvmError::LockfileMismatch {
+                file: archive.filename.clone(),
+                locked: archive.sha256.clone(),
+                actual: published.sha256.clone(),
+                origin: "the mirror".to_string(),
+            }
+            .into());
+        }
+        locked.push((version.version.as_str(), archive, published));
+    }
+
+    let installed = list_installed(config)?;
+    let mut report = SyncReport::default();
+    for (version, archive, published) in locked {
+        if installed.iter().any(|name| name == version) {
+            if check_locked_install(&ui, config, version, archive)? {
+                report.verified.push(version.to_string());
+            }
+            continue;
+        }
+
+        // The published checksum equals the locked one, so the archive is verified against
+        // the lockfile before anything is extracted
+        manager
+            .fetch_archive(published, &config.mirror, config.cache(), &Downloader::new())
+            .await
+            .with_context(|| format!("Failed to download Go {version}"))?;
+        manager
+            .install_with(install_request(version, config, false, false), &Downloader::new())
+            .await
+            .with_context(|| format!("Failed to install Go {version}"))?;
+        check_locked_install(&ui, config, version, archive)?;
+        ui.success(&format!("Go {version} installed"));
+        report.installed.push(version.to_string());
+    }
+
+    let mut current = manager.get_current_version(config.versions());
+    if let Some(default) = &lockfile.default {
+        if current.as_ref() != Some(default) {
+            manager
+                .switch_version(default, config.versions())
+                .with_context(|| format!("Failed to switch to Go {default}"))?;
+            ui.info(&format!("Switched to Go {default}"));
+            current = Some(default.clone());
+        }
+        report.default = Some(default.clone());
+    }
+
+    if remove_extras {
+        for version in installed.iter().filter(|name| lockfile.find(name).is_none()) {
+            if current.as_ref() == Some(version) {
+                ui.warning(&format!("Go {version} is active and is kept"));
+                continue;
+            }
+            manager.uninstall(UninstallRequest {
+                version: version.clone(),
+                base_dir: config.versions().clone(),
+            })?;
+            ui.info(&format!("Removed Go {version}"));
+            report.removed.push(version.clone());
+        }
+        prune_store(&ui, config);
+    }
+
+    if !report.installed.is_empty() {
+        after_install(&ui, config);
+    }
+    ui.success(&format!("{} versions in sync with {}", lockfile.versions.len(), path.display()));
+    output.emit("sync", &report)
+}
+
+/// Compares the archive checksum recorded for an installed version with its locked
+/// archive, returning whether it could be checked
+fn check_locked_install(
+    ui: &SimpleUI,
+    config: &Config,
+    version: &str,
+    archive: &LockedArchive,
+) -> Result<bool> {
+    let manifest = InstallManifest::read(&config.versions().join(version))?;
+    match manifest.and_then(|manifest| manifest.sha256) {
+        Some(sha256) if sha256 == archive.sha256 => Ok(true),
+        Some(sha256) => Err(GvmError::LockfileMismatch {
+            file: archive.filename.clone(),
+            locked: archive.sha256.clone(),
+            actual: sha256,
+            origin: format!("the installed Go {version}"),
+        }
+        .into()),
+        None => {
+            ui.warning(&format!("Cannot verify Go {version}: no archive checksum was recorded"));
+            Ok(false)
+        }
+    }
+}
+
+/// List installed Go versions.
+fn list_installed_versions(config: &Config, output: OutputFormat) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
+    let base_dir = config.versions();
+
+    let list_request = ListInstalledRequest { base_dir: base_dir.clone() };
+
+    let list = manager.list_installed(list_request).context("Failed to list versions")?;
+    output.emit("list", &list)?;
+
+    if list.versions.is_empty() {
+        ui.warning("No installed Go versions found");
+        ui.hint("Use 'gvm install <version>' to install a new version");
+    } else {
+        let (index, db) = offline_audit_sources(config);
+        let mut flagged = 0;
+        // List versions directly without a title
+        for version in &list.versions {
+            let audit = VersionAudit::new(&version.version, index.as_ref(), db.as_ref());
+            let label = match audit.problems() {
+                Some(problems) => {
+                    flagged += 1;
+                    format!("{} [{problems}]", installed_label(version))
+                }
+                None => installed_label(version),
+            };
+            ui.list_item(&label, version.is_current);
+        }
+        // Show total count only if there are multiple versions
+        if list.versions.len() > 1 {
+            ui.info(&format!("Total: {} versions", list.versions.len()));
+        }
+        if flagged > 0 {
+            ui.warning(&format!("{flagged} installed versions are unsupported or vulnerable"));
+            ui.hint("Use 'gvm audit' for details");
         }
     }
 
     Ok(())
 }
 
+/// Loads the release index and vulnerability database for auditing without contacting
+/// the network, as far as they are cached
+fn offline_audit_sources(config: &Config) -> (Option<ReleaseIndex>, Option<VulnDb>) {
+    let index = ReleaseIndex::read_cached(config.cache()).ok();
+    let db =
+        config.vuln_db.as_deref().and_then(|source| VulnDb::load_offline(source, config.cache()));
+    (index, db)
+}
+
+/// Formats an installed version, adding the commit and build date of source builds.
+fn installed_label(version: &GoVersionInfo) -> String {
+    let manifest =
+        version.install_path.as_deref().and_then(|path| InstallManifest::read(path).ok().flatten());
+
+    match manifest {
+        Some(InstallManifest {
+            build: Some(BuildInfo { git_commit: Some(commit), .. }),
+            installed_at,
+            ..
+        }) => {
+            format!(
+                "{} (commit {}, built {})",
+                version.version,
+                short_commit(&commit),
+                installed_at.format("%Y-%m-%d")
+            )
+        }
+        Some(InstallManifest {
+            install_method: InstallMethod::External,
+            imported_from: Some(path),
+            ..
+        }) => format!("{} (external: {})", version.version, path.display()),
+        _ => version.version.clone(),
+    }
+}
+
+/// List available Go versions from the release index as a table.
+async fn list_available_versions(
+    config: &Config,
+    filter: &ReleaseFilter,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
+    let index = ReleaseIndex::load(&config.mirror, config.cache())
+        .await
+        .context("Failed to load the release index")?;
+    let installed = list_installed(config)?;
+    let current = manager.get_current_version(config.versions());
+    let cached: HashSet<String> =
+        Cache::new(config.cache()).archives()?.into_iter().map(|a| a.filename).collect();
+    let (os, arch) = filter.platform.clone().unwrap_or_else(|| {
+        let platform = PlatformInfo::detect();
+        (platform.os, platform.arch)
+    });
+    let oldest_supported = index.oldest_supported_series();
+
+    let versions: Vec<ListedRelease> = filter
+        .select(&index, &installed)
+        .into_iter()
+        .map(|release| {
+            let version = release.version_number().to_string();
+            let archive = release.archive_for(&os, &arch).cloned();
+            ListedRelease {
+                is_installed: installed.contains(&version),
+                is_cached: archive.as_ref().is_some_and(|a| cached.contains(&a.filename)),
+                is_current: current.as_deref() == Some(version.as_str()),
+                stable: release.stable,
+                archived: release.is_archived(oldest_supported),
+                archive,
+                version,
+            }
+        })
+        .collect();
+
+    if versions.is_empty() {
+        ui.warning("No Go versions match the given filters");
+    } else {
+        ui.section(&format!("Available Go Versions ({os}/{arch})"));
+        let rows: Vec<(Vec<String>, bool)> = versions
+            .iter()
+            .map(|listed| {
+                let kind = match (listed.archived, listed.stable) {
+                    (true, _) => "archived",
+                    (false, true) => "stable",
+                    (false, false) => "unstable",
+                };
+                let size =
+                    listed.archive.as_ref().map_or_else(|| "-".into(), |a| format_size(a.size));
+                let local: Vec<&str> = [
+                    (listed.is_installed, "installed"),
+                    (listed.is_cached, "cached"),
+                    (listed.is_current, "active"),
+                ]
+                .into_iter()
+                .filter_map(|(set, marker)| set.then_some(marker))
+                .collect();
+                (
+                    vec![listed.version.clone(), kind.into(), size, local.join(", ")],
+                    listed.is_current,
+                )
+            })
+            .collect();
+        ui.table(&["VERSION", "KIND", "SIZE", "LOCAL"], &rows);
+        ui.newline();
+        ui.info(&format!("Total: {} versions", versions.len()));
+        ui.hint("Use 'gvm install <version>' to install");
+    }
+    let total_count = versions.len();
+    output.emit(
+        "list",
+        &ReleaseListReport { platform: format!("{os}/{arch}"), versions, total_count },
+    )
+}
+
 /// Show detailed information about a Go version.
 ///
+/// Releases are also described with their date, kind, release notes summary and the
+/// files published for each platform, as far as the release index and history can be
+/// loaded.
+///
 /// # Errors
 /// Returns an error if the info operation fails.
-pub fn info(version: &str, config: &Config) -> Result<()> {
-    let ui = SimpleUI::new();
-    let manager = GoManager::new();
+pub async fn info(version: &str, config: &Config, output: OutputFormat) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
     let install_dir = config.versions();
     let cache_dir = config.cache();
 
-    match manager.get_version_info(version, install_dir, cache_dir) {
-        Ok(info) => {
-            // Simplified output, showing only key information
-            ui.info(&format!("Go {} ({}-{})", info.version, info.os, info.arch));
+    let info = manager
+        .get_version_info(version, install_dir, cache_dir, &config.mirror)
+        .with_context(|| format!("Failed to get info for Go {version}"))?;
+    let release = release_details(version, config).await;
+    let report = InfoReport { version: info, release };
+    output.emit("info", &report)?;
+    let InfoReport { version: info, release } = report;
+
+    // Simplified output, showing only key information
+    ui.key_value("Version", &format!("Go {} ({}-{})", info.version, info.os, info.arch));
 
-            if info.is_installed {
-                ui.success("Installed");
-                ui.hint(&format!("To use: gvm use {version}"));
+    if let Some(release) = &release {
+        if let Some(date) = release.date {
+            ui.key_value("Released", &date.to_string());
+        }
+        match release.kind {
+            Some(kind) => ui.key_value("Kind", kind.label()),
+            None if !release.stable => ui.key_value("Kind", "pre-release"),
+            None => {}
+        }
+        if let Some(summary) = &release.summary {
+            ui.key_value("Notes", &format!("Go {version} {summary}"));
+        }
+    }
+
+    if info.is_installed {
+        ui.key_value_colored("Status", "installed", "green");
+        let verification = info
+            .install_path
+            .as_deref()
+            .and_then(|path| InstallManifest::read(path).ok().flatten())
+            .and_then(|manifest| manifest.verification);
+        if let Some(verification) = verification {
+            ui.key_value("Verified", &verification.go_version);
+            ui.key_value("GOROOT", &verification.goroot);
+            ui.key_value("GOOS/GOARCH", &format!("{}/{}", verification.goos, verification.goarch));
+            ui.key_value(
+                "Verified at",
+                &verification.verified_at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            );
+        }
+    } else {
+        ui.key_value_colored("Status", "not installed", "yellow");
+    }
+
+    if let Some(release) = release.filter(|release| !release.files.is_empty()) {
+        ui.section("Downloads");
+        for file in &release.files {
+            let platform = if file.os.is_empty() {
+                "source".to_string()
             } else {
-                ui.warning("Not installed");
-                ui.hint(&format!("To install: gvm install {version}"));
+                format!("{}-{}", file.os, file.arch)
+            };
+            ui.list_item(
+                &format!(
+                    "{:<16} {:<9} {:>9}  {}",
+                    platform,
+                    file.kind,
+                    format_size(file.size),
+                    file.sha256
+                ),
+                false,
+            );
+        }
+    }
+
+    if info.is_installed {
+        ui.hint(&format!("To use: gvm use {version}"));
+    } else {
+        ui.hint(&format!("To install: gvm install {version}"));
+    }
+    Ok(())
+}
+
+/// Looks a release up in the release index and history
+///
+/// Returns `None` if the version is not a known release or the index cannot be loaded;
+/// the release notes are left out if the history cannot be loaded.
+async fn release_details(version: &str, config: &Config) -> Option<ReleaseDetails> {
+    let index = ReleaseIndex::load(&config.mirror, config.cache())
+        .await
+        .map_err(|e| debug!("Release index unavailable: {e:#}"))
+        .ok()?;
+    let release = index.find(version)?;
+    let note = load_history(config).await.and_then(|history| history.find(version).cloned());
+
+    Some(ReleaseDetails {
+        stable: release.stable,
+        date: note.as_ref().and_then(|note| note.date),
+        kind: note.as_ref().map(|note| note.kind),
+        summary: note.map(|note| note.summary),
+        files: release.files.clone(),
+    })
+}
+
+/// Loads the release history, logging why when it is unavailable
+async fn load_history(config: &Config) -> Option<ReleaseHistory> {
+    ReleaseHistory::load(&config.release_history_url, config.cache())
+        .await
+        .map_err(|e| debug!("Release history unavailable: {e:#}"))
+        .ok()
+}
+
+/// List the releases between two versions.
+///
+/// # Errors
+/// Returns an error if a version is invalid or the release index cannot be loaded.
+pub async fn diff(from: &str, to: &str, config: &Config, output: OutputFormat) -> Result<()> {
+    let ui = output.ui();
+    let parse = |version: &str| {
+        GoVersion::parse(version).ok_or_else(|| GvmError::InvalidVersion(version.to_string()))
+    };
+    let (from_version, to_version) = (parse(from)?, parse(to)?);
+
+    let index = ReleaseIndex::load(&config.mirror, config.cache())
+        .await
+        .context("Failed to load the release index")?;
+    let history = load_history(config).await;
+    if history.is_none() {
+        ui.warning("Release history unavailable, showing versions only");
+    }
+
+    let releases: Vec<ReleaseSummary> = index
+        .releases_between(&from_version, &to_version)
+        .into_iter()
+        .map(|release| {
+            let version = release.version_number().to_string();
+            let note = history.as_ref().and_then(|history| history.find(&version));
+            ReleaseSummary {
+                date: note.and_then(|note| note.date),
+                kind: note.map(|note| note.kind),
+                summary: note.map(|note| note.summary.clone()),
+                version,
+            }
+        })
+        .collect();
+
+    for release in &releases {
+        let date = release.date.map(|date| date.to_string()).unwrap_or_default();
+        let kind = release.kind.map(ReleaseKind::label).unwrap_or_default();
+        ui.list_item(&format!("{:<10} {:<10} {}", release.version, date, kind), false);
+        if let Some(summary) = &release.summary {
+            ui.key_value("Notes", summary);
+        }
+    }
+    let security =
+        releases.iter().filter(|release| release.kind == Some(ReleaseKind::Security)).count();
+    if releases.is_empty() {
+        ui.info(&format!("No releases after Go {from} up to Go {to}"));
+    } else {
+        ui.info(&format!(
+            "{} releases after Go {from} up to Go {to}, {security} with security fixes",
+            releases.len()
+        ));
+    }
+    output.emit("diff", &DiffReport { from: from.to_string(), to: to.to_string(), releases })
+}
+
+/// Audit versions for end of support and known vulnerabilities.
+///
+/// Without versions, every installed version is audited. Vulnerabilities are only checked
+/// when a vulnerability database is given with `vuln_db` or `GVM_VULN_DB`.
+///
+/// # Errors
+/// Returns an error if the release index or vulnerability database cannot be loaded, or
+/// if versions fail the audit as selected by `fail_on`.
+pub async fn audit(
+    versions: &[String],
+    config: &Config,
+    vuln_db: Option<&str>,
+    fail_on: FailOn,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let versions = if versions.is_empty() { list_installed(config)? } else { versions.to_vec() };
+
+    let index = ReleaseIndex::load(&config.mirror, config.cache())
+        .await
+        .context("Failed to load the release index")?;
+    let db = match vuln_db.or(config.vuln_db.as_deref()) {
+        Some(source) => Some(
+            VulnDb::load(source, config.cache())
+                .await
+                .context("Failed to load the vulnerability database")?,
+        ),
+        None => {
+            ui.info("No vulnerability database configured, only checking support status");
+            ui.hint("Set GVM_VULN_DB or pass --vuln-db to check for known vulnerabilities");
+            None
+        }
+    };
+
+    let mut report = AuditReport {
+        supported_series: supported_series(&index)
+            .into_iter()
+            .map(|(major, minor)| format!("{major}.{minor}"))
+            .collect(),
+        ..AuditReport::default()
+    };
+    for version in &versions {
+        let audit = VersionAudit::new(version, Some(&index), db.as_ref());
+        match (audit.problems(), audit.support) {
+            (Some(problems), _) => ui.warning(&format!("Go {version}: {problems}")),
+            (None, SupportStatus::Supported) => ui.success(&format!("Go {version}: supported")),
+            (None, SupportStatus::Prerelease) => ui.info(&format!("Go {version}: pre-release")),
+            (None, _) => ui.info(&format!("Go {version}: not a release, support unknown")),
+        }
+        for vulnerability in &audit.vulnerabilities {
+            let fixed = vulnerability
+                .fixed
+                .as_ref()
+                .map(|fixed| format!(" (fixed in {fixed})"))
+                .unwrap_or_default();
+            let summary = vulnerability.summary.as_deref().unwrap_or("no summary");
+            ui.key_value(&vulnerability.id, &format!("{summary}{fixed}"));
+        }
+        if let Some(latest) = &audit.latest_patch {
+            ui.hint(&format!("Go {latest} is available: gvm upgrade {}", series_of(latest)));
+        }
+        report.vulnerable += usize::from(audit.is_vulnerable());
+        report.unsupported += usize::from(audit.is_unsupported());
+        report.versions.push(audit);
+    }
+    ui.info(&format!("Supported series: {}", report.supported_series.join(", ")));
+
+    let (vulnerable, unsupported) = (report.vulnerable, report.unsupported);
+    output.emit("audit", &report)?;
+    let failed = match fail_on {
+        FailOn::Any => vulnerable + unsupported > 0,
+        FailOn::Vulnerable => vulnerable > 0,
+        FailOn::Unsupported => unsupported > 0,
+        FailOn::Never => false,
+    };
+    if failed {
+        return Err(GvmError::AuditFailed { vulnerable, unsupported }.into());
+    }
+    Ok(())
+}
+
+/// Gets the series name of a version, e.g. "1.22" for 1.22.5
+fn series_of(version: &str) -> String {
+    GoVersion::parse(version).map_or_else(|| version.to_string(), |v| v.series_name())
+}
+
+/// Diagnose the gvm environment, optionally fixing what can be fixed automatically.
+///
+/// # Errors
+/// Returns an error if errors remain after checking (and fixing).
+pub fn doctor(config: &Config, fix: bool, output: OutputFormat) -> Result<()> {
+    let ui = output.ui();
+    let mut findings = Doctor::new(config, DoctorEnv::from_process()).run();
+
+    if fix {
+        for finding in findings.iter_mut().filter(|f| f.severity != Severity::Ok) {
+            let Some(action) = &finding.fix else {
+                continue;
+            };
+            match action.apply() {
+                Ok(()) => finding.fixed = true,
+                Err(e) => ui.warning(&format!(
+                    "Failed to {}: {}",
+                    action.describe(),
+                    ErrorUtils::message(&e)
+                )),
+            }
+        }
+    }
+
+    for finding in &findings {
+        match (finding.severity, &finding.fix) {
+            (_, Some(action)) if finding.fixed => {
+                ui.success(&format!("{} (fixed: {})", finding.message, action.describe()));
+                continue;
+            }
+            (Severity::Ok, _) => ui.success(&finding.message),
+            (Severity::Warning, _) => ui.warning(&finding.message),
+            (Severity::Error, _) => ui.error(&finding.message),
+        }
+        match &finding.fix {
+            Some(action) => ui.hint(&format!(
+                "{} (or run 'gvm doctor --fix' to {})",
+                finding.suggestion.as_deref().unwrap_or_default(),
+                action.describe()
+            )),
+            None => {
+                if let Some(suggestion) = &finding.suggestion {
+                    ui.hint(suggestion);
+                }
             }
         }
+    }
+
+    let problems = findings.iter().filter(|f| f.is_open_problem()).count();
+    let errors =
+        findings.iter().filter(|f| f.is_open_problem() && f.severity == Severity::Error).count();
+    output.emit("doctor", &DoctorReport { findings, problems })?;
+
+    if errors > 0 {
+        return Err(GvmError::ProblemsFound(errors).into());
+    }
+    if problems > 0 {
+        ui.info(&format!("{problems} warning(s), no errors found"));
+    } else {
+        ui.success("No problems found");
+    }
+    Ok(())
+}
+
+/// List the archives in the download cache.
+///
+/// # Errors
+/// Returns an error if the cache directory cannot be read.
+pub fn cache_list(config: &Config, output: OutputFormat) -> Result<()> {
+    let ui = output.ui();
+    let archives = Cache::new(config.cache()).archives()?;
+    let installed = list_installed(config)?;
+    let total_size = archives.iter().map(|a| a.size).sum();
+
+    if archives.is_empty() {
+        ui.info("The download cache is empty");
+    } else {
+        ui.section("Cached Go Archives");
+        for archive in &archives {
+            ui.list_item(
+                &format!(
+                    "{:<36} {:<10} {:<16} {:>9} {:>5}",
+                    archive.filename,
+                    archive.version.as_deref().unwrap_or("-"),
+                    archive.platform(),
+                    format_size(archive.size),
+                    format_age(archive.age())
+                ),
+                archive.is_installed(&installed),
+            );
+        }
+        ui.newline();
+        ui.info(&format!("Total: {} archives, {}", archives.len(), format_size(total_size)));
+        ui.hint("Use 'gvm cache clean' to free space");
+    }
+    output.emit("cache list", &CacheListReport { archives, total_size })
+}
+
+/// Show how much space the download cache uses.
+///
+/// # Errors
+/// Returns an error if the cache directory cannot be read.
+pub fn cache_size(config: &Config, output: OutputFormat) -> Result<()> {
+    let ui = output.ui();
+    let cache = Cache::new(config.cache());
+    let archives = cache.archives()?;
+    let report = CacheSizeReport {
+        path: config.cache().clone(),
+        archive_count: archives.len(),
+        archive_size: archives.iter().map(|a| a.size).sum(),
+        total_size: cache.total_size()?,
+        max_size: config.cache_policy.max_size,
+    };
+
+    ui.key_value("Cache", &report.path.display().to_string());
+    ui.key_value(
+        "Archives",
+        &format!("{} ({})", report.archive_count, format_size(report.archive_size)),
+    );
+    ui.key_value("Total", &format_size(report.total_size));
+    if let Some(max_size) = report.max_size {
+        ui.key_value("Limit", &format_size(max_size));
+    }
+    output.emit("cache size", &report)
+}
+
+/// Verify the cached archives against the published checksums.
+///
+/// Archives that are not in the release index are only checked to be readable.
+///
+/// # Errors
+/// Returns an error if corrupt archives remain.
+pub async fn cache_verify(config: &Config, remove: bool, output: OutputFormat) -> Result<()> {
+    let ui = output.ui();
+    let cache = Cache::new(config.cache());
+    let archives = cache.archives()?;
+    let index = match ReleaseIndex::load(&config.mirror, config.cache()).await {
+        Ok(index) => Some(index),
         Err(e) => {
-            ui.error(&format!("Failed to get info for Go {version}: {e}"));
-            ui.hint("Please ensure the version exists and is accessible");
+            ui.warning(&format!(
+                "Release index unavailable, only checking that archives are readable: {}",
+                ErrorUtils::message(&e)
+            ));
+            None
+        }
+    };
+
+    let mut report = CacheVerifyReport::default();
+    for archive in &archives {
+        let check = check_archive(&archive.path, index.as_ref());
+        let mut removed = false;
+        match &check {
+            ArchiveCheck::Verified => ui.success(&format!("{} verified", archive.filename)),
+            ArchiveCheck::Readable => {
+                ui.info(&format!("{} is readable (no published checksum)", archive.filename));
+            }
+            ArchiveCheck::Corrupt(problem) => {
+                ui.error(&format!("{} is corrupt: {problem}", archive.filename));
+                if remove {
+                    cache.remove(archive)?;
+                    ui.info(&format!("Removed {}", archive.filename));
+                    removed = true;
+                } else {
+                    report.corrupt += 1;
+                }
+            }
         }
+        report.archives.push(VerifiedArchive {
+            filename: archive.filename.clone(),
+            check,
+            removed,
+        });
     }
+    let corrupt = report.corrupt;
+    output.emit("cache verify", &report)?;
 
+    if corrupt > 0 {
+        return Err(GvmError::CorruptCache(corrupt).into());
+    }
+    ui.success(&format!("{} cached archives checked", archives.len()));
     Ok(())
 }
+
+/// Delete cached archives matching a filter.
+///
+/// # Errors
+/// Returns an error if the cache cannot be read or an archive cannot be deleted.
+pub fn cache_clean(
+    config: &Config,
+    filter: &CleanFilter,
+    dry_run: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let cache = Cache::new(config.cache());
+    let archives = cache.archives()?;
+    let installed = list_installed(config)?;
+    let selected = filter.select(&archives, &installed);
+
+    for archive in &selected {
+        if dry_run {
+            ui.info(&format!("Would remove {} ({})", archive.filename, format_size(archive.size)));
+        } else {
+            cache.remove(archive)?;
+            ui.info(&format!("Removed {} ({})", archive.filename, format_size(archive.size)));
+        }
+    }
+
+    let freed = selected.iter().map(|a| a.size).sum();
+    if selected.is_empty() {
+        ui.info("No cached archives to remove");
+    } else if dry_run {
+        ui.info(&format!("{} archives ({}) would be removed", selected.len(), format_size(freed)));
+    } else {
+        ui.success(&format!("Removed {} archives, freed {}", selected.len(), format_size(freed)));
+    }
+    let removed = selected.into_iter().cloned().collect();
+    output.emit("cache clean", &CacheCleanReport { removed, freed, dry_run })
+}
+
+/// Package Go archives for several versions and platforms into an offline bundle.
+///
+/// Archives are taken from the download cache or downloaded and verified against the
+/// release index, a snapshot of which is bundled as well.
+///
+/// # Errors
+/// Returns an error if a version or platform is not available, a download fails, or the
+/// bundle cannot be written.
+pub async fn bundle_create(
+    path: &Path,
+    versions: &[String],
+    platforms: &[(String, String)],
+    config: &Config,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
+    let index = ReleaseIndex::load(&config.mirror, config.cache())
+        .await
+        .context("Failed to load the release index")?;
+    let platforms = if platforms.is_empty() {
+        let host = PlatformInfo::detect();
+        vec![(host.os, host.arch)]
+    } else {
+        platforms.to_vec()
+    };
+
+    let mut locked: Vec<LockedVersion> = Vec::new();
+    let mut files = Vec::new();
+    for spec in versions {
+        let version = index.resolve(spec)?;
+        let release =
+            index.find(&version).ok_or_else(|| GvmError::ReleaseNotFound(spec.clone()))?;
+        if locked.iter().any(|v| v.version == version) {
+            continue;
+        }
+        for (os, arch) in &platforms {
+            let file =
+                release.archive_for(os, arch).ok_or_else(|| GvmError::UnsupportedPlatform {
+                    version: version.clone(),
+                    os: os.clone(),
+                    arch: arch.clone(),
+                })?;
+            files.push(file);
+        }
+        locked.push(LockedVersion::from_release(release, &platforms));
+    }
+
+    let version_names: Vec<&str> = locked.iter().map(|v| v.version.as_str()).collect();
+    ui.info(&format!("Bundling {} archives of Go {}", files.len(), version_names.join(", ")));
+    let downloader = Downloader::new();
+    let mut archives = Vec::new();
+    for file in files {
+        let archive = manager
+            .fetch_archive(file, &config.mirror, config.cache(), &downloader)
+            .await
+            .with_context(|| format!("Failed to download {}", file.filename))?;
+        archives.push(archive);
+    }
+
+    let manifest = BundleManifest::new(locked);
+    bundle::create(path, &manifest, &serde_json::to_vec(&index.releases)?, &archives)?;
+    let size = std::fs::metadata(path).map_err(GvmError::io("read", path))?.len();
+    ui.success(&format!("Created bundle {} ({})", path.display(), format_size(size)));
+    ui.hint(&format!("Use 'gvm bundle install {}' on the offline machine", path.display()));
+    output.emit(
+        "bundle create",
+        &BundleCreateReport { path: path.to_path_buf(), versions: manifest.versions, size },
+    )
+}
+
+/// Install Go versions from an offline bundle without network access.
+///
+/// The bundled release index replaces the cached one when it is newer, so that commands
+/// such as `list --all` and `audit` work offline as well.
+///
+/// # Errors
+/// Returns an error if the bundle cannot be read, a requested version is not bundled for
+/// this platform, or an installation fails.
+pub async fn bundle_install(
+    path: &Path,
+    versions: &[String],
+    config: &Config,
+    force: bool,
+    verify: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
+    let bundle = Bundle::open(path)?;
+    let bundled: Vec<&str> =
+        bundle.manifest().versions.iter().map(|v| v.version.as_str()).collect();
+    if let Some(missing) = versions.iter().find(|v| !bundled.contains(&v.as_str())) {
+        return Err(GvmError::Usage(format!(
+            "Go {missing} is not in bundle {} (bundled: {})",
+            path.display(),
+            bundled.join(", ")
+        ))
+        .into());
+    }
+
+    let mut report = BundleInstallReport {
+        index_updated: update_cached_index(&bundle, config)?,
+        ..BundleInstallReport::default()
+    };
+    // Installed versions record where they came from
+    let bundle_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let installed = list_installed(config)?;
+    let selected =
+        bundled.iter().filter(|v| versions.is_empty() || versions.iter().any(|r| r == *v));
+    for &version in selected {
+        if !force && installed.iter().any(|name| name == version) {
+            ui.info(&format!("Go {version} is already installed"));
+            report.skipped.push(version.to_string());
+            continue;
+        }
+        let request = InstallRequest {
+            bundle: Some(bundle_path.clone()),
+            ..install_request(version, config, force, verify)
+        };
+        let version_info = manager
+            .install_with(request, &Downloader::new())
+            .await
+            .with_context(|| format!("Failed to install Go {version} from the bundle"))?;
+        ui.success(&format!("Go {version} installed"));
+        report.installed.push(version_info);
+    }
+
+    if !report.installed.is_empty() {
+        after_install(&ui, config);
+        ui.hint("Use 'gvm use <version>' to activate a version");
+    }
+    output.emit("bundle install", &report)
+}
+
+/// Writes the release index snapshot of a bundle to the cache unless the cached index is
+/// newer, returning whether it was written
+fn update_cached_index(bundle: &Bundle, config: &Config) -> Result<bool> {
+    let cache_file = ReleaseIndex::cache_file(config.cache());
+    let cached_at = std::fs::metadata(&cache_file)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(chrono::DateTime::<chrono::Utc>::from);
+    if cached_at.is_some_and(|cached_at| cached_at >= bundle.manifest().created_at) {
+        return Ok(false);
+    }
+    std::fs::create_dir_all(config.cache()).map_err(GvmError::io("create", config.cache()))?;
+    std::fs::write(&cache_file, bundle.read_index()?)
+        .map_err(GvmError::io("write", &cache_file))?;
+    Ok(true)
+}
+
+/// Serves the download cache as a mirror for other gvm instances until interrupted
+///
+/// With `upstream`, archives that are not cached are pulled from it on first request.
+///
+/// # Errors
+/// Returns an error if the address cannot be bound or accepting connections fails.
+pub async fn serve(
+    listen: SocketAddr,
+    upstream: Option<&str>,
+    config: &Config,
+    output: OutputFormat,
+) -> Result<()> {
+    let ui = output.ui();
+    std::fs::create_dir_all(config.cache()).map_err(GvmError::io("create", config.cache()))?;
+    let listener = tokio::net::TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("Failed to listen on {listen}"))?;
+    let addr = listener.local_addr()?;
+
+    ui.success(&format!("Serving {} on http://{addr}", config.cache().display()));
+    match upstream {
+        Some(upstream) => {
+            ui.info(&format!("Archives that are not cached are pulled from {upstream}"))
+        }
+        None => ui.info("Only cached archives are served"),
+    }
+    let host =
+        if addr.ip().is_unspecified() { "<this-host>".to_string() } else { addr.ip().to_string() };
+    ui.hint(&format!("Point other machines at it with GVM_MIRROR=http://{host}:{}", addr.port()));
+    ui.hint("Press Ctrl+C to stop");
+
+    let server = Arc::new(Server::new(config.cache(), upstream.map(str::to_string)));
+    tokio::select! {
+        result = server.run(listener) => result,
+        _ = tokio::signal::ctrl_c() => {
+            ui.info("Stopped serving");
+            Ok(())
+        }
+    }
+}
+
+/// Deduplicates files of installed versions with hard links to a shared store.
+///
+/// Store entries that no version links to any more are removed first.
+///
+/// # Errors
+/// Returns an error if deduplication is not supported or a file cannot be linked.
+pub fn dedup(config: &Config, output: OutputFormat) -> Result<()> {
+    let ui = output.ui();
+    let report = dedup_installed(config)?;
+    if report.linked == 0 {
+        ui.info(&format!("No duplicate files among {} versions", report.versions.len()));
+    } else {
+        ui.success(&format!(
+            "Linked {} duplicate files of {} versions, freeing {}",
+            report.linked,
+            report.versions.len(),
+            format_size(report.reclaimed)
+        ));
+    }
+    if report.pruned > 0 {
+        ui.info(&format!("Removed {} unused store entries", report.pruned));
+    }
+    output.emit("dedup", &report)
+}
+
+/// Deduplicates the installed versions, except external ones whose files gvm does not own
+fn dedup_installed(config: &Config) -> Result<DedupReport> {
+    let store = Store::new(&config.store_path);
+    let (pruned, _) = store.prune()?;
+    let versions: Vec<String> = list_installed(config)?
+        .into_iter()
+        .filter(|version| !is_symlink(&config.versions().join(version)))
+        .collect();
+    let dirs: Vec<PathBuf> = versions.iter().map(|v| config.versions().join(v)).collect();
+    let stats = store.dedup(&dirs)?;
+    Ok(DedupReport {
+        store: config.store_path.clone(),
+        versions,
+        files: stats.files,
+        linked: stats.linked,
+        reclaimed: stats.reclaimed,
+        pruned,
+    })
+}
+
+/// Show the disk usage of each installed version, the cache and the gvm root.
+///
+/// With deduplicated versions, the on-disk size of a version is the space that uninstalling
+/// it frees, and files shared by several versions are reported separately.
+///
+/// # Errors
+/// Returns an error if the versions directory cannot be read.
+pub fn du(config: &Config, sort: DuSort, output: OutputFormat) -> Result<()> {
+    let ui = output.ui();
+    let manager = output.manager();
+    let store = Store::new(&config.store_path);
+    let stored = store.stored_files();
+    let current = manager.get_current_version(config.versions());
+
+    let mut installed = list_installed(config)?;
+    installed.sort_by(|a, b| crate::version::compare_versions(a, b));
+    let mut versions: Vec<VersionDiskUsage> = installed
+        .into_iter()
+        .map(|version| VersionDiskUsage {
+            usage: stored.version_usage(&config.versions().join(&version)),
+            is_current: current.as_ref() == Some(&version),
+            version,
+        })
+        .collect();
+    if sort == DuSort::Size {
+        versions.sort_by_key(|v| std::cmp::Reverse(v.usage.on_disk));
+    }
+
+    let version_dirs: Vec<PathBuf> =
+        versions.iter().map(|v| config.versions().join(&v.version)).collect();
+    let mut linked: Vec<&Path> = version_dirs.iter().map(PathBuf::as_path).collect();
+    linked.push(store.dir());
+    let exclusive: u64 = versions.iter().map(|v| v.usage.on_disk).sum();
+    let shared = disk_usage(&linked).on_disk.saturating_sub(exclusive);
+
+    // The versions and the cache may be configured outside of the root
+    let mut roots = vec![config.root_path.as_path()];
+    for dir in [config.versions(), config.cache()] {
+        if !dir.starts_with(&config.root_path) {
+            roots.push(dir);
+        }
+    }
+    // Store entries are extra names of version files, not extra content
+    let mut total = disk_usage(&roots);
+    total.apparent = total.apparent.saturating_sub(disk_usage(&[store.dir()]).apparent);
+    let report = DiskUsageReport {
+        root: config.root_path.clone(),
+        versions,
+        shared,
+        cache: disk_usage(&[config.cache().as_path()]),
+        total,
+    };
+
+    let row = |name: &str, apparent: String, on_disk: u64| {
+        vec![name.to_string(), apparent, format_size(on_disk)]
+    };
+    let mut rows: Vec<(Vec<String>, bool)> = report
+        .versions
+        .iter()
+        .map(|v| (row(&v.version, format_size(v.usage.apparent), v.usage.on_disk), v.is_current))
+        .collect();
+    if report.shared > 0 {
+        rows.push((row("(shared)", "-".to_string(), report.shared), false));
+    }
+    for (name, usage) in [("(cache)", report.cache), ("(total)", report.total)] {
+        rows.push((row(name, format_size(usage.apparent), usage.on_disk), false));
+    }
+    ui.table(&["NAME", "APPARENT", "ON DISK"], &rows);
+    if report.shared > 0 {
+        ui.hint("A version's ON DISK size is what uninstalling it frees; shared files stay");
+    }
+    output.emit("du", &report)
+}
+
+/// Runs the post-install steps: store cleanup for replaced versions, then the configured
+/// deduplication and cache policy
+fn after_install(ui: &SimpleUI, config: &Config) {
+    prune_store(ui, config);
+    if config.dedup_after_install {
+        match dedup_installed(config) {
+            Ok(report) if report.reclaimed > 0 => ui.info(&format!(
+                "Deduplication freed {} by linking {} files",
+                format_size(report.reclaimed),
+                report.linked
+            )),
+            Ok(_) => {}
+            Err(e) => ui.warning(&format!("Failed to deduplicate: {}", ErrorUtils::message(&e))),
+        }
+    }
+    apply_cache_policy(ui, config);
+}
+
+/// Removes store entries left unused by uninstalled versions, warning instead of failing
+fn prune_store(ui: &SimpleUI, config: &Config) {
+    match Store::new(&config.store_path).prune() {
+        Ok((0, _)) => {}
+        Ok((count, size)) => {
+            debug!("Removed {count} unused store entries ({})", format_size(size));
+        }
+        Err(e) => ui.warning(&format!(
+            "Failed to clean up the deduplication store: {}",
+            ErrorUtils::message(&e)
+        )),
+    }
+}
+
+/// Applies the configured cache policy after an installation, warning instead of failing
+fn apply_cache_policy(ui: &SimpleUI, config: &Config) {
+    let policy = config.cache_policy;
+    if policy == CachePolicy::default() {
+        return;
+    }
+
+    let cache = Cache::new(config.cache());
+    let result = cache.archives().and_then(|archives| {
+        let installed = list_installed(config)?;
+        let selected = policy.select(&archives, &installed);
+        for archive in &selected {
+            cache.remove(archive)?;
+        }
+        Ok((selected.len(), selected.iter().map(|a| a.size).sum::<u64>()))
+    });
+    match result {
+        Ok((0, _)) => {}
+        Ok((count, freed)) => ui.info(&format!(
+            "Cache policy removed {count} cached archive(s), freeing {}",
+            format_size(freed)
+        )),
+        Err(e) => {
+            ui.warning(&format!("Failed to apply the cache policy: {}", ErrorUtils::message(&e)));
+        }
+    }
+}
@@ -16,6 +16,20 @@ pub struct Config {
     pub versions_path: PathBuf,
     /// Directory for cached downloads
     pub cache_path: PathBuf,
+    /// Git remote used for source builds
+    pub source_repo: String,
+    /// Base URL that Go archives are downloaded from
+    pub mirror: String,
+    /// Content-addressed store that deduplicated files link to
+    pub store_path: PathBuf,
+    /// Cache policy applied after each installation
+    pub cache_policy: crate::cache::CachePolicy,
+    /// Deduplicate installed versions after each installation
+    pub dedup_after_install: bool,
+    /// Go vulnerability database snapshot (OSV JSON file or URL)
+    pub vuln_db: Option<String>,
+    /// Release history page that release notes are read from
+    pub release_history_url: String,
 }
 
 impl Config {
@@ -23,12 +37,32 @@ impl Config {
     /// Creates a new configuration.
     ///
     /// # Errors
-    /// Returns an error if the configuration directories cannot be created.
+    /// Returns an error if the home directory cannot be determined or the cache policy is
+    /// invalid.
     pub fn new() -> Result<Self> {
         let root_path = Self::resolve_root_path()?;
         let versions_path = Self::resolve_versions_path(&root_path);
         let cache_path = Self::resolve_cache_path(&root_path);
-        Ok(Config { root_path, versions_path, cache_path })
+        let source_repo = Self::resolve_source_repo();
+        let mirror = Self::resolve_mirror();
+        let store_path = root_path.join(crate::dedup::STORE_DIR);
+        let cache_policy = Self::resolve_cache_policy()?;
+        let dedup_after_install = Self::resolve_dedup()?;
+        let vuln_db = env::var("GVM_VULN_DB").ok().filter(|source| !source.is_empty());
+        let release_history_url = env::var("GVM_RELEASE_HISTORY_URL")
+            .unwrap_or_else(|_| crate::history::DEFAULT_RELEASE_HISTORY_URL.to_string());
+        Ok(Config {
+            root_path,
+            versions_path,
+            cache_path,
+            source_repo,
+            mirror,
+            store_path,
+            cache_policy,
+            dedup_after_install,
+            vuln_db,
+            release_history_url,
+        })
     }
 
     /// Get the GVM root path
@@ -66,6 +100,48 @@ impl Config {
         root_path.join("cache")
     }
 
+    /// Get the Go source repository
+    ///
+    /// Priority: Environment variable `GVM_GO_SOURCE_REPO` -> Default (`https://go.googlesource.com/go`)
+    fn resolve_source_repo() -> String {
+        env::var("GVM_GO_SOURCE_REPO")
+            .unwrap_or_else(|_| crate::source::DEFAULT_SOURCE_REPO.to_string())
+    }
+
+    /// Get the download mirror
+    ///
+    /// Priority: Environment variable `GVM_MIRROR` -> Default (`https://go.dev/dl`)
+    fn resolve_mirror() -> String {
+        env::var("GVM_MIRROR")
+            .map(|mirror| mirror.trim_end_matches('/').to_string())
+            .unwrap_or_else(|_| crate::go::DEFAULT_MIRROR.to_string())
+    }
+
+    /// Get the cache policy
+    ///
+    /// From environment variables `GVM_CACHE_KEEP` (`all` or `installed`) and
+    /// `GVM_CACHE_MAX_SIZE` (e.g. `2G`); by default every archive is kept
+    fn resolve_cache_policy() -> Result<crate::cache::CachePolicy> {
+        crate::cache::CachePolicy::from_values(
+            env::var("GVM_CACHE_KEEP").ok().as_deref(),
+            env::var("GVM_CACHE_MAX_SIZE").ok().as_deref(),
+        )
+    }
+
+    /// Get whether to deduplicate after installing
+    ///
+    /// From environment variable `GVM_DEDUP` (`1`/`true`/`yes`/`on`); off by default
+    fn resolve_dedup() -> Result<bool> {
+        match env::var("GVM_DEDUP").unwrap_or_default().trim().to_ascii_lowercase().as_str() {
+            "" | "0" | "false" | "no" | "off" => Ok(false),
+            "1" | "true" | "yes" | "on" => Ok(true),
+            other => Err(crate::error::GvmError::Usage(format!(
+                "Invalid GVM_DEDUP '{other}': expected 'true' or 'false'"
+            ))
+            .into()),
+        }
+    }
+
     /// Get the versions path
     #[must_use]
     pub fn versions(&self) -> &PathBuf {
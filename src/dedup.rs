@@ -0,0 +1,331 @@
+//! Installation deduplication module
+//!
+//! Consecutive releases share most of their files. Deduplication replaces identical files of
+//! installed versions with hard links to one copy in a content-addressed store:
+//!
+//! ```text
+//! $GVM_ROOT_PATH/store/<xx>/<sha256>-<mode>
+//! ```
+//!
+//! Removing a version only removes its links, so the other versions keep working; store
+//! entries that no version links to any more are removed by [`Store::prune`]. Hard link
+//! counts are only available on Unix, so deduplication is Unix-only.
+
+use crate::error::GvmError;
+use crate::manifest::MANIFEST_FILE;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+
+/// Store directory under the gvm root
+pub const STORE_DIR: &str = "store";
+
+/// Result of a deduplication pass
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DedupStats {
+    /// Files that were compared
+    pub files: u64,
+    /// Files that were replaced with links to the store
+    pub linked: u64,
+    /// Disk space freed, in bytes
+    pub reclaimed: u64,
+}
+
+/// Apparent and on-disk size of a set of files
+///
+/// Files with several hard links count once towards the on-disk size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DiskUsage {
+    pub apparent: u64,
+    pub on_disk: u64,
+}
+
+/// Order of the versions in `gvm du`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DuSort {
+    /// Oldest version first
+    #[default]
+    Version,
+    /// Largest on-disk size first
+    Size,
+}
+
+/// The content-addressed store
+#[derive(Debug, Clone)]
+pub struct Store {
+    dir: PathBuf,
+}
+
+impl Store {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Replaces the files of the given version directories that have identical copies with
+    /// hard links to the store
+    ///
+    /// Symlinked (external) versions are skipped, as are the gvm install manifests, which are
+    /// rewritten in place.
+    ///
+    /// # Errors
+    /// Returns an error if deduplication is not supported on this platform, or a file cannot
+    /// be read or linked.
+    pub fn dedup(&self, version_dirs: &[PathBuf]) -> Result<DedupStats> {
+        check_supported()?;
+        let dirs: Vec<&Path> = version_dirs
+            .iter()
+            .map(PathBuf::as_path)
+            .filter(|dir| dir.symlink_metadata().is_ok_and(|m| m.is_dir()))
+            .collect();
+        let mut roots = dirs.clone();
+        roots.push(&self.dir);
+        let before = disk_usage(&roots);
+
+        // Files that already are store entries need no hashing
+        let mut stored: HashSet<(u64, u64)> = HashSet::new();
+        walk_files(&self.dir, &mut |_, metadata| {
+            stored.extend(file_id(metadata));
+            Ok(())
+        })?;
+
+        let mut stats = DedupStats::default();
+        for dir in dirs {
+            walk_files(dir, &mut |path, metadata| {
+                if metadata.len() == 0
+                    || path.file_name().is_some_and(|name| name == MANIFEST_FILE)
+                    || file_id(metadata).is_some_and(|id| stored.contains(&id))
+                {
+                    return Ok(());
+                }
+                stats.files += 1;
+                let entry = self.entry_path(path, metadata)?;
+                if entry.exists() {
+                    replace_with_link(&entry, path)?;
+                    stats.linked += 1;
+                } else {
+                    self.add(path, &entry)?;
+                    stored.extend(file_id(metadata));
+                }
+                Ok(())
+            })?;
+        }
+
+        stats.reclaimed = before.on_disk.saturating_sub(disk_usage(&roots).on_disk);
+        Ok(stats)
+    }
+
+    /// Removes store entries that no installed version links to, returning how many were
+    /// removed and their size
+    ///
+    /// # Errors
+    /// Returns an error if the store cannot be read or an entry cannot be removed.
+    pub fn prune(&self) -> Result<(usize, u64)> {
+        let mut pruned = (0, 0);
+        if !self.dir.exists() {
+            return Ok(pruned);
+        }
+        walk_files(&self.dir, &mut |path, metadata| {
+            if link_count(metadata) <= 1 {
+                std::fs::remove_file(path).map_err(GvmError::io("remove", path))?;
+                pruned.0 += 1;
+                pruned.1 += metadata.len();
+            }
+            Ok(())
+        })?;
+        Ok(pruned)
+    }
+
+    /// Gets the files in the store, to compute the usage of several versions with one walk
+    /// of the store
+    pub fn stored_files(&self) -> StoredFiles {
+        let mut stored = HashSet::new();
+        let _ = walk_files(&self.dir, &mut |_, metadata| {
+            stored.extend(file_id(metadata));
+            Ok(())
+        });
+        StoredFiles(stored)
+    }
+
+    /// Gets the store entry for a file from its content and permissions
+    fn entry_path(&self, path: &Path, metadata: &Metadata) -> Result<PathBuf> {
+        let sha256 = crate::downloader::sha256_file(path).map_err(GvmError::io("read", path))?;
+        Ok(self.dir.join(&sha256[..2]).join(format!("{sha256}-{:o}", file_mode(metadata))))
+    }
+
+    fn add(&self, path: &Path, entry: &Path) -> Result<()> {
+        if let Some(parent) = entry.parent() {
+            std::fs::create_dir_all(parent).map_err(GvmError::io("create", parent))?;
+        }
+        std::fs::hard_link(path, entry).with_context(|| {
+            format!(
+                "Failed to link {} into the store {}; hard links need the store and the \
+                 versions on the same file system",
+                path.display(),
+                self.dir.display()
+            )
+        })
+    }
+}
+
+/// Identities of the files in a store, see [`Store::stored_files`]
+#[derive(Debug, Clone, Default)]
+pub struct StoredFiles(HashSet<(u64, u64)>);
+
+impl StoredFiles {
+    /// Gets the usage of a version directory, where `on_disk` is the space that removing it
+    /// frees: files that other versions link to as well are not counted
+    pub fn version_usage(&self, version_dir: &Path) -> DiskUsage {
+        let mut usage = DiskUsage::default();
+        let mut links: HashMap<(u64, u64), (u64, u64, u64)> = HashMap::new();
+        let _ = walk_files(version_dir, &mut |_, metadata| {
+            usage.apparent += metadata.len();
+            match file_id(metadata) {
+                Some(id) => {
+                    links.entry(id).or_insert((0, metadata.len(), link_count(metadata))).0 += 1
+                }
+                None => usage.on_disk += metadata.len(),
+            }
+            Ok(())
+        });
+        for (id, (count, len, nlink)) in links {
+            if nlink <= count + u64::from(self.0.contains(&id)) {
+                usage.on_disk += len;
+            }
+        }
+        usage
+    }
+}
+
+/// Replaces a file with a hard link to a store entry, atomically
+fn replace_with_link(entry: &Path, path: &Path) -> Result<()> {
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    let temp = path.with_file_name(format!(".{name}.gvm-dedup"));
+    let _ = std::fs::remove_file(&temp);
+    std::fs::hard_link(entry, &temp).map_err(GvmError::io("link", &temp))?;
+    if let Err(e) = std::fs::rename(&temp, path) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(GvmError::io("replace", path)(e).into());
+    }
+    Ok(())
+}
+
+/// Gets the apparent and on-disk size of the files under some directories
+pub fn disk_usage(paths: &[&Path]) -> DiskUsage {
+    let mut usage = DiskUsage::default();
+    let mut seen = HashSet::new();
+    for path in paths {
+        let _ = walk_files(path, &mut |_, metadata| {
+            usage.apparent += metadata.len();
+            if file_id(metadata).map_or(true, |id| seen.insert(id)) {
+                usage.on_disk += metadata.len();
+            }
+            Ok(())
+        });
+    }
+    usage
+}
+
+/// Calls `f` for every regular file under a directory, without following symlinks
+fn walk_files(dir: &Path, f: &mut dyn FnMut(&Path, &Metadata) -> Result<()>) -> Result<()> {
+    let Ok(metadata) = dir.symlink_metadata() else {
+        return Ok(());
+    };
+    if metadata.is_file() {
+        return f(dir, &metadata);
+    }
+    if !metadata.is_dir() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir).map_err(GvmError::io("read directory", dir))? {
+        let entry = entry.map_err(GvmError::io("read directory", dir))?;
+        walk_files(&entry.path(), f)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn check_supported() -> Result<()> {
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_supported() -> Result<()> {
+    Err(GvmError::Usage("Deduplication is only supported on Unix".to_string()).into())
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+#[cfg(unix)]
+fn link_count(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.nlink()
+}
+
+#[cfg(not(unix))]
+fn link_count(_metadata: &Metadata) -> u64 {
+    // Without link counts nothing can be proven unused
+    u64::MAX
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn file_mode(metadata: &Metadata) -> u32 {
+    u32::from(metadata.permissions().readonly())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_and_prune() {
+        let root = tempfile::tempdir().unwrap();
+        let versions = root.path().join("versions");
+        for (version, readme) in [("1.22.4", "old"), ("1.22.5", "new")] {
+            let bin = versions.join(version).join("bin");
+            std::fs::create_dir_all(&bin).unwrap();
+            std::fs::write(bin.join("go"), [7u8; 1000]).unwrap();
+            std::fs::write(versions.join(version).join("README"), readme).unwrap();
+            std::fs::write(versions.join(version).join(MANIFEST_FILE), "{}").unwrap();
+        }
+        let dirs = vec![versions.join("1.22.4"), versions.join("1.22.5")];
+        let store = Store::new(root.path().join(STORE_DIR));
+
+        let stats = store.dedup(&dirs).unwrap();
+        assert_eq!(stats, DedupStats { files: 4, linked: 1, reclaimed: 1000 });
+        let usage = disk_usage(&[versions.as_path()]);
+        assert_eq!(usage.apparent - usage.on_disk, 1000);
+        // A second pass finds nothing new
+        assert_eq!(store.dedup(&dirs).unwrap(), DedupStats::default());
+        assert_eq!(
+            store.stored_files().version_usage(&dirs[0]),
+            DiskUsage { apparent: 1005, on_disk: 5 }
+        );
+
+        std::fs::remove_dir_all(&dirs[0]).unwrap();
+        assert_eq!(store.prune().unwrap(), (1, 3));
+        assert_eq!(std::fs::read(dirs[1].join("bin").join("go")).unwrap(), [7u8; 1000]);
+        std::fs::remove_dir_all(&dirs[1]).unwrap();
+        assert_eq!(store.prune().unwrap(), (2, 1003));
+    }
+}
@@ -0,0 +1,556 @@
+//! Environment diagnostics module
+//!
+//! Backs `gvm doctor`: checks the things that commonly make `go version` disagree with
+//! `gvm use` (a broken `current` link, PATH order, a system Go, `GOROOT`, `GOTOOLCHAIN`)
+//! as well as the state of gvm's own directories and download cache. Each problem comes
+//! with a suggestion, and some can be fixed automatically.
+
+use crate::cache::{check_archive, ArchiveCheck, Cache};
+use crate::config::Config;
+use crate::error::GvmError;
+use crate::platform::PlatformInfo;
+use crate::releases::ReleaseIndex;
+use crate::symlink::{is_symlink, read_link, remove_symlink};
+use anyhow::Result;
+use serde::Serialize;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Area of the environment a finding is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    CurrentLink,
+    Path,
+    SystemGo,
+    Goroot,
+    Gotoolchain,
+    Directories,
+    TempDirs,
+    Cache,
+}
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// Automatic fix for a finding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", content = "path", rename_all = "snake_case")]
+pub enum Fix {
+    RemoveLink(PathBuf),
+    RemoveDir(PathBuf),
+    RemoveFile(PathBuf),
+    CreateDir(PathBuf),
+}
+
+impl Fix {
+    /// Applies the fix
+    ///
+    /// # Errors
+    /// Returns an error if the file system operation fails.
+    pub fn apply(&self) -> Result<()> {
+        match self {
+            Self::RemoveLink(path) => remove_symlink(path).map_err(GvmError::io("remove", path)),
+            Self::RemoveDir(path) => {
+                std::fs::remove_dir_all(path).map_err(GvmError::io("remove", path))
+            }
+            Self::RemoveFile(path) => {
+                std::fs::remove_file(path).map_err(GvmError::io("remove", path))
+            }
+            Self::CreateDir(path) => {
+                std::fs::create_dir_all(path).map_err(GvmError::io("create", path))
+            }
+        }?;
+        Ok(())
+    }
+
+    /// Describes the fix
+    pub fn describe(&self) -> String {
+        match self {
+            Self::RemoveLink(path) => format!("remove the link {}", path.display()),
+            Self::RemoveDir(path) => format!("remove {}", path.display()),
+            Self::RemoveFile(path) => format!("delete {}", path.display()),
+            Self::CreateDir(path) => format!("create {}", path.display()),
+        }
+    }
+}
+
+/// Result of a single check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub check: Check,
+    pub severity: Severity,
+    pub message: String,
+    /// What the user can do about it
+    pub suggestion: Option<String>,
+    /// What `--fix` would do about it
+    pub fix: Option<Fix>,
+    /// Whether the fix has been applied
+    pub fixed: bool,
+}
+
+impl Finding {
+    fn ok(check: Check, message: impl Into<String>) -> Self {
+        Self {
+            check,
+            severity: Severity::Ok,
+            message: message.into(),
+            suggestion: None,
+            fix: None,
+            fixed: false,
+        }
+    }
+
+    fn problem(
+        check: Check,
+        severity: Severity,
+        message: impl Into<String>,
+        suggestion: impl Into<String>,
+    ) -> Self {
+        Self {
+            check,
+            severity,
+            message: message.into(),
+            suggestion: Some(suggestion.into()),
+            fix: None,
+            fixed: false,
+        }
+    }
+
+    fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+
+    /// Checks whether this finding is a problem that has not been fixed
+    pub fn is_open_problem(&self) -> bool {
+        self.severity != Severity::Ok && !self.fixed
+    }
+}
+
+/// Environment the checks look at
+#[derive(Debug, Clone, Default)]
+pub struct DoctorEnv {
+    pub path: Option<OsString>,
+    pub goroot: Option<String>,
+    pub gotoolchain: Option<String>,
+    /// Settings file written by `go env -w`
+    pub go_env_file: Option<PathBuf>,
+}
+
+impl DoctorEnv {
+    /// Reads the environment of this process
+    pub fn from_process() -> Self {
+        let go_env_file = std::env::var_os("GOENV")
+            .filter(|v| !v.is_empty() && v != "off")
+            .map(PathBuf::from)
+            .or_else(|| dirs::config_dir().map(|dir| dir.join("go").join("env")));
+        Self {
+            path: std::env::var_os("PATH"),
+            goroot: std::env::var("GOROOT").ok().filter(|v| !v.is_empty()),
+            gotoolchain: std::env::var("GOTOOLCHAIN").ok().filter(|v| !v.is_empty()),
+            go_env_file,
+        }
+    }
+}
+
+/// Runs the environment checks
+pub struct Doctor<'a> {
+    config: &'a Config,
+    env: DoctorEnv,
+}
+
+impl<'a> Doctor<'a> {
+    pub fn new(config: &'a Config, env: DoctorEnv) -> Self {
+        Self { config, env }
+    }
+
+    /// Runs all checks
+    pub fn run(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        findings.push(self.check_current_link());
+        findings.push(self.check_path());
+        findings.extend(self.check_system_go());
+        findings.extend(self.check_goroot());
+        findings.extend(self.check_gotoolchain());
+        findings.extend(self.check_directories());
+        findings.extend(self.check_temp_dirs());
+        findings.extend(self.check_cache());
+        findings
+    }
+
+    fn current_link(&self) -> PathBuf {
+        self.config.versions().join("current")
+    }
+
+    /// Directory that must be on PATH for the active version to be used
+    fn gvm_bin(&self) -> PathBuf {
+        self.current_link().join("bin")
+    }
+
+    fn check_current_link(&self) -> Finding {
+        let link = self.current_link();
+        if !is_symlink(&link) {
+            return if link.exists() {
+                Finding::problem(
+                    Check::CurrentLink,
+                    Severity::Error,
+                    format!("{} is not a link", link.display()),
+                    "Move it out of the way, then run 'gvm use <version>'",
+                )
+            } else {
+                Finding::problem(
+                    Check::CurrentLink,
+                    Severity::Warning,
+                    "No Go version is active",
+                    "Run 'gvm use <version>' to activate one",
+                )
+            };
+        }
+
+        let target = read_link(&link).unwrap_or_default();
+        let go = link.join("bin").join(PlatformInfo::go_executable_name());
+        if !link.exists() || !go.exists() {
+            return Finding::problem(
+                Check::CurrentLink,
+                Severity::Error,
+                format!("The current link points to a missing installation ({})", target.display()),
+                "Run 'gvm use <version>' to activate an installed version",
+            )
+            .with_fix(Fix::RemoveLink(link));
+        }
+
+        Finding::ok(Check::CurrentLink, format!("current -> {}", target.display()))
+    }
+
+    fn check_path(&self) -> Finding {
+        let gvm_bin = self.gvm_bin();
+        let suggestion = path_suggestion(&gvm_bin);
+        let entries: Vec<PathBuf> =
+            self.env.path.as_ref().map(|p| std::env::split_paths(p).collect()).unwrap_or_default();
+
+        let Some(position) = entries.iter().position(|entry| *entry == gvm_bin) else {
+            return Finding::problem(
+                Check::Path,
+                Severity::Error,
+                format!("{} is not on PATH", gvm_bin.display()),
+                suggestion,
+            );
+        };
+
+        let shadowing = entries[..position]
+            .iter()
+            .find(|entry| entry.join(PlatformInfo::go_executable_name()).exists());
+        match shadowing {
+            Some(entry) => Finding::problem(
+                Check::Path,
+                Severity::Error,
+                format!(
+                    "{} comes before {} on PATH, so its go is used instead",
+                    entry.display(),
+                    gvm_bin.display()
+                ),
+                suggestion,
+            ),
+            None => Finding::ok(Check::Path, format!("{} is on PATH", gvm_bin.display())),
+        }
+    }
+
+    fn check_system_go(&self) -> Vec<Finding> {
+        let active = self.current_link().canonicalize().ok();
+        crate::import::discover(self.config.versions())
+            .into_iter()
+            .filter(|install| active.as_ref() != Some(&install.path))
+            .map(|install| {
+                Finding::ok(
+                    Check::SystemGo,
+                    format!(
+                        "System Go {} found in {} ({}); keep {} ahead of it on PATH, or run \
+                         'gvm import' to manage it with gvm",
+                        install.go_version,
+                        install.path.display(),
+                        install.origin,
+                        self.gvm_bin().display()
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    fn check_goroot(&self) -> Option<Finding> {
+        let goroot = self.env.goroot.as_ref()?;
+        let link = self.current_link();
+        let canonical = |path: &Path| path.canonicalize().ok();
+        if Path::new(goroot) == link
+            || canonical(Path::new(goroot)).is_some_and(|p| Some(p) == canonical(&link))
+        {
+            return Some(Finding::ok(Check::Goroot, format!("GOROOT is {goroot}")));
+        }
+
+        Some(Finding::problem(
+            Check::Goroot,
+            Severity::Error,
+            format!("GOROOT is set to {goroot}, which overrides the active version"),
+            format!("Unset GOROOT, or set it to {}", link.display()),
+        ))
+    }
+
+    fn check_gotoolchain(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let overrides = |value: &str| !matches!(value, "local" | "auto");
+
+        if let Some(value) = self.env.gotoolchain.as_deref().filter(|v| overrides(v)) {
+            findings.push(Finding::problem(
+                Check::Gotoolchain,
+                Severity::Warning,
+                format!("GOTOOLCHAIN={value} may run a different toolchain than the active one"),
+                "Unset GOTOOLCHAIN, or set it to 'local'",
+            ));
+        }
+
+        let file_value = self
+            .env
+            .go_env_file
+            .as_deref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| {
+                content.lines().find_map(|line| {
+                    line.trim().strip_prefix("GOTOOLCHAIN=").map(|v| v.trim().to_string())
+                })
+            });
+        if let Some(value) = file_value.filter(|v| overrides(v)) {
+            findings.push(Finding::problem(
+                Check::Gotoolchain,
+                Severity::Warning,
+                format!(
+                    "'go env -w' sets GOTOOLCHAIN={value}, which may override the active toolchain"
+                ),
+                "Run 'go env -u GOTOOLCHAIN'",
+            ));
+        }
+
+        findings
+    }
+
+    fn check_directories(&self) -> Vec<Finding> {
+        let dirs = [
+            ("Root", &self.config.root_path),
+            ("Versions", self.config.versions()),
+            ("Cache", self.config.cache()),
+        ];
+        dirs.into_iter()
+            .map(|(name, dir)| {
+                if !dir.is_dir() {
+                    Finding::problem(
+                        Check::Directories,
+                        Severity::Warning,
+                        format!("{name} directory {} does not exist", dir.display()),
+                        "It is created on the next install",
+                    )
+                    .with_fix(Fix::CreateDir(dir.clone()))
+                } else if !is_writable(dir) {
+                    Finding::problem(
+                        Check::Directories,
+                        Severity::Error,
+                        format!("{name} directory {} is not writable", dir.display()),
+                        "Check the ownership and permissions of the directory",
+                    )
+                } else {
+                    Finding::ok(
+                        Check::Directories,
+                        format!("{name} directory {} is writable", dir.display()),
+                    )
+                }
+            })
+            .collect()
+    }
+
+    fn check_temp_dirs(&self) -> Vec<Finding> {
+        let Ok(entries) = std::fs::read_dir(self.config.versions()) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_dir()
+                    && path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.ends_with(crate::go::TEMP_SUFFIX))
+            })
+            .map(|path| {
+                Finding::problem(
+                    Check::TempDirs,
+                    Severity::Warning,
+                    format!("{} is left over from an interrupted installation", path.display()),
+                    "It can be removed safely",
+                )
+                .with_fix(Fix::RemoveDir(path))
+            })
+            .collect()
+    }
+
+    fn check_cache(&self) -> Vec<Finding> {
+        let cache_dir = self.config.cache();
+        if !cache_dir.is_dir() {
+            return Vec::new();
+        }
+
+        let index_file = ReleaseIndex::cache_file(cache_dir);
+        let index = ReleaseIndex::read_cached(cache_dir).ok();
+        let mut findings = Vec::new();
+        if index.is_none() && index_file.exists() {
+            findings.push(
+                Finding::problem(
+                    Check::Cache,
+                    Severity::Warning,
+                    format!("Cached release index {} is corrupt", index_file.display()),
+                    "It is downloaded again when needed",
+                )
+                .with_fix(Fix::RemoveFile(index_file)),
+            );
+        }
+
+        let archives = Cache::new(cache_dir).archives().unwrap_or_default();
+        for archive in &archives {
+            if let ArchiveCheck::Corrupt(problem) = check_archive(&archive.path, index.as_ref()) {
+                findings.push(
+                    Finding::problem(
+                        Check::Cache,
+                        Severity::Error,
+                        format!("Cached archive {} is corrupt: {problem}", archive.filename),
+                        "Delete it so the next install downloads it again",
+                    )
+                    .with_fix(Fix::RemoveFile(archive.path.clone())),
+                );
+            }
+        }
+
+        if findings.is_empty() {
+            findings.push(Finding::ok(
+                Check::Cache,
+                format!("{} cached archives verified", archives.len()),
+            ));
+        }
+        findings
+    }
+}
+
+/// Suggests how to put the gvm bin directory first on PATH
+fn path_suggestion(gvm_bin: &Path) -> String {
+    if cfg!(target_os = "windows") {
+        format!("Add {} to the start of PATH in the system environment settings", gvm_bin.display())
+    } else {
+        format!(
+            "Add 'export PATH=\"{}:$PATH\"' to the end of your shell profile",
+            gvm_bin.display()
+        )
+    }
+}
+
+/// Checks whether files can be created in a directory
+fn is_writable(dir: &Path) -> bool {
+    tempfile::tempfile_in(dir).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(root: &Path) -> Config {
+        Config {
+            root_path: root.to_path_buf(),
+            versions_path: root.join("versions"),
+            cache_path: root.join("cache"),
+            source_repo: String::new(),
+            mirror: String::new(),
+            store_path: root.join("store"),
+            cache_policy: crate::cache::CachePolicy::default(),
+            dedup_after_install: false,
+            vuln_db: None,
+            release_history_url: String::new(),
+        }
+    }
+
+    fn find(findings: &[Finding], check: Check) -> &Finding {
+        findings.iter().find(|f| f.check == check).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_healthy_environment() {
+        let temp = tempfile::tempdir().unwrap();
+        let config = config(temp.path());
+        let bin = config.versions().join("1.21.0").join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::write(bin.join("go"), "").unwrap();
+        std::fs::create_dir_all(config.cache()).unwrap();
+        crate::symlink::create_symlink(
+            &config.versions().join("1.21.0"),
+            &config.versions().join("current"),
+        )
+        .unwrap();
+
+        let env = DoctorEnv {
+            path: Some(std::env::join_paths([config.versions().join("current/bin")]).unwrap()),
+            goroot: Some(config.versions().join("current").display().to_string()),
+            gotoolchain: Some("local".to_string()),
+            go_env_file: None,
+        };
+        let findings = Doctor::new(&config, env).run();
+        let problems: Vec<_> = findings.iter().filter(|f| f.is_open_problem()).collect();
+        assert!(problems.is_empty(), "{problems:?}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detects_and_fixes_problems() {
+        let temp = tempfile::tempdir().unwrap();
+        let config = config(temp.path());
+        let shadow = temp.path().join("usr-bin");
+        std::fs::create_dir_all(&shadow).unwrap();
+        std::fs::write(shadow.join("go"), "").unwrap();
+        std::fs::create_dir_all(config.versions().join("1.21.0_temp")).unwrap();
+        std::fs::create_dir_all(config.cache()).unwrap();
+        std::fs::write(config.cache().join("go1.21.0.linux-amd64.tar.gz"), "truncated").unwrap();
+        crate::symlink::create_symlink(
+            &config.versions().join("1.20.0"),
+            &config.versions().join("current"),
+        )
+        .unwrap();
+        let go_env = temp.path().join("go-env");
+        std::fs::write(&go_env, "GOPROXY=direct\nGOTOOLCHAIN=go1.22.0\n").unwrap();
+
+        let env = DoctorEnv {
+            path: Some(
+                std::env::join_paths([shadow.clone(), config.versions().join("current/bin")])
+                    .unwrap(),
+            ),
+            goroot: Some("/usr/local/go".to_string()),
+            gotoolchain: None,
+            go_env_file: Some(go_env),
+        };
+        let findings = Doctor::new(&config, env).run();
+
+        assert_eq!(find(&findings, Check::Path).severity, Severity::Error);
+        assert!(find(&findings, Check::Path).message.contains("usr-bin"));
+        assert_eq!(find(&findings, Check::Goroot).severity, Severity::Error);
+        assert!(find(&findings, Check::Gotoolchain).message.contains("go1.22.0"));
+
+        for check in [Check::CurrentLink, Check::TempDirs, Check::Cache] {
+            let finding = find(&findings, check);
+            assert_ne!(finding.severity, Severity::Ok);
+            finding.fix.as_ref().unwrap().apply().unwrap();
+        }
+        assert!(!is_symlink(&config.versions().join("current")));
+        assert!(!config.versions().join("1.21.0_temp").exists());
+        assert!(!config.cache().join("go1.21.0.linux-amd64.tar.gz").exists());
+    }
+}
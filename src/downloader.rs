@@ -102,6 +102,8 @@ pub struct Downloader {
     /// Download configuration (reserved for future extension)
     #[allow(dead_code)]
     config: DownloadConfig,
+    /// Connection budget shared with other downloaders
+    budget: Option<Arc<Semaphore>>,
 }
 
 impl Downloader {
@@ -121,7 +123,14 @@ impl Downloader {
             .connect_timeout(config.connect_timeout)
             .build()
             .unwrap();
-        Self { client, config }
+        Self { client, config, budget: None }
+    }
+
+    /// Limits the connections of this downloader to a budget shared with other downloaders
+    #[must_use]
+    pub fn with_budget(mut self, budget: Arc<Semaphore>) -> Self {
+        self.budget = Some(budget);
+        self
     }
 
     /// Downloads a file (simplified version, without using `indicatif`)
@@ -146,30 +155,17 @@ impl Downloader {
         output_path: impl AsRef<Path>,
         filename: &str,
     ) -> DownloadResult<()> {
-        let output_path = output_path.as_ref();
-        // Get file information
-        let total_size = self.get_file_size(url).await?;
-
         // Create progress bar display
         let progress = BasicProgress::new(format!("Downloading {filename}"));
         let progress_clone = progress.clone();
 
-        // Ensure the output directory exists
-        if let Some(parent) = output_path.parent() {
-            tokio::fs::create_dir_all(parent).await?;
-        }
-
         let callback: ProgressCallback = Box::new(move |downloaded, total| {
             if total > 0 {
                 progress_clone.show_download(downloaded, total);
             }
         });
 
-        if self.config.enable_chunked_download && total_size > self.config.min_chunk_size {
-            self.download_chunked(url, output_path, total_size, Some(callback)).await?;
-        } else {
-            self.download_single_threaded(url, output_path, Some(callback)).await?;
-        }
+        self.download_with_progress(url, output_path, callback).await?;
 
         // Display final message upon completion
         progress.done(&format!("Downloaded {filename}"));
@@ -177,6 +173,39 @@ impl Downloader {
         Ok(())
     }
 
+    /// Downloads a file, reporting progress to a callback
+    pub async fn download_with_progress(
+        &self,
+        url: &str,
+        output_path: impl AsRef<Path>,
+        callback: ProgressCallback,
+    ) -> DownloadResult<()> {
+        let output_path = output_path.as_ref();
+        // Get file information
+        let total_size = self.get_file_size(url).await?;
+
+        // Ensure the output directory exists
+        if let Some(parent) = output_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        if self.config.enable_chunked_download && total_size > self.config.min_chunk_size {
+            self.download_chunked(url, output_path, total_size, Some(callback)).await
+        } else {
+            self.download_single_threaded(url, output_path, Some(callback)).await
+        }
+    }
+
+    /// Acquires a connection from the shared budget, if any
+    async fn acquire_budget(
+        budget: Option<&Arc<Semaphore>>,
+    ) -> Option<tokio::sync::OwnedSemaphorePermit> {
+        match budget {
+            Some(budget) => budget.clone().acquire_owned().await.ok(),
+            None => None,
+        }
+    }
+
     /// Gets the file size
     async fn get_file_size(&self, url: &str) -> DownloadResult<u64> {
         let response = self.client.head(url).send().await?;
@@ -202,6 +231,7 @@ impl Downloader {
             tokio::fs::create_dir_all(parent).await?;
         }
 
+        let _connection = Self::acquire_budget(self.budget.as_ref()).await;
         let response = self.client.get(url).send().await?;
         let file_size = response.content_length().unwrap_or(0);
         let mut file = File::create(output_path).await?;
@@ -222,6 +252,7 @@ impl Downloader {
                 }
             }
         }
+        file.flush().await?;
         Ok(())
     }
 
@@ -231,7 +262,7 @@ impl Downloader {
         url: &str,
         output_path: impl AsRef<Path>,
         total_size: u64,
-        _progress_callback: Option<ProgressCallback>,
+        progress_callback: Option<ProgressCallback>,
     ) -> DownloadResult<()> {
         let output_path = output_path.as_ref();
 
@@ -271,9 +302,6 @@ impl Downloader {
         let semaphore = Arc::new(Semaphore::new(self.config.concurrent_connections));
         let downloaded_bytes = Arc::new(std::sync::atomic::AtomicU64::new(0));
 
-        // Simplified version: no progress callback for now to avoid lifetime issues
-        // TODO: Implement a better progress callback mechanism in a future version
-
         // Concurrently download all chunks
         let download_tasks: Vec<_> = chunks
             .into_iter()
@@ -282,6 +310,7 @@ impl Downloader {
                 let url = url.to_string();
                 let output_path = output_path.to_path_buf();
                 let semaphore = semaphore.clone();
+                let budget = self.budget.clone();
                 let downloaded_bytes = downloaded_bytes.clone();
 
                 let max_retries = self.config.max_retries;
@@ -289,6 +318,7 @@ impl Downloader {
 
                 tokio::spawn(async move {
                     let _permit = semaphore.acquire().await.unwrap();
+                    let _connection = Self::acquire_budget(budget.as_ref()).await;
 
                     for attempt in 1..=max_retries {
                         match Self::download_chunk(
@@ -324,15 +354,31 @@ impl Downloader {
             })
             .collect();
 
-        // Wait for all download tasks to complete
-        let results: Result<Vec<_>, _> = try_join_all(download_tasks)
-            .await
+        // Wait for all download tasks to complete, reporting progress periodically
+        let all_tasks = try_join_all(download_tasks);
+        tokio::pin!(all_tasks);
+        let mut ticker = tokio::time::interval(Duration::from_millis(100));
+        let joined = loop {
+            tokio::select! {
+                joined = &mut all_tasks => break joined,
+                _ = ticker.tick() => {
+                    if let Some(ref callback) = progress_callback {
+                        callback(downloaded_bytes.load(std::sync::atomic::Ordering::Relaxed), total_size);
+                    }
+                }
+            }
+        };
+        let results: Result<Vec<_>, _> = joined
             .map_err(|e| DownloadError::Other(format!("Task join error: {e}")))?
             .into_iter()
             .collect();
 
         results?;
 
+        if let Some(ref callback) = progress_callback {
+            callback(total_size, total_size);
+        }
+
         info!("Chunked download completed: {total_size} bytes in {num_chunks} chunks");
         Ok(())
     }
@@ -376,6 +422,8 @@ impl Downloader {
             // Update the global downloaded byte count
             downloaded_bytes.fetch_add(bytes.len() as u64, std::sync::atomic::Ordering::Relaxed);
         }
+        // Tokio files write in the background; a dropped file may lose its last write
+        file.flush().await?;
 
         if chunk_downloaded != chunk.size {
             Err(DownloadError::ChunkDownloadFailed(format!(
@@ -406,6 +454,49 @@ pub fn format_file_size(bytes: u64) -> String {
     }
 }
 
+/// Connection timeout for small documents such as release indexes
+const FETCH_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Request timeout for small documents such as release indexes
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Fetches a small text document, such as a release index or the vulnerability database,
+/// giving up on unresponsive servers
+///
+/// `what` names the document in error messages.
+///
+/// # Errors
+/// Returns an error if the request fails, times out or returns an error status.
+pub async fn fetch_text(url: &str, what: &str) -> anyhow::Result<String> {
+    use anyhow::Context;
+
+    debug!("Fetching {what} from {url}");
+    let client = Client::builder()
+        .user_agent(DownloadConfig::default().user_agent)
+        .connect_timeout(FETCH_CONNECT_TIMEOUT)
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .context("Failed to create the HTTP client")?;
+    client
+        .get(url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .with_context(|| format!("Failed to fetch {what} from {url}"))?
+        .text()
+        .await
+        .with_context(|| format!("Failed to read {what} from {url}"))
+}
+
+/// Computes the hex-encoded SHA256 checksum of a file
+pub fn sha256_file(path: impl AsRef<Path>) -> std::io::Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hasher.finalize().iter().map(|b| format!("{b:02x}")).collect())
+}
+
 impl Default for Downloader {
     fn default() -> Self {
         Self::new()
@@ -458,6 +549,17 @@ mod tests {
         assert_eq!(format_file_size(1024 * 1024 * 1024), "1.0 GB");
     }
 
+    #[test]
+    fn test_sha256_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data");
+        std::fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
     #[tokio::test]
     async fn test_download_config_validation() {
         let config = DownloadConfig::default();
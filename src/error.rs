@@ -2,6 +2,10 @@
 //!
 //! Provides a unified error type and handling for the project.
 
+use crate::downloader::DownloadError;
+use crate::ui_flat::SimpleUI;
+use std::path::PathBuf;
+
 /// Unified Result type for the project
 pub type Result<T, E = anyhow::Error> = std::result::Result<T, E>;
 
@@ -11,6 +15,200 @@ macro_rules! err {
     ($($arg:tt)*) => { anyhow::anyhow!($($arg)*) };
 }
 
+/// Category of a command failure, mapped to the process exit code
+///
+/// | Code | Kind           | Meaning                                          |
+/// |------|----------------|--------------------------------------------------|
+/// | 0    |                | Success                                          |
+/// | 1    | `General`      | Any other failure                                |
+/// | 2    | `Usage`        | Invalid arguments or version specification       |
+/// | 3    | `NotInstalled` | The requested version is not installed           |
+/// | 4    | `Network`      | Download or release index request failed         |
+/// | 5    | `Checksum`     | Downloaded archive failed checksum verification  |
+/// | 6    | `Permission`   | Permission denied on the file system             |
+/// | 7    | `Conflict`     | Version already installed or currently active    |
+/// | 8    | `Audit`        | Audit found vulnerable or unsupported versions   |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    General,
+    Usage,
+    NotInstalled,
+    Network,
+    Checksum,
+    Permission,
+    Conflict,
+    Audit,
+}
+
+impl FailureKind {
+    /// Gets the process exit code for this kind of failure
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::General => 1,
+            Self::Usage => 2,
+            Self::NotInstalled => 3,
+            Self::Network => 4,
+            Self::Checksum => 5,
+            Self::Permission => 6,
+            Self::Conflict => 7,
+            Self::Audit => 8,
+        }
+    }
+}
+
+/// Errors raised by gvm operations
+///
+/// Each variant has a stable error code, a failure kind that selects the exit code and
+/// usually a hint telling the user what to do next.
+#[derive(Debug, thiserror::Error)]
+pub enum GvmError {
+    #[error("Go version {0} is not installed")]
+    VersionNotInstalled(String),
+    #[error("Bootstrap Go version {0} is not installed")]
+    BootstrapNotInstalled(String),
+    #[error("Cannot uninstall Go {0} as it is currently active")]
+    VersionActive(String),
+    #[error("Go version {0} is already installed")]
+    AlreadyInstalled(String),
+    #[error("Invalid Go version '{0}'")]
+    InvalidVersion(String),
+    #[error("No Go release matches '{0}'")]
+    ReleaseNotFound(String),
+    #[error("Cannot upgrade Go {0}")]
+    NotUpgradable(String),
+    #[error("{0}")]
+    Usage(String),
+    #[error("Checksum mismatch for {file}: expected {expected}, got {actual}")]
+    ChecksumMismatch { file: String, expected: String, actual: String },
+    #[error("Go {version} is not available for {os}/{arch}")]
+    UnsupportedPlatform { version: String, os: String, arch: String },
+    #[error("Download failed")]
+    Download(#[from] DownloadError),
+    #[error("Failed to {action} {}", path.display())]
+    Io {
+        action: &'static str,
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{failed} of {total} installations failed")]
+    BatchFailed { failed: usize, total: usize, kind: FailureKind },
+    #[error("gvm doctor found {0} error(s)")]
+    ProblemsFound(usize),
+    #[error("{0} cached archive(s) are corrupt")]
+    CorruptCache(usize),
+    #[error("Confirmation required, but there is no terminal to ask on")]
+    ConfirmationRequired,
+    #[error("Audit found {vulnerable} vulnerable and {unsupported} unsupported version(s)")]
+    AuditFailed { vulnerable: usize, unsupported: usize },
+    #[error(
+        "Checksum of {file} differs from the lockfile: locked {locked}, {origin} has {actual}"
+    )]
+    LockfileMismatch { file: String, locked: String, actual: String, origin: String },
+}
+
+impl GvmError {
+    /// Creates a `map_err` adapter attaching an action and path to an IO error
+    pub fn io(
+        action: &'static str,
+        path: impl Into<PathBuf>,
+    ) -> impl FnOnce(std::io::Error) -> Self {
+        let path = path.into();
+        move |source| Self::Io { action, path, source }
+    }
+
+    /// Gets the stable error code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::VersionNotInstalled(_) => "version-not-installed",
+            Self::BootstrapNotInstalled(_) => "bootstrap-not-installed",
+            Self::VersionActive(_) => "version-active",
+            Self::AlreadyInstalled(_) => "already-installed",
+            Self::InvalidVersion(_) => "invalid-version",
+            Self::ReleaseNotFound(_) => "release-not-found",
+            Self::NotUpgradable(_) => "not-upgradable",
+            Self::Usage(_) => "usage",
+            Self::ChecksumMismatch { .. } => "checksum-mismatch",
+            Self::UnsupportedPlatform { .. } => "unsupported-platform",
+            Self::Download(_) => "download-failed",
+            Self::Io { .. } => "io-error",
+            Self::BatchFailed { .. } => "batch-failed",
+            Self::ProblemsFound(_) => "problems-found",
+            Self::CorruptCache(_) => "corrupt-cache",
+            Self::ConfirmationRequired => "confirmation-required",
+            Self::AuditFailed { .. } => "audit-failed",
+            Self::LockfileMismatch { .. } => "lockfile-mismatch",
+        }
+    }
+
+    /// Gets the failure kind that determines the exit code
+    pub fn kind(&self) -> FailureKind {
+        match self {
+            Self::VersionNotInstalled(_) | Self::BootstrapNotInstalled(_) => {
+                FailureKind::NotInstalled
+            }
+            Self::VersionActive(_) | Self::AlreadyInstalled(_) => FailureKind::Conflict,
+            Self::InvalidVersion(_)
+            | Self::ReleaseNotFound(_)
+            | Self::NotUpgradable(_)
+            | Self::Usage(_)
+            | Self::ConfirmationRequired => FailureKind::Usage,
+            Self::ChecksumMismatch { .. }
+            | Self::CorruptCache(_)
+            | Self::LockfileMismatch { .. } => FailureKind::Checksum,
+            Self::UnsupportedPlatform { .. } | Self::ProblemsFound(_) => FailureKind::General,
+            Self::AuditFailed { .. } => FailureKind::Audit,
+            Self::Download(DownloadError::Io(source)) | Self::Io { source, .. } => {
+                ErrorUtils::io_failure_kind(source)
+            }
+            Self::Download(_) => FailureKind::Network,
+            Self::BatchFailed { kind, .. } => *kind,
+        }
+    }
+
+    /// Gets a hint on how to resolve the error
+    pub fn hint(&self) -> Option<String> {
+        let hint = match self {
+            Self::VersionNotInstalled(_) => "Use 'gvm list' to see installed versions",
+            Self::BootstrapNotInstalled(_) => "Pass an installed version to --bootstrap",
+            Self::VersionActive(_) => "Switch to another version first with 'gvm use <version>'",
+            Self::AlreadyInstalled(_) => "Use --force to reinstall it",
+            Self::InvalidVersion(_) => {
+                "Use a version such as 1.21.3, a series such as 1.21, or 'latest'"
+            }
+            Self::ReleaseNotFound(_) | Self::UnsupportedPlatform { .. } => {
+                "Use 'gvm list --all' to see available versions"
+            }
+            Self::NotUpgradable(_) => {
+                "Only releases and 'tip' can be upgraded; rebuild source builds with 'gvm install --from-source'"
+            }
+            Self::ChecksumMismatch { .. } => {
+                "The corrupted download was removed; run the command again to retry"
+            }
+            Self::Download(_) => "Check your network connection or set GVM_MIRROR to a mirror",
+            Self::ProblemsFound(_) => {
+                "Follow the suggestions above, or run 'gvm doctor --fix' to fix what can be fixed"
+            }
+            Self::CorruptCache(_) => "Run 'gvm cache verify --remove' to delete them",
+            Self::AuditFailed { .. } => {
+                "Run 'gvm upgrade' to move to the newest patch releases, or install a supported series"
+            }
+            Self::LockfileMismatch { .. } => {
+                "Check that the mirror serves the official archives; if the change is expected, regenerate the lockfile with 'gvm export'"
+            }
+            Self::ConfirmationRequired => {
+                "Pass --yes to proceed without asking, or --dry-run to only show the changes"
+            }
+            Self::Io { source, .. } if source.kind() == std::io::ErrorKind::PermissionDenied => {
+                "Check the permissions of the GVM directories"
+            }
+            Self::Usage(_) | Self::Io { .. } | Self::BatchFailed { .. } => return None,
+        };
+        Some(hint.to_string())
+    }
+}
+
 /// Error handling utility functions
 pub struct ErrorUtils;
 
@@ -41,6 +239,77 @@ impl ErrorUtils {
             format!("Network error: {err}")
         }
     }
+
+    /// Finds the first `GvmError` in an error chain
+    pub fn gvm_error(err: &anyhow::Error) -> Option<&GvmError> {
+        err.downcast_ref::<GvmError>()
+            .or_else(|| err.chain().find_map(|cause| cause.downcast_ref::<GvmError>()))
+    }
+
+    /// Classifies an error by the first recognizable cause in its chain
+    pub fn failure_kind(err: &anyhow::Error) -> FailureKind {
+        if let Some(gvm_error) = Self::gvm_error(err) {
+            return gvm_error.kind();
+        }
+
+        for cause in err.chain() {
+            if let Some(DownloadError::Io(io)) = cause.downcast_ref::<DownloadError>() {
+                return Self::io_failure_kind(io);
+            }
+            if cause.is::<DownloadError>() || cause.is::<reqwest::Error>() {
+                return FailureKind::Network;
+            }
+            if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+                return Self::io_failure_kind(io);
+            }
+        }
+
+        FailureKind::General
+    }
+
+    fn io_failure_kind(err: &std::io::Error) -> FailureKind {
+        match err.kind() {
+            std::io::ErrorKind::PermissionDenied => FailureKind::Permission,
+            _ => FailureKind::General,
+        }
+    }
+
+    /// Gets the stable code of an error, if it is a known gvm error
+    pub fn code(err: &anyhow::Error) -> Option<&'static str> {
+        Self::gvm_error(err).map(GvmError::code)
+    }
+
+    /// Gets the hint for an error, if any
+    pub fn hint(err: &anyhow::Error) -> Option<String> {
+        Self::gvm_error(err).and_then(GvmError::hint)
+    }
+
+    /// Prints an error with its code and hint
+    pub fn report(ui: &SimpleUI, err: &anyhow::Error) {
+        match Self::code(err) {
+            Some(code) => ui.error(&format!("{} [{code}]", Self::message(err))),
+            None => ui.error(&Self::message(err)),
+        }
+        if let Some(hint) = Self::hint(err) {
+            ui.hint(&hint);
+        }
+    }
+
+    /// Renders an error and its causes on one line, skipping causes already included in
+    /// the message before them
+    pub fn message(err: &anyhow::Error) -> String {
+        let mut message = String::new();
+        for cause in err.chain() {
+            let cause = cause.to_string();
+            if !message.ends_with(&cause) {
+                if !message.is_empty() {
+                    message.push_str(": ");
+                }
+                message.push_str(&cause);
+            }
+        }
+        message
+    }
 }
 
 #[cfg(test)]
@@ -56,4 +325,34 @@ mod tests {
         let err = io::Error::new(io::ErrorKind::PermissionDenied, "permission denied");
         assert_eq!(ErrorUtils::io_error_to_message(&err), "Permission denied");
     }
+
+    #[test]
+    fn test_failure_kind() {
+        let err = anyhow::Error::new(GvmError::VersionNotInstalled("1.2.3".to_string()))
+            .context("Failed to switch to Go 1.2.3");
+        assert_eq!(ErrorUtils::failure_kind(&err), FailureKind::NotInstalled);
+        assert_eq!(ErrorUtils::code(&err), Some("version-not-installed"));
+        assert_eq!(
+            ErrorUtils::hint(&err).as_deref(),
+            Some("Use 'gvm list' to see installed versions")
+        );
+
+        let err = anyhow::Error::new(GvmError::Download(DownloadError::FileSize));
+        assert_eq!(ErrorUtils::failure_kind(&err), FailureKind::Network);
+
+        let err = anyhow::Error::new(DownloadError::FileSize).context("Download failed");
+        assert_eq!(ErrorUtils::failure_kind(&err), FailureKind::Network);
+
+        assert_eq!(ErrorUtils::failure_kind(&anyhow::anyhow!("boom")), FailureKind::General);
+        assert_eq!(ErrorUtils::code(&anyhow::anyhow!("boom")), None);
+    }
+
+    #[test]
+    fn test_io_error_context() {
+        let source = io::Error::new(io::ErrorKind::PermissionDenied, "denied");
+        let err = anyhow::Error::new(GvmError::io("remove", "/gvm/versions/1.21.0")(source));
+        assert_eq!(ErrorUtils::failure_kind(&err), FailureKind::Permission);
+        assert_eq!(ErrorUtils::message(&err), "Failed to remove /gvm/versions/1.21.0: denied");
+        assert!(ErrorUtils::hint(&err).is_some());
+    }
 }
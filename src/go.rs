@@ -1,14 +1,22 @@
 // Go version management module
 use crate::{
-    downloader::Downloader,
+    bundle::Bundle,
+    downloader::{Downloader, ProgressCallback},
+    error::GvmError,
+    manifest::{BuildInfo, InstallManifest, InstallMethod, Verification},
+    releases::{GoFile, ReleaseIndex, VersionSpec},
+    reporter::{Event, ProgressThrottle, Reporter, SilentReporter},
+    source::{self, SourceSpec},
     symlink::{create_symlink, is_symlink, read_link, remove_symlink},
-    InstallRequest, ListInstalledRequest, RuntimeStatus, StatusRequest, SwitchRequest,
-    UninstallRequest, VersionList,
+    ImportRequest, InstallRequest, ListInstalledRequest, RuntimeStatus, SourceInstallRequest,
+    StatusRequest, SwitchRequest, UninstallRequest, VersionList,
 };
-use anyhow::{anyhow, Result};
-use log::info;
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
 use std::collections::HashMap;
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Detailed information about a Go version
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
@@ -17,7 +25,7 @@ pub struct GoVersionInfo {
     pub version: String,
     /// Operating system (e.g., "linux", "windows", "darwin")
     pub os: String,
-    /// Architecture (e.g., "amd64", "arm64", "386")
+    /// Architecture (GOARCH, e.g., "amd64", "arm64", "arm")
     pub arch: String,
     /// File extension (e.g., "tar.gz", "zip")
     pub extension: String,
@@ -41,7 +49,9 @@ pub struct GoVersionInfo {
     pub cache_path: Option<PathBuf>,
 }
 
-pub struct GoManager {}
+pub struct GoManager {
+    reporter: Arc<dyn Reporter>,
+}
 
 impl Default for GoManager {
     fn default() -> Self {
@@ -50,49 +60,53 @@ impl Default for GoManager {
 }
 
 impl GoManager {
+    /// Creates a manager that drops its events; see [`GoManager::with_reporter`]
     #[must_use]
     pub fn new() -> Self {
-        Self {}
+        Self::with_reporter(Arc::new(SilentReporter))
     }
 
-    /// Extract archive to specified directory
-    #[cfg(target_os = "windows")]
-    pub fn extract_archive(&self, archive_path: &Path, extract_to: &Path) -> Result<()> {
-        let file = std::fs::File::open(archive_path)?;
-        let mut archive = zip::ZipArchive::new(file)?;
-
-        for i in 0..archive.len() {
-            let mut file = archive.by_index(i)?;
-
-            let outpath = extract_to.join(file.name());
-
-            if file.name().ends_with('/') {
-                std::fs::create_dir_all(&outpath)?;
-            } else {
-                if let Some(p) = outpath.parent() {
-                    if !p.exists() {
-                        std::fs::create_dir_all(p)?;
-                    }
-                }
-                let mut outfile = std::fs::File::create(&outpath)?;
-                std::io::copy(&mut file, &mut outfile)?;
-            }
-        }
+    /// Creates a manager that sends its events to `reporter`
+    #[must_use]
+    pub fn with_reporter(reporter: Arc<dyn Reporter>) -> Self {
+        Self { reporter }
+    }
 
-        Ok(())
+    /// Gets the reporter receiving this manager's events
+    pub fn reporter(&self) -> &Arc<dyn Reporter> {
+        &self.reporter
     }
 
     /// Extract archive to specified directory
-    #[cfg(not(target_os = "windows"))]
     pub fn extract_archive(&self, archive_path: &Path, extract_to: &Path) -> Result<()> {
-        let file = std::fs::File::open(archive_path)?;
-
-        let gz = flate2::read::GzDecoder::new(file);
-        let mut tar = tar::Archive::new(gz);
-
-        tar.unpack(extract_to)?;
+        extract(archive_path, extract_to, |_, _| {})
+    }
 
-        Ok(())
+    /// Extracts an archive on the blocking thread pool, reporting [`Event::ExtractProgress`]
+    /// under `version`
+    async fn extract_reporting(
+        &self,
+        version: &str,
+        archive_path: &Path,
+        extract_to: &Path,
+    ) -> Result<()> {
+        let reporter = self.reporter.clone();
+        let version = version.to_string();
+        let archive_path = archive_path.to_path_buf();
+        let extract_to = extract_to.to_path_buf();
+        run_blocking(move || {
+            let throttle = ProgressThrottle::new();
+            extract(&archive_path, &extract_to, |extracted, total| {
+                if throttle.should_report(extracted, total) {
+                    reporter.report(&Event::ExtractProgress {
+                        version: version.clone(),
+                        extracted,
+                        total,
+                    });
+                }
+            })
+        })
+        .await
     }
 
     /// Switch to a specific Go version
@@ -101,7 +115,7 @@ impl GoManager {
         let current_path = base_dir.join("current");
 
         if !version_path.exists() {
-            return Err(anyhow!("Go version {} is not installed", version));
+            return Err(GvmError::VersionNotInstalled(version.to_string()).into());
         }
 
         // Remove existing symlink if it exists
@@ -113,6 +127,7 @@ impl GoManager {
         create_symlink(&version_path, &current_path)?;
 
         info!("Switched to Go version {version}");
+        self.reporter.report(&Event::Switched { version: version.to_string() });
         Ok(())
     }
 
@@ -152,6 +167,15 @@ impl GoManager {
 
     /// Install Go version
     pub async fn install(&self, request: InstallRequest) -> Result<GoVersionInfo> {
+        self.install_with(request, &Downloader::new()).await
+    }
+
+    /// Install Go version with a specific downloader
+    pub async fn install_with(
+        &self,
+        request: InstallRequest,
+        downloader: &Downloader,
+    ) -> Result<GoVersionInfo> {
         let version = &request.version;
         let install_dir = &request.install_dir;
         let download_dir = &request.download_dir;
@@ -159,46 +183,43 @@ impl GoManager {
         // Determine platform information
         let platform = crate::platform::PlatformInfo::detect();
         let filename = platform.archive_filename(version);
-        let download_url = format!("https://go.dev/dl/{filename}");
+        let (download_url, mirror) = match &request.bundle {
+            Some(bundle) => (bundle.display().to_string(), bundle.display().to_string()),
+            None => (mirror_url(&request.mirror, &filename), request.mirror.clone()),
+        };
         let archive_path = download_dir.join(&filename);
 
-        // Download if not cached
-        if !archive_path.exists() {
-            info!("Downloading Go {version} from {download_url}");
-
-            let downloader = Downloader::new();
-
-            downloader
-                .download_with_simple_progress(&download_url, &archive_path, &filename)
-                .await
-                .map_err(|e| anyhow::anyhow!("Download failed: {}", e))?;
-        }
-
-        // Extract archive
         let version_dir = install_dir.join(version);
-        if version_dir.exists() && !request.force {
-            return Err(anyhow::anyhow!("Go version {} is already installed", version));
+        if is_installed(&version_dir) && !request.force {
+            return Err(GvmError::AlreadyInstalled(version.to_string()).into());
         }
+        self.reporter.report(&Event::InstallStarted { version: version.to_string() });
 
-        if version_dir.exists() {
-            std::fs::remove_dir_all(&version_dir)
-                .map_err(|e| anyhow::anyhow!("Failed to remove existing installation: {}", e))?;
+        // Take the archive from the bundle, or download it if not cached
+        if let Some(bundle) = &request.bundle {
+            let (bundle, version) = (bundle.clone(), version.clone());
+            let (platform, archive_path) = (platform.clone(), archive_path.clone());
+            run_blocking(move || take_from_bundle(&bundle, &version, &platform, &archive_path))
+                .await?;
+        } else if !archive_path.exists() {
+            info!("Downloading Go {version} from {download_url}");
+            self.download_archive(downloader, &download_url, &archive_path, version, None).await?;
         }
 
         // Create a temporary directory for extraction
-        let temp_extract_dir = install_dir.join(format!("{version}_temp"));
+        let temp_extract_dir = install_dir.join(format!("{version}{TEMP_SUFFIX}"));
 
         if temp_extract_dir.exists() {
             std::fs::remove_dir_all(&temp_extract_dir)
-                .map_err(|e| anyhow::anyhow!("Failed to remove temp directory: {}", e))?;
+                .map_err(GvmError::io("remove temp directory", &temp_extract_dir))?;
         }
 
         std::fs::create_dir_all(&temp_extract_dir)
-            .map_err(|e| anyhow::anyhow!("Failed to create temp directory: {}", e))?;
+            .map_err(GvmError::io("create temp directory", &temp_extract_dir))?;
 
         // Extract to the temporary directory
         info!("Extracting archive to {}", temp_extract_dir.display());
-        self.extract_archive(&archive_path, &temp_extract_dir)?;
+        self.extract_reporting(version, &archive_path, &temp_extract_dir).await?;
 
         // The official Go archive extracts into a "go" directory, which we need to rename to the version number
         let extracted_go_dir = temp_extract_dir.join("go");
@@ -208,32 +229,54 @@ impl GoManager {
             return Err(anyhow::anyhow!("Expected 'go' directory not found after extraction"));
         }
 
-        // Rename the 'go' directory to the version directory
-        std::fs::rename(&extracted_go_dir, &version_dir).map_err(|e| {
-            anyhow::anyhow!("Failed to rename go directory to version directory: {}", e)
-        })?;
+        // Keep an existing installation until the new one is verified, so it can be restored
+        let previous = set_aside(&version_dir)?;
+        let installed: Result<()> = async {
+            // Rename the 'go' directory to the version directory
+            std::fs::rename(&extracted_go_dir, &version_dir)
+                .map_err(GvmError::io("move extracted files to", &version_dir))?;
 
-        // Clean up the temporary directory
-        std::fs::remove_dir_all(&temp_extract_dir)
-            .map_err(|e| anyhow::anyhow!("Failed to remove temp directory: {}", e))?;
+            // Verify installation - the Go binary should now be in the bin subdirectory of the version directory
+            let go_binary =
+                version_dir.join("bin").join(crate::platform::PlatformInfo::go_executable_name());
 
-        // Verify installation - the Go binary should now be in the bin subdirectory of the version directory
-        let go_binary =
-            version_dir.join("bin").join(crate::platform::PlatformInfo::go_executable_name());
+            if !go_binary.exists() {
+                return Err(anyhow::anyhow!(
+                    "Go binary not found after extraction at {}",
+                    go_binary.display()
+                ));
+            }
 
-        if !go_binary.exists() {
-            return Err(anyhow::anyhow!(
-                "Go binary not found after extraction at {}",
-                go_binary.display()
-            ));
+            let mut manifest = {
+                let mut manifest = InstallManifest::new(version, InstallMethod::Binary);
+                let (archive_path, download_url) = (archive_path.clone(), download_url.clone());
+                run_blocking(move || {
+                    manifest.set_archive(&archive_path, &download_url, &mirror)?;
+                    Ok(manifest)
+                })
+                .await?
+            };
+            if request.verify {
+                manifest.verification =
+                    Some(self.verify_or_rollback(&version_dir, Some(version), &platform)?);
+            }
+            manifest.write(&version_dir)
         }
+        .await;
+        finish_replace(&version_dir, previous, installed)?;
+
+        // Clean up the temporary directory
+        std::fs::remove_dir_all(&temp_extract_dir)
+            .map_err(GvmError::io("remove temp directory", &temp_extract_dir))?;
 
         info!("Successfully installed Go version {version}");
+        self.reporter
+            .report(&Event::Installed { version: version.to_string(), path: version_dir.clone() });
 
         Ok(GoVersionInfo {
             version: version.to_string(),
-            os: platform.os,
-            arch: platform.arch,
+            os: platform.os.clone(),
+            arch: platform.goarch().to_string(),
             extension: platform.extension,
             filename: filename.clone(),
             download_url,
@@ -247,6 +290,560 @@ impl GoManager {
         })
     }
 
+    /// Downloads a release archive into the download directory unless it is cached,
+    /// verifying it against its published checksum
+    ///
+    /// A cached archive that fails verification is downloaded again.
+    pub async fn fetch_archive(
+        &self,
+        file: &GoFile,
+        mirror: &str,
+        download_dir: &Path,
+        downloader: &Downloader,
+    ) -> Result<PathBuf> {
+        let archive_path = download_dir.join(&file.filename);
+        if archive_path.exists() {
+            if verify_checksum_blocking(&archive_path, &file.sha256).await.is_ok() {
+                return Ok(archive_path);
+            }
+            self.warn(format!("Cached {} is corrupt, downloading it again", file.filename));
+        }
+
+        let url = mirror_url(mirror, &file.filename);
+        info!("Downloading {} from {url}", file.filename);
+        let label = format!("{} {}/{}", file.version.trim_start_matches("go"), file.os, file.arch);
+        self.download_archive(downloader, &url, &archive_path, &label, Some(&file.sha256)).await?;
+        Ok(archive_path)
+    }
+
+    /// Downloads an archive into the cache, verifying it when the checksum is known
+    ///
+    /// Others may read the cache at the same time (e.g. `gvm serve`), so the archive is
+    /// downloaded to a `.part` file and only renamed once it is complete and verified.
+    async fn download_archive(
+        &self,
+        downloader: &Downloader,
+        url: &str,
+        archive_path: &Path,
+        label: &str,
+        expected_sha256: Option<&str>,
+    ) -> Result<()> {
+        let name = archive_path.file_name().unwrap_or_default().to_string_lossy();
+        let part_path = archive_path.with_file_name(format!("{name}.part"));
+        downloader
+            .download_with_progress(url, &part_path, self.download_progress(label))
+            .await
+            .map_err(GvmError::Download)?;
+        if let Some(expected) = expected_sha256 {
+            verify_checksum_blocking(&part_path, expected).await?;
+        }
+        std::fs::rename(&part_path, archive_path).map_err(GvmError::io("rename", &part_path))?;
+        Ok(())
+    }
+
+    /// Creates a download progress callback reporting [`Event::DownloadProgress`] under `version`
+    fn download_progress(&self, version: &str) -> ProgressCallback {
+        let reporter = self.reporter.clone();
+        let version = version.to_string();
+        let throttle = ProgressThrottle::new();
+        Box::new(move |downloaded, total| {
+            if throttle.should_report(downloaded, total) {
+                reporter.report(&Event::DownloadProgress {
+                    version: version.clone(),
+                    downloaded,
+                    total,
+                });
+            }
+        })
+    }
+
+    /// Logs a warning and reports it as [`Event::Warning`]
+    fn warn(&self, message: String) {
+        warn!("{message}");
+        self.reporter.report(&Event::Warning { message });
+    }
+
+    /// Resolve version specifications ("latest", "1.21", "1.21.3") to concrete versions
+    ///
+    /// The release index is only loaded when a specification is not an exact version.
+    /// Duplicates are removed, keeping the first occurrence.
+    pub async fn resolve_versions(
+        &self,
+        specs: &[String],
+        mirror: &str,
+        cache_dir: &Path,
+    ) -> Result<Vec<String>> {
+        let parsed =
+            specs.iter().map(|spec| VersionSpec::parse(spec)).collect::<Result<Vec<_>>>()?;
+        let index = if parsed.iter().any(VersionSpec::needs_index) {
+            Some(ReleaseIndex::load(mirror, cache_dir).await?)
+        } else {
+            None
+        };
+
+        let mut versions: Vec<String> = Vec::new();
+        for (spec, raw) in parsed.into_iter().zip(specs) {
+            let version = match (spec, &index) {
+                (VersionSpec::Exact(version), _) => version,
+                (_, Some(index)) => index.resolve(raw)?,
+                (_, None) => unreachable!("release index is loaded for non-exact versions"),
+            };
+            if !versions.contains(&version) {
+                versions.push(version);
+            }
+        }
+
+        Ok(versions)
+    }
+
+    /// Build and install Go from source
+    pub async fn install_from_source(
+        &self,
+        request: SourceInstallRequest,
+    ) -> Result<GoVersionInfo> {
+        let spec = SourceSpec::parse(&request.source);
+        self.install_source(&request, spec, None).await
+    }
+
+    /// Build and install a development snapshot of the configured git remote
+    ///
+    /// `request.source` is the branch or commit to build; the result is installed as
+    /// `tip-<short commit>`.
+    pub async fn install_tip(&self, request: SourceInstallRequest) -> Result<GoVersionInfo> {
+        // Avoid fetching when the branch head is already installed
+        if !request.force {
+            if let Some(commit) = source::git_ls_remote(&request.git_remote, &request.source)? {
+                let name = tip_version_name(&commit);
+                if request.install_dir.join(&name).exists() {
+                    return Err(GvmError::AlreadyInstalled(name.clone()).into());
+                }
+            }
+        }
+
+        let spec = SourceSpec::GitRef(request.source.clone());
+        self.install_source(&request, spec, Some(TIP_PREFIX)).await
+    }
+
+    /// Gets the version name of the newest tip build of a branch, if it is installed
+    pub fn installed_tip(&self, request: &SourceInstallRequest) -> Result<Option<String>> {
+        Ok(source::git_ls_remote(&request.git_remote, &request.source)?
+            .map(|commit| tip_version_name(&commit))
+            .filter(|name| request.install_dir.join(name).exists()))
+    }
+
+    /// Removes tip builds other than `keep` and the current version
+    ///
+    /// Returns the removed version names.
+    pub fn prune_tip_builds(&self, install_dir: &Path, keep: &str) -> Result<Vec<String>> {
+        let current = self.get_current_version(install_dir);
+        let mut removed = Vec::new();
+
+        for version in
+            self.list_installed(ListInstalledRequest { base_dir: install_dir.into() })?.versions
+        {
+            let name = version.version;
+            if !is_tip_version(&name) || name == keep || current.as_deref() == Some(name.as_str()) {
+                continue;
+            }
+
+            let version_dir = install_dir.join(&name);
+            std::fs::remove_dir_all(&version_dir)
+                .map_err(GvmError::io("remove old tip build", &version_dir))?;
+            info!("Removed old tip build {name}");
+            removed.push(name);
+        }
+
+        Ok(removed)
+    }
+
+    /// Fetches, builds and registers a source tree
+    ///
+    /// With a `commit_prefix`, the build is named `<prefix>-<short commit>`.
+    async fn install_source(
+        &self,
+        request: &SourceInstallRequest,
+        spec: SourceSpec,
+        commit_prefix: Option<&str>,
+    ) -> Result<GoVersionInfo> {
+        let install_dir = &request.install_dir;
+
+        let known_name = request.name.clone().or_else(|| spec.implied_version().map(String::from));
+        if let Some(name) = &known_name {
+            if install_dir.join(name).exists() && !request.force {
+                return Err(GvmError::AlreadyInstalled(name.clone()).into());
+            }
+        }
+
+        let bootstrap = self.resolve_bootstrap(install_dir, request.bootstrap.as_deref())?;
+        self.reporter.report(&Event::InstallStarted {
+            version: known_name.clone().unwrap_or_else(|| request.source.clone()),
+        });
+
+        // Build in a temporary directory and move the result into place afterwards
+        let temp_label = known_name.clone().unwrap_or_else(|| sanitize_name(&request.source));
+        let temp_dir = install_dir.join(format!("{temp_label}{TEMP_SUFFIX}"));
+        if temp_dir.exists() {
+            std::fs::remove_dir_all(&temp_dir)
+                .map_err(GvmError::io("remove temp directory", &temp_dir))?;
+        }
+        std::fs::create_dir_all(&temp_dir)
+            .map_err(GvmError::io("create temp directory", &temp_dir))?;
+
+        let result =
+            self.build_source_tree(request, &spec, commit_prefix, &bootstrap, &temp_dir).await;
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        result
+    }
+
+    /// Fetches, builds and registers a source tree inside `temp_dir`
+    async fn build_source_tree(
+        &self,
+        request: &SourceInstallRequest,
+        spec: &SourceSpec,
+        commit_prefix: Option<&str>,
+        bootstrap: &Path,
+        temp_dir: &Path,
+    ) -> Result<GoVersionInfo> {
+        let mut download_url = String::new();
+        let mut source_archive = None;
+        let mut git_remote = None;
+        let mut git_commit = None;
+
+        let goroot = match spec {
+            SourceSpec::Release(version) => {
+                let filename = source::source_archive_filename(version);
+                let archive_path = request.download_dir.join(&filename);
+                download_url = mirror_url(&request.mirror, &filename);
+                if !archive_path.exists() {
+                    info!("Downloading Go {version} source from {download_url}");
+                    self.download_archive(
+                        &Downloader::new(),
+                        &download_url,
+                        &archive_path,
+                        version,
+                        None,
+                    )
+                    .await?;
+                }
+                self.extract_reporting(version, &archive_path, temp_dir).await?;
+                source_archive = Some(archive_path);
+                source::find_source_root(temp_dir)
+            }
+            SourceSpec::Archive(path) => {
+                self.extract_archive(path, temp_dir)?;
+                source::find_source_root(temp_dir)
+            }
+            SourceSpec::Directory(path) => {
+                let goroot = temp_dir.join("go");
+                source::copy_dir_all(path, &goroot)?;
+                git_commit = source::git_head(&goroot);
+                Some(goroot)
+            }
+            SourceSpec::GitRef(reference) => {
+                let goroot = temp_dir.join("go");
+                git_commit = Some(source::git_fetch(&request.git_remote, reference, &goroot)?);
+                git_remote = Some(request.git_remote.clone());
+                Some(goroot)
+            }
+        }
+        .ok_or_else(|| anyhow!("No Go source tree found in {}", request.source))?;
+
+        // Name the build before running make so existing versions are not rebuilt
+        let go_version = source::read_version_file(&goroot);
+        let name = match commit_prefix {
+            Some(prefix) => {
+                git_commit.as_deref().map(|commit| format!("{prefix}-{}", short_commit(commit)))
+            }
+            None => request
+                .name
+                .clone()
+                .or_else(|| spec.implied_version().map(String::from))
+                .or_else(|| go_version.as_deref().and_then(source::version_name_from_go_version))
+                .or_else(|| {
+                    git_commit.as_deref().map(|commit| format!("devel-{}", short_commit(commit)))
+                }),
+        }
+        .ok_or_else(|| {
+            anyhow!("Cannot determine a version name for this build; pass one with --name")
+        })?;
+
+        let version_dir = request.install_dir.join(&name);
+        if version_dir.exists() && !request.force {
+            return Err(GvmError::AlreadyInstalled(name.clone()).into());
+        }
+
+        source::run_make(&goroot, bootstrap)?;
+
+        let go_binary =
+            goroot.join("bin").join(crate::platform::PlatformInfo::go_executable_name());
+        if !go_binary.exists() {
+            return Err(anyhow!("Go binary not found after build at {}", go_binary.display()));
+        }
+
+        // Keep an existing installation until the new one is verified, so it can be restored
+        let previous = set_aside(&version_dir)?;
+        let platform = crate::platform::PlatformInfo::detect();
+        let installed = (|| {
+            std::fs::rename(&goroot, &version_dir)
+                .map_err(GvmError::io("move build to", &version_dir))?;
+
+            let mut manifest = InstallManifest::new(&name, InstallMethod::Source);
+            if let Some(archive_path) = &source_archive {
+                manifest.set_archive(archive_path, &download_url, &request.mirror)?;
+            }
+            manifest.build = Some(BuildInfo {
+                source: request.source.clone(),
+                git_remote,
+                git_commit,
+                bootstrap: bootstrap.to_path_buf(),
+                go_version,
+            });
+
+            if request.verify {
+                let expected = source::is_release_version(&name).then_some(name.as_str());
+                manifest.verification =
+                    Some(self.verify_or_rollback(&version_dir, expected, &platform)?);
+            }
+            manifest.write(&version_dir)
+        })();
+        finish_replace(&version_dir, previous, installed)?;
+
+        info!("Successfully built Go version {name} from source");
+        self.reporter
+            .report(&Event::Installed { version: name.clone(), path: version_dir.clone() });
+
+        Ok(GoVersionInfo {
+            version: name,
+            os: platform.os.clone(),
+            arch: platform.goarch().to_string(),
+            extension: String::new(),
+            filename: String::new(),
+            download_url,
+            sha256: None,
+            size: None,
+            is_installed: true,
+            is_cached: false,
+            is_current: false,
+            install_path: Some(version_dir),
+            cache_path: None,
+        })
+    }
+
+    /// Runs the toolchain in `version_dir` and checks it reports the expected version and platform
+    ///
+    /// `expected_version` is skipped for builds without a release version (e.g. tip).
+    pub fn verify_installation(
+        &self,
+        version_dir: &Path,
+        expected_version: Option<&str>,
+        platform: &crate::platform::PlatformInfo,
+    ) -> Result<Verification> {
+        let go_binary =
+            version_dir.join("bin").join(crate::platform::PlatformInfo::go_executable_name());
+        let run_go = |args: &[&str]| -> Result<String> {
+            let output = std::process::Command::new(&go_binary)
+                .args(args)
+                .current_dir(version_dir)
+                .env_remove("GOROOT")
+                .env("GOTOOLCHAIN", "local")
+                .output()
+                .with_context(|| format!("Failed to run {}", go_binary.display()))?;
+            if !output.status.success() {
+                return Err(anyhow!(
+                    "'go {}' failed: {}",
+                    args.join(" "),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        };
+
+        // e.g. "go version go1.21.3 linux/amd64"
+        let go_version = run_go(&["version"])?;
+        let fields: Vec<&str> = go_version.split_whitespace().collect();
+        if let Some(expected) = expected_version {
+            let expected = format!("go{expected}");
+            if fields.get(2) != Some(&expected.as_str()) {
+                return Err(anyhow!(
+                    "'go version' reported '{}', expected {}",
+                    go_version,
+                    expected
+                ));
+            }
+        }
+
+        let env = run_go(&["env", "GOROOT", "GOOS", "GOARCH"])?;
+        let mut lines = env.lines().map(str::trim);
+        let (goroot, goos, goarch) = match (lines.next(), lines.next(), lines.next()) {
+            (Some(goroot), Some(goos), Some(goarch)) => (goroot, goos, goarch),
+            _ => return Err(anyhow!("Unexpected 'go env' output: {}", env)),
+        };
+
+        if goos != platform.os || goarch != platform.goarch() {
+            return Err(anyhow!(
+                "Toolchain targets {}/{}, expected {}/{}",
+                goos,
+                goarch,
+                platform.os,
+                platform.goarch()
+            ));
+        }
+
+        let same_root = match (Path::new(goroot).canonicalize(), version_dir.canonicalize()) {
+            (Ok(reported), Ok(expected)) => reported == expected,
+            _ => false,
+        };
+        if !same_root {
+            return Err(anyhow!(
+                "Toolchain reports GOROOT {}, expected {}",
+                goroot,
+                version_dir.display()
+            ));
+        }
+
+        info!("Verified {go_version} in {}", version_dir.display());
+        Ok(Verification {
+            go_version,
+            goroot: goroot.to_string(),
+            goos: goos.to_string(),
+            goarch: goarch.to_string(),
+            verified_at: chrono::Utc::now(),
+        })
+    }
+
+    /// Verifies a fresh installation, removing it if verification fails
+    ///
+    /// The caller restores the installation it replaced, see [`finish_replace`].
+    fn verify_or_rollback(
+        &self,
+        version_dir: &Path,
+        expected_version: Option<&str>,
+        platform: &crate::platform::PlatformInfo,
+    ) -> Result<Verification> {
+        let verification =
+            self.verify_installation(version_dir, expected_version, platform).map_err(|e| {
+                let _ = std::fs::remove_dir_all(version_dir);
+                anyhow!("Verification failed, installation rolled back: {}", e)
+            })?;
+        let version = version_dir.file_name().unwrap_or_default().to_string_lossy();
+        self.reporter.report(&Event::Verified {
+            version: version.into_owned(),
+            go_version: verification.go_version.clone(),
+        });
+        Ok(verification)
+    }
+
+    /// Resolves the toolchain used as `GOROOT_BOOTSTRAP`
+    ///
+    /// Priority: requested version -> `GOROOT_BOOTSTRAP` environment variable -> current
+    /// version -> newest installed version
+    fn resolve_bootstrap(&self, install_dir: &Path, requested: Option<&str>) -> Result<PathBuf> {
+        let has_go = |dir: &Path| {
+            dir.join("bin").join(crate::platform::PlatformInfo::go_executable_name()).exists()
+        };
+
+        if let Some(version) = requested {
+            let dir = install_dir.join(version);
+            return if has_go(&dir) {
+                Ok(dir)
+            } else {
+                Err(GvmError::BootstrapNotInstalled(version.to_string()).into())
+            };
+        }
+
+        if let Some(dir) = std::env::var_os("GOROOT_BOOTSTRAP").filter(|v| !v.is_empty()) {
+            return Ok(PathBuf::from(dir));
+        }
+
+        let current = self.get_current_version(install_dir).map(|v| install_dir.join(v));
+        let installed = self
+            .list_installed(ListInstalledRequest { base_dir: install_dir.to_path_buf() })?
+            .versions
+            .into_iter()
+            .rev()
+            .filter_map(|v| v.install_path);
+
+        current.into_iter().chain(installed).find(|dir| has_go(dir)).ok_or_else(|| {
+            anyhow!("No installed Go version available to bootstrap the build; install one first")
+        })
+    }
+
+    /// Registers a Go installation made outside of gvm as an installed version
+    ///
+    /// The installation is referenced in place through a link, or copied into the install
+    /// directory with `request.copy`. Referenced installations are never deleted by gvm.
+    pub fn import(&self, request: ImportRequest) -> Result<GoVersionInfo> {
+        let source =
+            request.source.canonicalize().map_err(GvmError::io("read", &request.source))?;
+        let system = crate::import::inspect(&source, "import")
+            .ok_or_else(|| anyhow!("{} is not a Go installation", source.display()))?;
+        let name = request.name.clone().or(system.version).ok_or_else(|| {
+            anyhow!(
+                "Cannot determine a version name for {}; pass one with --name",
+                system.go_version
+            )
+        })?;
+
+        let install_dir = &request.install_dir;
+        let version_dir = install_dir.join(&name);
+        if is_installed(&version_dir) {
+            if !request.force {
+                return Err(GvmError::AlreadyInstalled(name).into());
+            }
+            remove_version_dir(&version_dir)
+                .map_err(GvmError::io("remove existing installation", &version_dir))?;
+        }
+        std::fs::create_dir_all(install_dir)
+            .map_err(GvmError::io("create directory", install_dir))?;
+
+        let method = if request.copy {
+            let temp_dir = install_dir.join(format!("{name}{TEMP_SUFFIX}"));
+            if temp_dir.exists() {
+                std::fs::remove_dir_all(&temp_dir)
+                    .map_err(GvmError::io("remove temp directory", &temp_dir))?;
+            }
+            let copied = source::copy_dir_all(&source, &temp_dir).and_then(|()| {
+                std::fs::rename(&temp_dir, &version_dir)
+                    .map_err(GvmError::io("move copied files to", &version_dir))?;
+                Ok(())
+            });
+            if copied.is_err() {
+                let _ = std::fs::remove_dir_all(&temp_dir);
+            }
+            copied?;
+            InstallMethod::Imported
+        } else {
+            create_symlink(&source, &version_dir)
+                .map_err(GvmError::io("create link", &version_dir))?;
+            InstallMethod::External
+        };
+
+        let mut manifest = InstallManifest::new(&name, method);
+        manifest.imported_from = Some(source.clone());
+        manifest.write(&version_dir)?;
+
+        info!("Imported Go {name} from {}", source.display());
+        self.reporter
+            .report(&Event::Installed { version: name.clone(), path: version_dir.clone() });
+
+        let platform = crate::platform::PlatformInfo::detect();
+        Ok(GoVersionInfo {
+            version: name,
+            os: platform.os.clone(),
+            arch: platform.goarch().to_string(),
+            extension: String::new(),
+            filename: String::new(),
+            download_url: String::new(),
+            sha256: None,
+            size: None,
+            is_installed: true,
+            is_cached: false,
+            is_current: false,
+            install_path: Some(version_dir),
+            cache_path: None,
+        })
+    }
+
     /// Switch to a version
     pub fn switch_to(&self, request: SwitchRequest) -> Result<()> {
         self.switch_version(&request.version, &request.base_dir)
@@ -258,8 +855,8 @@ impl GoManager {
         let base_dir = &request.base_dir;
         let version_path = base_dir.join(version);
 
-        if !version_path.exists() {
-            return Err(anyhow::anyhow!("Go version {} is not installed", version));
+        if !is_installed(&version_path) {
+            return Err(GvmError::VersionNotInstalled(version.to_string()).into());
         }
 
         // Check if this is the current version
@@ -267,17 +864,14 @@ impl GoManager {
         if current_path.exists() && is_symlink(&current_path) {
             if let Ok(target) = read_link(&current_path) {
                 if target == version_path {
-                    return Err(anyhow::anyhow!(
-                        "Cannot uninstall Go {} as it is currently active. Please switch to another version first.",
-                        version
-                    ));
+                    return Err(GvmError::VersionActive(version.to_string()).into());
                 }
             }
         }
 
-        // Remove the version directory
-        std::fs::remove_dir_all(&version_path)
-            .map_err(|e| anyhow::anyhow!("Failed to remove version directory: {}", e))?;
+        // Remove the version directory; external versions are only unregistered
+        remove_version_dir(&version_path)
+            .map_err(GvmError::io("remove version directory", &version_path))?;
 
         info!("Successfully uninstalled Go version {version}");
         Ok(())
@@ -293,22 +887,22 @@ impl GoManager {
         }
 
         let current_version = self.get_current_version(base_dir);
+        let platform = crate::platform::PlatformInfo::detect();
 
-        for entry in std::fs::read_dir(base_dir)
-            .map_err(|e| anyhow::anyhow!("Failed to read directory: {}", e))?
+        for entry in
+            std::fs::read_dir(base_dir).map_err(GvmError::io("read directory", base_dir))?
         {
-            let entry =
-                entry.map_err(|e| anyhow::anyhow!("Failed to read directory entry: {}", e))?;
+            let entry = entry.map_err(GvmError::io("read directory", base_dir))?;
             let path = entry.path();
 
             if path.is_dir() && path.file_name().is_some() {
                 if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    if name != "current" {
+                    if is_version_dir_name(name) {
                         let is_current = current_version.as_ref().is_some_and(|cv| cv == name);
-                        versions.push(GoVersionInfo {
+                        let mut version_info = GoVersionInfo {
                             version: name.to_string(),
-                            os: std::env::consts::OS.to_string(),
-                            arch: std::env::consts::ARCH.to_string(),
+                            os: platform.os.clone(),
+                            arch: platform.goarch().to_string(),
                             extension: String::new(),
                             filename: String::new(),
                             download_url: String::new(),
@@ -319,86 +913,22 @@ impl GoManager {
                             is_current,
                             install_path: Some(path.clone()),
                             cache_path: None,
-                        });
+                        };
+                        if let Some(manifest) = read_manifest(&path) {
+                            version_info.apply_manifest(&manifest);
+                        }
+                        versions.push(version_info);
                     }
                 }
             }
         }
 
-        versions.sort();
+        versions.sort_by(|a, b| crate::version::compare_versions(&a.version, &b.version));
         let total_count = versions.len();
 
         Ok(VersionList { versions, total_count })
     }
 
-    /// List available versions
-    pub fn list_available(&self) -> Result<VersionList> {
-        // This is a simplified implementation
-        // In a real implementation, you would fetch the list from Go's official API
-        
-        // 获取当前版本以便标记
-        let base_dir = dirs::home_dir().unwrap_or_else(|| std::path::PathBuf::from(".")).join(".gvm").join("versions");
-        let current_version = self.get_current_version(&base_dir);
-        
-        let mut versions = vec![
-            GoVersionInfo {
-                version: "1.21.3".to_string(),
-                os: "linux".to_string(),
-                arch: "amd64".to_string(),
-                extension: "tar.gz".to_string(),
-                filename: "go1.21.3.linux-amd64.tar.gz".to_string(),
-                download_url: String::new(),
-                sha256: None,
-                size: None,
-                is_installed: false,
-                is_cached: false,
-                is_current: false,
-                install_path: None,
-                cache_path: None,
-            },
-            GoVersionInfo {
-                version: "1.21.2".to_string(),
-                os: "linux".to_string(),
-                arch: "amd64".to_string(),
-                extension: "tar.gz".to_string(),
-                filename: "go1.21.2.linux-amd64.tar.gz".to_string(),
-                download_url: String::new(),
-                sha256: None,
-                size: None,
-                is_installed: false,
-                is_cached: false,
-                is_current: false,
-                install_path: None,
-                cache_path: None,
-            },
-            GoVersionInfo {
-                version: "1.21.1".to_string(),
-                os: "linux".to_string(),
-                arch: "amd64".to_string(),
-                extension: "tar.gz".to_string(),
-                filename: "go1.21.1.linux-amd64.tar.gz".to_string(),
-                download_url: String::new(),
-                sha256: None,
-                size: None,
-                is_installed: false,
-                is_cached: false,
-                is_current: false,
-                install_path: None,
-                cache_path: None,
-            },
-        ];
-        
-        // 标记当前版本
-        if let Some(ref current) = current_version {
-            for version in &mut versions {
-                version.is_current = version.version == *current;
-            }
-        }
-
-        let total_count = versions.len();
-        Ok(VersionList { versions, total_count })
-    }
-
     /// Get status
     pub fn status(&self, request: StatusRequest) -> Result<RuntimeStatus> {
         let base_dir = request.base_dir.unwrap_or_else(|| {
@@ -434,6 +964,7 @@ impl GoManager {
             current_version,
             current_path: self.get_link_target(&base_dir).map(|p| p.display().to_string()),
             environment_vars,
+            audit: None,
         })
     }
 
@@ -443,18 +974,22 @@ impl GoManager {
         version: &str,
         install_dir: &Path,
         cache_dir: &Path,
+        mirror: &str,
     ) -> Result<GoVersionInfo> {
         let platform = crate::platform::PlatformInfo::detect();
         let filename = platform.archive_filename(version);
-        let download_url = format!("https://go.dev/dl/{filename}");
+        let download_url = mirror_url(mirror, &filename);
 
         let install_path = install_dir.join(version);
-        let cache_path = cache_dir.join(&filename);
+        let manifest = if install_path.exists() { read_manifest(&install_path) } else { None };
+        let cache_path = cache_dir.join(
+            manifest.as_ref().and_then(|m| m.filename.as_deref()).unwrap_or(filename.as_str()),
+        );
 
-        Ok(GoVersionInfo {
+        let mut version_info = GoVersionInfo {
             version: version.to_string(),
-            os: platform.os,
-            arch: platform.arch,
+            os: platform.os.clone(),
+            arch: platform.goarch().to_string(),
             extension: platform.extension,
             filename: filename.clone(),
             download_url,
@@ -465,6 +1000,742 @@ impl GoManager {
             is_current: false, // 这个方法不检查当前版本状态
             install_path: if install_path.exists() { Some(install_path) } else { None },
             cache_path: if cache_path.exists() { Some(cache_path) } else { None },
-        })
+        };
+        if let Some(manifest) = &manifest {
+            version_info.apply_manifest(manifest);
+        }
+
+        Ok(version_info)
+    }
+}
+
+impl GoVersionInfo {
+    /// Fills platform and archive details from an installation manifest
+    pub fn apply_manifest(&mut self, manifest: &InstallManifest) {
+        if !manifest.os.is_empty() {
+            self.os = manifest.os.clone();
+        }
+        if !manifest.arch.is_empty() {
+            self.arch = manifest.arch.clone();
+        }
+        if let Some(filename) = &manifest.filename {
+            self.filename = filename.clone();
+            self.extension = archive_extension(filename).to_string();
+        }
+        if let Some(source_url) = &manifest.source_url {
+            self.download_url = source_url.clone();
+        }
+        self.sha256 = manifest.sha256.clone();
+        self.size = manifest.size;
+    }
+}
+
+/// Checks whether a version directory is present, including links to missing external versions
+fn is_installed(version_dir: &Path) -> bool {
+    version_dir.exists() || is_symlink(version_dir)
+}
+
+/// Moves an existing installation aside so that it can be restored if its replacement fails
+fn set_aside(version_dir: &Path) -> Result<Option<PathBuf>> {
+    if !is_installed(version_dir) {
+        return Ok(None);
+    }
+    let name = version_dir.file_name().unwrap_or_default().to_string_lossy();
+    let previous = version_dir.with_file_name(format!("{name}{PREVIOUS_SUFFIX}"));
+    if is_installed(&previous) {
+        remove_version_dir(&previous).map_err(GvmError::io("remove", &previous))?;
+    }
+    std::fs::rename(version_dir, &previous)
+        .map_err(GvmError::io("move aside existing installation", version_dir))?;
+    Ok(Some(previous))
+}
+
+/// Finishes replacing an installation moved aside by [`set_aside`]: the previous
+/// installation is removed if the replacement succeeded, and restored if it failed
+fn finish_replace<T>(
+    version_dir: &Path,
+    previous: Option<PathBuf>,
+    result: Result<T>,
+) -> Result<T> {
+    let Some(previous) = previous else {
+        return result;
+    };
+    if result.is_ok() {
+        remove_version_dir(&previous)
+            .map_err(GvmError::io("remove previous installation", &previous))?;
+    } else {
+        if is_installed(version_dir) {
+            let _ = remove_version_dir(version_dir);
+        }
+        std::fs::rename(&previous, version_dir)
+            .map_err(GvmError::io("restore previous installation", version_dir))?;
+    }
+    result
+}
+
+/// Removes an installed version; external versions are unlinked without touching their files
+fn remove_version_dir(version_dir: &Path) -> std::io::Result<()> {
+    if !is_symlink(version_dir) {
+        return std::fs::remove_dir_all(version_dir);
+    }
+    let manifest = InstallManifest::path(version_dir);
+    if manifest.exists() {
+        std::fs::remove_file(manifest)?;
+    }
+    remove_symlink(version_dir)
+}
+
+/// Reads the installation manifest of a version directory, ignoring unreadable manifests
+fn read_manifest(version_dir: &Path) -> Option<InstallManifest> {
+    InstallManifest::read(version_dir)
+        .map_err(|e| warn!("Ignoring install manifest in {}: {}", version_dir.display(), e))
+        .ok()
+        .flatten()
+}
+
+/// Gets the archive extension of a Go download filename
+fn archive_extension(filename: &str) -> &str {
+    if filename.ends_with(".tar.gz") {
+        "tar.gz"
+    } else {
+        filename.rsplit_once('.').map_or("", |(_, extension)| extension)
+    }
+}
+
+/// Default base URL for Go downloads
+pub const DEFAULT_MIRROR: &str = "https://go.dev/dl";
+
+/// Copies a version's archive for this platform out of a bundle into the cache
+///
+/// A cached copy is only used if it matches the bundled one.
+fn take_from_bundle(
+    bundle: &Path,
+    version: &str,
+    platform: &crate::platform::PlatformInfo,
+    archive_path: &Path,
+) -> Result<()> {
+    let bundle = Bundle::open(bundle)?;
+    let archive =
+        bundle.manifest().archive_for(version, &platform.os, &platform.arch).ok_or_else(|| {
+            GvmError::UnsupportedPlatform {
+                version: version.to_string(),
+                os: platform.os.clone(),
+                arch: platform.arch.clone(),
+            }
+        })?;
+    if !archive_path.exists() || verify_checksum(archive_path, &archive.sha256).is_err() {
+        info!("Extracting {} from bundle {}", archive.filename, bundle.path().display());
+        bundle.extract_archive(archive, archive_path)?;
+    }
+    Ok(())
+}
+
+/// Verifies an archive against its expected SHA256, removing it on mismatch
+fn verify_checksum(archive_path: &Path, expected: &str) -> Result<()> {
+    let actual = crate::downloader::sha256_file(archive_path)
+        .map_err(GvmError::io("checksum", archive_path))?;
+    if actual.eq_ignore_ascii_case(expected) {
+        return Ok(());
+    }
+
+    let _ = std::fs::remove_file(archive_path);
+    Err(GvmError::ChecksumMismatch {
+        file: archive_path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        expected: expected.to_string(),
+        actual,
+    }
+    .into())
+}
+
+/// Runs blocking file work, like extraction or checksumming, on the blocking thread pool so
+/// concurrent installs don't stall the runtime's workers
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tokio::task::spawn_blocking(f).await.context("Blocking task failed")?
+}
+
+/// Runs [`verify_checksum`] on the blocking thread pool
+async fn verify_checksum_blocking(archive_path: &Path, expected: &str) -> Result<()> {
+    let archive_path = archive_path.to_path_buf();
+    let expected = expected.to_string();
+    run_blocking(move || verify_checksum(&archive_path, &expected)).await
+}
+
+/// Extracts an archive, calling `progress` with the entries done and the entry count
+#[cfg(target_os = "windows")]
+fn extract(archive_path: &Path, extract_to: &Path, progress: impl Fn(u64, u64)) -> Result<()> {
+    let file = std::fs::File::open(archive_path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    let total = archive.len() as u64;
+    progress(0, total);
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+
+        let outpath = extract_to.join(file.name());
+
+        if file.name().ends_with('/') {
+            std::fs::create_dir_all(&outpath)?;
+        } else {
+            if let Some(p) = outpath.parent() {
+                if !p.exists() {
+                    std::fs::create_dir_all(p)?;
+                }
+            }
+            let mut outfile = std::fs::File::create(&outpath)?;
+            std::io::copy(&mut file, &mut outfile)?;
+        }
+        progress(i as u64 + 1, total);
+    }
+
+    Ok(())
+}
+
+/// Extracts an archive, calling `progress` with the compressed bytes read and the archive size
+#[cfg(not(target_os = "windows"))]
+fn extract(archive_path: &Path, extract_to: &Path, progress: impl Fn(u64, u64)) -> Result<()> {
+    let file = std::fs::File::open(archive_path)?;
+    let total = file.metadata()?.len();
+    progress(0, total);
+
+    let reader = CountingReader { inner: file, count: 0, total, progress: &progress };
+    let gz = flate2::read::GzDecoder::new(reader);
+    let mut tar = tar::Archive::new(gz);
+
+    tar.unpack(extract_to)?;
+    // The tar reader may stop before the gzip trailer
+    progress(total, total);
+
+    Ok(())
+}
+
+/// Reader that reports how much of its input has been read
+#[cfg(not(target_os = "windows"))]
+struct CountingReader<'a, R, F> {
+    inner: R,
+    count: u64,
+    total: u64,
+    progress: &'a F,
+}
+
+#[cfg(not(target_os = "windows"))]
+impl<R: Read, F: Fn(u64, u64)> Read for CountingReader<'_, R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        (self.progress)(self.count.min(self.total), self.total);
+        Ok(n)
+    }
+}
+
+/// Builds the download URL of a file on a mirror
+pub fn mirror_url(mirror: &str, filename: &str) -> String {
+    format!("{}/{filename}", mirror.trim_end_matches('/'))
+}
+
+/// Version name prefix of development snapshots
+pub const TIP_PREFIX: &str = "tip";
+
+/// Suffix of the directories archives are extracted and sources are built in
+pub const TEMP_SUFFIX: &str = "_temp";
+
+/// Suffix of an installation moved aside while it is being replaced
+pub const PREVIOUS_SUFFIX: &str = "_previous";
+
+/// Checks whether an entry of the versions directory is an installed version rather than
+/// the `current` link, a hidden entry, or a work directory left by an interrupted install
+pub fn is_version_dir_name(name: &str) -> bool {
+    name != "current"
+        && !name.starts_with('.')
+        && !name.ends_with(TEMP_SUFFIX)
+        && !name.ends_with(PREVIOUS_SUFFIX)
+}
+
+/// Branch built by `gvm install tip` unless a commit is given
+pub const TIP_DEFAULT_REF: &str = "master";
+
+/// Shortens a git commit hash for display and naming
+pub fn short_commit(commit: &str) -> &str {
+    &commit[..commit.len().min(12)]
+}
+
+/// Gets the version name of a tip build
+pub fn tip_version_name(commit: &str) -> String {
+    format!("{TIP_PREFIX}-{}", short_commit(commit))
+}
+
+/// Checks whether a version name is a tip build
+pub fn is_tip_version(version: &str) -> bool {
+    version.strip_prefix(TIP_PREFIX).is_some_and(|rest| rest.starts_with('-'))
+}
+
+/// Turns an arbitrary source specification into a safe directory name
+fn sanitize_name(spec: &str) -> String {
+    spec.chars().map(|c| if c.is_ascii_alphanumeric() || c == '.' { c } else { '-' }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a fake Go source tree whose `make.bash` produces a stub `bin/go`
+    #[cfg(unix)]
+    fn fake_source_tree(root: &Path, version_line: &str) {
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("VERSION"), format!("{version_line}\n")).unwrap();
+        std::fs::write(
+            root.join("src").join("make.bash"),
+            "test -x \"$GOROOT_BOOTSTRAP/bin/go\" || exit 1\n\
+             mkdir -p ../bin && printf '#!/bin/sh\\necho go\\n' > ../bin/go && chmod +x ../bin/go\n",
+        )
+        .unwrap();
+    }
+
+    /// Creates a stub installed version usable as a bootstrap toolchain
+    #[cfg(unix)]
+    fn fake_installed_version(install_dir: &Path, version: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        let bin = install_dir.join(version).join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::write(bin.join("go"), "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(bin.join("go"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    fn source_request(source: &Path, install_dir: &Path) -> SourceInstallRequest {
+        SourceInstallRequest {
+            source: source.display().to_string(),
+            name: None,
+            bootstrap: Some("1.21.0".to_string()),
+            git_remote: source::DEFAULT_SOURCE_REPO.to_string(),
+            install_dir: install_dir.to_path_buf(),
+            download_dir: install_dir.join("cache"),
+            force: false,
+            verify: false,
+            mirror: DEFAULT_MIRROR.to_string(),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_install_from_source_directory() {
+        let temp = tempfile::tempdir().unwrap();
+        let install_dir = temp.path().join("versions");
+        let source_dir = temp.path().join("go-src");
+        fake_installed_version(&install_dir, "1.21.0");
+        fake_source_tree(&source_dir, "go1.99.1");
+
+        let manager = GoManager::new();
+        let info =
+            manager.install_from_source(source_request(&source_dir, &install_dir)).await.unwrap();
+
+        assert_eq!(info.version, "1.99.1");
+        let version_dir = install_dir.join("1.99.1");
+        assert!(version_dir.join("bin").join("go").exists());
+        let mut entries: Vec<_> = std::fs::read_dir(&install_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, ["1.21.0", "1.99.1"]);
+
+        let manifest = InstallManifest::read(&version_dir).unwrap().unwrap();
+        assert_eq!(manifest.install_method, InstallMethod::Source);
+        let build = manifest.build.unwrap();
+        assert_eq!(build.bootstrap, install_dir.join("1.21.0"));
+        assert_eq!(build.go_version.as_deref(), Some("go1.99.1"));
+
+        // A second build of the same version requires --force
+        let err = manager.install_from_source(source_request(&source_dir, &install_dir)).await;
+        assert!(err.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_install_from_source_archive() {
+        let temp = tempfile::tempdir().unwrap();
+        let install_dir = temp.path().join("versions");
+        let tree = temp.path().join("tree");
+        fake_installed_version(&install_dir, "1.21.0");
+        fake_source_tree(&tree.join("go"), "go1.99.2");
+
+        let archive = temp.path().join("go1.99.2.src.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&archive).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        builder.append_dir_all("go", tree.join("go")).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let mut request = source_request(&archive, &install_dir);
+        request.name = Some("custom".to_string());
+        let info = GoManager::new().install_from_source(request).await.unwrap();
+
+        assert_eq!(info.version, "custom");
+        assert!(install_dir.join("custom").join("bin").join("go").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_install_from_source_git_ref() {
+        let temp = tempfile::tempdir().unwrap();
+        let install_dir = temp.path().join("versions");
+        let repo = temp.path().join("repo");
+        fake_installed_version(&install_dir, "1.21.0");
+        if !fake_git_repo(&repo) {
+            // git is not available in this environment
+            return;
+        }
+        assert!(git(&repo, &["tag", "snapshot"]));
+
+        let mut request = source_request(Path::new("snapshot"), &install_dir);
+        request.git_remote = repo.display().to_string();
+        let info = GoManager::new().install_from_source(request).await.unwrap();
+
+        assert!(info.version.starts_with("devel-"));
+        let manifest = InstallManifest::read(info.install_path.as_ref().unwrap()).unwrap().unwrap();
+        let build = manifest.build.unwrap();
+        assert_eq!(build.git_remote, Some(repo.display().to_string()));
+        assert!(info.version.ends_with(short_commit(build.git_commit.as_ref().unwrap())));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_install_tip_replaces_old_builds() {
+        let temp = tempfile::tempdir().unwrap();
+        let install_dir = temp.path().join("versions");
+        let repo = temp.path().join("repo");
+        fake_installed_version(&install_dir, "1.21.0");
+        if !fake_git_repo(&repo) {
+            return;
+        }
+
+        let mut request = source_request(Path::new(TIP_DEFAULT_REF), &install_dir);
+        request.git_remote = repo.display().to_string();
+        let manager = GoManager::new();
+
+        let first = manager.install_tip(request.clone()).await.unwrap();
+        assert!(is_tip_version(&first.version));
+        assert_eq!(manager.installed_tip(&request).unwrap(), Some(first.version.clone()));
+        assert!(manager.install_tip(request.clone()).await.is_err());
+
+        std::fs::write(repo.join("README"), "next").unwrap();
+        assert!(git(&repo, &["add", "."]));
+        assert!(git(&repo, &["commit", "-q", "-m", "next"]));
+        assert_eq!(manager.installed_tip(&request).unwrap(), None);
+
+        let second = manager.install_tip(request).await.unwrap();
+        assert_ne!(first.version, second.version);
+
+        let removed = manager.prune_tip_builds(&install_dir, &second.version).unwrap();
+        assert_eq!(removed, vec![first.version.clone()]);
+        assert!(!install_dir.join(&first.version).exists());
+        assert!(install_dir.join(&second.version).exists());
+        assert!(install_dir.join("1.21.0").exists());
+    }
+
+    #[cfg(unix)]
+    fn git(dir: &Path, args: &[&str]) -> bool {
+        std::process::Command::new("git")
+            .args(["-c", "user.name=gvm", "-c", "user.email=gvm@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Creates a git repository on `master` holding a fake development source tree
+    #[cfg(unix)]
+    fn fake_git_repo(repo: &Path) -> bool {
+        fake_source_tree(repo, "devel");
+        std::fs::remove_file(repo.join("VERSION")).unwrap();
+        git(repo, &["init", "-q", "-b", TIP_DEFAULT_REF])
+            && git(repo, &["add", "."])
+            && git(repo, &["commit", "-q", "-m", "init"])
+    }
+
+    /// Writes a stub `bin/go` that reports `reported_version` for the host platform
+    #[cfg(unix)]
+    fn fake_go_binary(version_dir: &Path, reported_version: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        let platform = crate::platform::PlatformInfo::detect();
+        let bin = version_dir.join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::write(
+            bin.join("go"),
+            format!(
+                "#!/bin/sh\n\
+                 case \"$1\" in\n\
+                 version) echo \"go version go{reported_version} {os}/{arch}\" ;;\n\
+                 env) cd \"$(dirname \"$0\")/..\" && pwd && echo {os} && echo {arch} ;;\n\
+                 esac\n",
+                os = platform.os,
+                arch = platform.goarch(),
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(bin.join("go"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_verify_installation() {
+        let temp = tempfile::tempdir().unwrap();
+        let version_dir = temp.path().join("1.99.0");
+        fake_go_binary(&version_dir, "1.99.0");
+        let platform = crate::platform::PlatformInfo::detect();
+        let manager = GoManager::new();
+
+        let verification =
+            manager.verify_installation(&version_dir, Some("1.99.0"), &platform).unwrap();
+        assert!(verification.go_version.contains("go1.99.0"));
+        assert_eq!(verification.goos, platform.os);
+        assert_eq!(verification.goarch, platform.goarch());
+
+        assert!(manager.verify_installation(&version_dir, Some("1.98.0"), &platform).is_err());
+        assert!(manager.verify_installation(&version_dir, None, &platform).is_ok());
+    }
+
+    /// Installs a cached fake binary archive whose toolchain reports `reported_version`
+    #[cfg(unix)]
+    async fn install_fake_archive(
+        temp: &Path,
+        version: &str,
+        reported_version: &str,
+    ) -> Result<GoVersionInfo> {
+        install_fake_archive_with(&GoManager::new(), temp, version, reported_version).await
+    }
+
+    #[cfg(unix)]
+    async fn install_fake_archive_with(
+        manager: &GoManager,
+        temp: &Path,
+        version: &str,
+        reported_version: &str,
+    ) -> Result<GoVersionInfo> {
+        let platform = crate::platform::PlatformInfo::detect();
+        let tree = temp.join("tree");
+        fake_go_binary(&tree.join("go"), reported_version);
+
+        let download_dir = temp.join("cache");
+        std::fs::create_dir_all(&download_dir).unwrap();
+        let encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(download_dir.join(platform.archive_filename(version))).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        builder.append_dir_all("go", tree.join("go")).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        manager
+            .install(InstallRequest {
+                version: version.to_string(),
+                install_dir: temp.join("versions"),
+                download_dir,
+                force: true,
+                verify: true,
+                mirror: DEFAULT_MIRROR.to_string(),
+                bundle: None,
+            })
+            .await
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_install_records_verification() {
+        let temp = tempfile::tempdir().unwrap();
+        let info = install_fake_archive(temp.path(), "1.99.0", "1.99.0").await.unwrap();
+
+        let manifest = InstallManifest::read(info.install_path.as_ref().unwrap()).unwrap().unwrap();
+        assert_eq!(manifest.install_method, InstallMethod::Binary);
+        assert!(manifest.verification.unwrap().go_version.contains("go1.99.0"));
+    }
+
+    #[cfg(unix)]
+    #[derive(Default)]
+    struct RecordingReporter(std::sync::Mutex<Vec<Event>>);
+
+    #[cfg(unix)]
+    impl Reporter for RecordingReporter {
+        fn report(&self, event: &Event) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_install_reports_events() {
+        let temp = tempfile::tempdir().unwrap();
+        let reporter = Arc::new(RecordingReporter::default());
+        let manager = GoManager::with_reporter(reporter.clone());
+        install_fake_archive_with(&manager, temp.path(), "1.99.0", "1.99.0").await.unwrap();
+        manager.switch_version("1.99.0", &temp.path().join("versions")).unwrap();
+
+        let events = reporter.0.lock().unwrap();
+        assert_eq!(events[0], Event::InstallStarted { version: "1.99.0".to_string() });
+        let extracted: Vec<(u64, u64)> = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::ExtractProgress { extracted, total, .. } => Some((*extracted, *total)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(extracted.first().map(|p| p.0), Some(0));
+        assert!(extracted.last().is_some_and(|(done, total)| done == total));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, Event::Verified { version, .. } if version == "1.99.0")));
+        assert!(
+            matches!(&events[events.len() - 2], Event::Installed { version, .. } if version == "1.99.0")
+        );
+        assert_eq!(events.last(), Some(&Event::Switched { version: "1.99.0".to_string() }));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_import_external_and_copied_versions() {
+        let temp = tempfile::tempdir().unwrap();
+        let install_dir = temp.path().join("versions");
+        let system = temp.path().join("usr-local-go");
+        std::fs::create_dir_all(system.join("bin")).unwrap();
+        std::fs::write(system.join("bin").join("go"), "").unwrap();
+        std::fs::write(system.join("VERSION"), "go1.21.3\n").unwrap();
+
+        let manager = GoManager::new();
+        let request = |copy, name: Option<&str>| ImportRequest {
+            source: system.clone(),
+            name: name.map(String::from),
+            install_dir: install_dir.clone(),
+            copy,
+            force: false,
+        };
+
+        let external = manager.import(request(false, None)).unwrap();
+        assert_eq!(external.version, "1.21.3");
+        assert!(is_symlink(&install_dir.join("1.21.3")));
+        let manifest = InstallManifest::read(&install_dir.join("1.21.3")).unwrap().unwrap();
+        assert_eq!(manifest.install_method, InstallMethod::External);
+        assert!(manager.import(request(false, None)).is_err());
+
+        let copied = manager.import(request(true, Some("system"))).unwrap();
+        assert!(copied.install_path.unwrap().join("bin").join("go").is_file());
+        assert!(!is_symlink(&install_dir.join("system")));
+
+        let uninstall = |version: &str| UninstallRequest {
+            version: version.to_string(),
+            base_dir: install_dir.clone(),
+        };
+        manager.uninstall(uninstall("1.21.3")).unwrap();
+        manager.uninstall(uninstall("system")).unwrap();
+        assert!(system.join("bin").join("go").is_file());
+        assert_eq!(std::fs::read_dir(&install_dir).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_installed_versions_read_manifest() {
+        let temp = tempfile::tempdir().unwrap();
+        install_fake_archive(temp.path(), "1.99.0", "1.99.0").await.unwrap();
+        let platform = crate::platform::PlatformInfo::detect();
+        let filename = platform.archive_filename("1.99.0");
+        let manager = GoManager::new();
+
+        let list = manager
+            .list_installed(ListInstalledRequest { base_dir: temp.path().join("versions") })
+            .unwrap();
+        let info = &list.versions[0];
+        assert_eq!(info.os, platform.os);
+        assert_eq!(info.arch, platform.goarch());
+        assert_eq!(info.filename, filename);
+        assert_eq!(info.extension, platform.extension);
+        assert_eq!(info.download_url, mirror_url(DEFAULT_MIRROR, &filename));
+        assert_eq!(
+            info.sha256.as_deref(),
+            Some(
+                crate::downloader::sha256_file(temp.path().join("cache").join(&filename)).unwrap()
+            )
+            .as_deref()
+        );
+        assert!(info.size.is_some());
+
+        let info = manager
+            .get_version_info(
+                "1.99.0",
+                &temp.path().join("versions"),
+                &temp.path().join("cache"),
+                DEFAULT_MIRROR,
+            )
+            .unwrap();
+        assert!(info.is_installed && info.is_cached);
+        assert_eq!(info.sha256, list.versions[0].sha256);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_install_rolls_back_on_failed_verification() {
+        let temp = tempfile::tempdir().unwrap();
+        let result = install_fake_archive(temp.path(), "1.99.0", "1.98.0").await;
+
+        assert!(result.is_err());
+        assert!(!temp.path().join("versions").join("1.99.0").exists());
+
+        // A forced reinstall that fails verification restores the previous installation
+        install_fake_archive(temp.path(), "1.99.0", "1.99.0").await.unwrap();
+        assert!(install_fake_archive(temp.path(), "1.99.0", "1.98.0").await.is_err());
+        let version_dir = temp.path().join("versions").join("1.99.0");
+        let manifest = InstallManifest::read(&version_dir).unwrap().unwrap();
+        assert!(manifest.verification.unwrap().go_version.contains("go1.99.0"));
+        assert!(!temp.path().join("versions").join("1.99.0_previous").exists());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_versions_uses_cached_index() {
+        let temp = tempfile::tempdir().unwrap();
+        let releases = ["1.21.13", "1.21.12", "1.20.14", "1.20"]
+            .iter()
+            .map(|v| crate::releases::GoRelease {
+                version: format!("go{v}"),
+                stable: true,
+                files: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        std::fs::write(
+            ReleaseIndex::cache_file(temp.path()),
+            serde_json::to_string(&releases).unwrap(),
+        )
+        .unwrap();
+
+        let specs: Vec<String> =
+            ["1.20", "latest", "1.21.12", "1.21.13"].iter().map(|s| s.to_string()).collect();
+        let resolved = GoManager::new()
+            .resolve_versions(&specs, "http://127.0.0.1:9", temp.path())
+            .await
+            .unwrap();
+        assert_eq!(resolved, ["1.20.14", "1.21.13", "1.21.12"]);
+    }
+
+    #[test]
+    fn test_verify_checksum_removes_mismatched_archive() {
+        let temp = tempfile::tempdir().unwrap();
+        let archive = temp.path().join("go1.99.0.linux-amd64.tar.gz");
+        std::fs::write(&archive, b"archive").unwrap();
+        let sha256 = crate::downloader::sha256_file(&archive).unwrap();
+
+        assert!(verify_checksum(&archive, &sha256.to_uppercase()).is_ok());
+
+        let err = verify_checksum(&archive, &"0".repeat(64)).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(GvmError::ChecksumMismatch { .. })));
+        assert!(!archive.exists());
+    }
+
+    #[test]
+    fn test_tip_version_names() {
+        assert_eq!(tip_version_name("0123456789abcdef"), "tip-0123456789ab");
+        assert!(is_tip_version("tip-0123456789ab"));
+        assert!(!is_tip_version("tipsy"));
+        assert!(!is_tip_version("1.22.0"));
     }
 }
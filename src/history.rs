@@ -0,0 +1,217 @@
+//! Go release history module
+//!
+//! Fetches the release history page (`https://go.dev/doc/devel/release`), caches it in the
+//! cache directory and extracts the date and summary of each release from it, for
+//! `gvm info` and `gvm diff`.
+
+use crate::downloader::fetch_text;
+use anyhow::Result;
+use chrono::NaiveDate;
+use log::{debug, warn};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default location of the release history page
+pub const DEFAULT_RELEASE_HISTORY_URL: &str = "https://go.dev/doc/devel/release";
+
+/// Filename of the cached release history in the cache directory
+pub const HISTORY_CACHE_FILE: &str = "release-history.html";
+
+/// Cached history age after which it is refreshed
+const HISTORY_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Kind of a release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReleaseKind {
+    /// First release of a minor series (e.g. 1.22.0)
+    Major,
+    /// Point release with security fixes
+    Security,
+    /// Point release with bug fixes only
+    Minor,
+}
+
+impl ReleaseKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Major => "major release",
+            Self::Security => "security release",
+            Self::Minor => "minor release",
+        }
+    }
+}
+
+/// A release as described by the release history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReleaseNote {
+    /// Version number (e.g. "1.22.3")
+    pub version: String,
+    pub date: Option<NaiveDate>,
+    pub kind: ReleaseKind,
+    /// What the release includes, e.g. "includes security fixes to the net package"
+    pub summary: String,
+}
+
+/// The release history
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReleaseHistory {
+    notes: Vec<ReleaseNote>,
+}
+
+impl ReleaseHistory {
+    /// Parses the release history page
+    pub fn parse(html: &str) -> Self {
+        let notes = html.split("<p").filter_map(|paragraph| parse_note(&strip_tags(paragraph)));
+        Self { notes: notes.collect() }
+    }
+
+    /// Loads the history, preferring a fresh cached copy and falling back to a stale one
+    /// when the page cannot be fetched
+    ///
+    /// # Errors
+    /// Returns an error if the history can neither be fetched nor read from the cache.
+    pub async fn load(url: &str, cache_dir: &Path) -> Result<Self> {
+        let cache_file = Self::cache_file(cache_dir);
+        let cache_age = std::fs::metadata(&cache_file)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok());
+
+        if cache_age.is_some_and(|age| age < HISTORY_MAX_AGE) {
+            if let Ok(html) = std::fs::read_to_string(&cache_file) {
+                debug!("Using cached release history {}", cache_file.display());
+                return Ok(Self::parse(&html));
+            }
+        }
+
+        match fetch_text(url, "release history").await {
+            Ok(html) => {
+                if let Err(e) = std::fs::create_dir_all(cache_dir)
+                    .and_then(|()| std::fs::write(&cache_file, &html))
+                {
+                    warn!("Failed to cache release history: {e}");
+                }
+                Ok(Self::parse(&html))
+            }
+            Err(e) if cache_file.exists() => {
+                warn!("Failed to fetch release history, using cached copy: {e}");
+                let html = std::fs::read_to_string(&cache_file)?;
+                Ok(Self::parse(&html))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Gets the cache file path for a cache directory
+    pub fn cache_file(cache_dir: &Path) -> PathBuf {
+        cache_dir.join(HISTORY_CACHE_FILE)
+    }
+
+    /// Finds the note of a release; "1.22" also finds "1.22.0"
+    pub fn find(&self, version: &str) -> Option<&ReleaseNote> {
+        let version = version.strip_prefix("go").unwrap_or(version);
+        self.notes.iter().find(|note| {
+            note.version == version || note.version.strip_suffix(".0") == Some(version)
+        })
+    }
+}
+
+/// Parses a paragraph such as "go1.22.3 (released 2024-05-07) includes security fixes ..."
+fn parse_note(text: &str) -> Option<ReleaseNote> {
+    let rest = text.strip_prefix("go")?;
+    let (version, rest) = rest.split_once(" (released ")?;
+    let (date, summary) = rest.split_once(')')?;
+    if version.is_empty() || !version.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    // Drop the pointer to the issue tracker milestone
+    let summary = summary.trim();
+    let summary = summary.split(" See the ").next().unwrap_or(summary).trim().to_string();
+    if summary.is_empty() {
+        return None;
+    }
+    let kind = if summary.contains("major release") {
+        ReleaseKind::Major
+    } else if summary.contains("security fix") {
+        ReleaseKind::Security
+    } else {
+        ReleaseKind::Minor
+    };
+
+    Some(ReleaseNote {
+        version: version.to_string(),
+        date: NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok(),
+        kind,
+        summary,
+    })
+}
+
+/// Removes tags from an HTML fragment, decoding common entities and collapsing whitespace
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    // The fragment starts inside the tag it was split at
+    let mut in_tag = true;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    // Tags around words leave a space before punctuation
+    text.replace(" ,", ",").replace(" .", ".").replace(" )", ")")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = r#"
+        <h3 id="go1.22.0">go1.22.0 (released 2024-02-06)</h3>
+        <p>
+        go1.22.0 (released 2024-02-06) is a major release of Go.
+        Read the <a href="/doc/go1.22">Go 1.22 Release Notes</a> for more information.
+        </p>
+        <p>
+        go1.22.3 (released 2024-05-07) includes security fixes to the <code>go</code>
+        command and the <code>net</code> package, as well as bug fixes to the compiler.
+        See the <a href="https://github.com/golang/go/issues?q=milestone%3AGo1.22.3">Go
+        1.22.3 milestone</a> on our issue tracker for details.
+        </p>
+        <p>go1.22.4 (released 2024-06-04) includes bug fixes to the runtime &amp; linker.</p>
+        <p>Unrelated paragraph.</p>
+    "#;
+
+    #[test]
+    fn test_parse() {
+        let history = ReleaseHistory::parse(HTML);
+
+        let note = history.find("1.22.3").unwrap();
+        assert_eq!(note.date, NaiveDate::from_ymd_opt(2024, 5, 7));
+        assert_eq!(note.kind, ReleaseKind::Security);
+        assert_eq!(
+            note.summary,
+            "includes security fixes to the go command and the net package, as well as bug fixes to the compiler."
+        );
+
+        assert_eq!(history.find("1.22").unwrap().kind, ReleaseKind::Major);
+        let note = history.find("go1.22.4").unwrap();
+        assert_eq!(note.kind, ReleaseKind::Minor);
+        assert_eq!(note.summary, "includes bug fixes to the runtime & linker.");
+        assert!(history.find("1.22.5").is_none());
+    }
+}
@@ -0,0 +1,138 @@
+//! System Go discovery module
+//!
+//! Finds Go installations made outside of gvm — the official installer, `golang.org/dl`
+//! wrappers in `~/sdk`, Homebrew and distribution packages, or whatever `go` is on PATH —
+//! so that `gvm import` can bring them under management.
+
+use crate::platform::PlatformInfo;
+use crate::source;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// A Go installation found outside of gvm
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SystemInstall {
+    /// Go root directory (canonical)
+    pub path: PathBuf,
+    /// First line of the `VERSION` file (e.g. "go1.21.3")
+    pub go_version: String,
+    /// Version name gvm would register it under, if it is a release
+    pub version: Option<String>,
+    /// Where the installation comes from (e.g. "Homebrew")
+    pub origin: &'static str,
+}
+
+/// Inspects a directory, returning the installation if it is a Go root
+pub fn inspect(path: &Path, origin: &'static str) -> Option<SystemInstall> {
+    let path = path.canonicalize().ok()?;
+    if !path.join("bin").join(PlatformInfo::go_executable_name()).is_file() {
+        return None;
+    }
+    let go_version = source::read_version_file(&path)?;
+    let version = source::version_name_from_go_version(&go_version);
+    Some(SystemInstall { path, go_version, version, origin })
+}
+
+/// Finds Go installations outside of `versions_dir`, without duplicates
+pub fn discover(versions_dir: &Path) -> Vec<SystemInstall> {
+    let managed = versions_dir.canonicalize().unwrap_or_else(|_| versions_dir.to_path_buf());
+    let mut found: Vec<SystemInstall> = Vec::new();
+
+    for (path, origin) in candidate_roots() {
+        let Some(install) = inspect(&path, origin) else {
+            continue;
+        };
+        if install.path.starts_with(&managed) || found.iter().any(|f| f.path == install.path) {
+            continue;
+        }
+        found.push(install);
+    }
+
+    found
+}
+
+/// Directories that commonly hold a Go root, with their origin
+fn candidate_roots() -> Vec<(PathBuf, &'static str)> {
+    let mut roots = Vec::new();
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        roots.push((PathBuf::from("/usr/local/go"), "official installer"));
+        for cellar in ["/opt/homebrew/Cellar/go", "/usr/local/Cellar/go"] {
+            roots.extend(
+                children(Path::new(cellar), "")
+                    .into_iter()
+                    .map(|p| (p.join("libexec"), "Homebrew")),
+            );
+        }
+        for dir in ["/usr/lib/go", "/usr/lib/golang", "/usr/local/lib/go"] {
+            roots.push((PathBuf::from(dir), "distribution package"));
+        }
+        roots.extend(
+            children(Path::new("/usr/lib"), "go-").into_iter().map(|p| (p, "distribution package")),
+        );
+        roots.push((PathBuf::from("/snap/go/current"), "snap"));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        roots.push((PathBuf::from("C:\\Program Files\\Go"), "official installer"));
+        roots.push((PathBuf::from("C:\\Go"), "official installer"));
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        roots.extend(children(&home.join("sdk"), "go").into_iter().map(|p| (p, "golang.org/dl")));
+    }
+
+    // `go` on PATH, resolved through symlinks such as /usr/bin/go
+    let go = PlatformInfo::go_executable_name();
+    if let Some(path) = std::env::var_os("PATH") {
+        for dir in std::env::split_paths(&path) {
+            if let Ok(binary) = dir.join(go).canonicalize() {
+                if let Some(root) = binary.parent().and_then(Path::parent) {
+                    roots.push((root.to_path_buf(), "PATH"));
+                }
+            }
+        }
+    }
+
+    roots
+}
+
+/// Lists the subdirectories of `dir` whose names start with `prefix`, sorted by name
+fn children(dir: &Path, prefix: &str) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().join("go");
+        std::fs::create_dir_all(root.join("bin")).unwrap();
+        assert!(inspect(&root, "test").is_none());
+
+        std::fs::write(root.join("bin").join(PlatformInfo::go_executable_name()), "").unwrap();
+        std::fs::write(root.join("VERSION"), "go1.21.3\ntime 2023-10-09T17:04:35Z\n").unwrap();
+        let install = inspect(&root, "test").unwrap();
+        assert_eq!(install.go_version, "go1.21.3");
+        assert_eq!(install.version.as_deref(), Some("1.21.3"));
+        assert_eq!(install.path, root.canonicalize().unwrap());
+
+        std::fs::write(root.join("VERSION"), "devel go1.22-abcdef").unwrap();
+        assert_eq!(inspect(&root, "test").unwrap().version, None);
+    }
+}
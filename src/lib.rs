@@ -4,18 +4,35 @@
 //! providing a simple command-line interface to manage multiple Go versions.
 
 // Core modules
+pub mod audit;
+pub mod bundle;
 pub mod cli;
 pub mod commands;
 pub mod config;
 
+pub mod cache;
+pub mod dedup;
+pub mod doctor;
 pub mod downloader;
 pub mod error;
 pub mod go;
+pub mod history;
+pub mod import;
+pub mod lockfile;
+pub mod manifest;
+pub mod output;
 pub mod platform;
+pub mod project;
+pub mod releases;
+pub mod reporter;
+pub mod serve;
+pub mod source;
 pub mod symlink;
+pub mod version;
 
 // Flattened UI and progress system
 pub mod progress_flat;
+pub mod terminal;
 pub mod ui_flat;
 
 // Note: Deprecated nested modules have been removed.
@@ -25,11 +42,13 @@ pub mod ui_flat;
 pub use cli::Cli;
 pub use config::Config;
 pub use downloader::Downloader;
-pub use error::{ErrorUtils, Result};
+pub use error::{ErrorUtils, FailureKind, GvmError, Result};
 pub use go::{GoManager, GoVersionInfo};
+pub use output::OutputFormat;
+pub use reporter::{Event, JsonLinesReporter, Reporter, SilentReporter, TerminalReporter};
 
 // UI and progress system (flattened)
-pub use progress_flat::{BasicProgress, InstallSteps};
+pub use progress_flat::{BasicProgress, InstallSteps, MultiProgress};
 pub use ui_flat::{format_duration, format_size, SimpleProgressBar, SimpleUI};
 
 // Note: Deprecated backward-compatible exports have been removed.
@@ -37,8 +56,6 @@ pub use ui_flat::{format_duration, format_size, SimpleProgressBar, SimpleUI};
 
 // Public type definitions
 
-
-
 /// Installation request
 #[derive(Debug, Clone)]
 pub struct InstallRequest {
@@ -46,6 +63,45 @@ pub struct InstallRequest {
     pub install_dir: std::path::PathBuf,
     pub download_dir: std::path::PathBuf,
     pub force: bool,
+    /// Run the installed toolchain to verify it, rolling back on failure
+    pub verify: bool,
+    /// Base URL to download archives from
+    pub mirror: String,
+    /// Offline bundle to take the archive from instead of the mirror
+    pub bundle: Option<std::path::PathBuf>,
+}
+
+/// Source build request
+#[derive(Debug, Clone)]
+pub struct SourceInstallRequest {
+    /// Release version, git ref, source directory or source archive
+    pub source: String,
+    /// Installed version name (derived from the source when omitted)
+    pub name: Option<String>,
+    /// Installed version to use as `GOROOT_BOOTSTRAP`
+    pub bootstrap: Option<String>,
+    /// Git remote for git refs
+    pub git_remote: String,
+    pub install_dir: std::path::PathBuf,
+    pub download_dir: std::path::PathBuf,
+    pub force: bool,
+    /// Run the built toolchain to verify it, rolling back on failure
+    pub verify: bool,
+    /// Base URL to download source releases from
+    pub mirror: String,
+}
+
+/// Import request for a Go installation made outside of gvm
+#[derive(Debug, Clone)]
+pub struct ImportRequest {
+    /// Go root to import
+    pub source: std::path::PathBuf,
+    /// Installed version name (derived from the `VERSION` file when omitted)
+    pub name: Option<String>,
+    pub install_dir: std::path::PathBuf,
+    /// Copy the installation instead of referencing it in place
+    pub copy: bool,
+    pub force: bool,
 }
 
 /// Switch request
@@ -77,11 +133,13 @@ pub struct StatusRequest {
 }
 
 /// Runtime status
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct RuntimeStatus {
     pub current_version: Option<String>,
     pub current_path: Option<String>,
     pub environment_vars: std::collections::HashMap<String, String>,
+    /// Support and vulnerability status of the current version, when known
+    pub audit: Option<audit::VersionAudit>,
 }
 
 /// Version list
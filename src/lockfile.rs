@@ -0,0 +1,162 @@
+//! Toolchain lockfile module
+//!
+//! A `gvm.lock` file records a set of Go versions with the checksums of their archives for
+//! each platform, and the default version. `gvm export` writes it from the installed
+//! versions and `gvm sync` reproduces it on another machine.
+
+use crate::error::GvmError;
+use crate::releases::GoRelease;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Default lockfile name
+pub const LOCKFILE_NAME: &str = "gvm.lock";
+
+/// Format version written to new lockfiles
+pub const LOCKFILE_VERSION: u32 = 1;
+
+/// A toolchain lockfile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub lockfile_version: u32,
+    /// Version to activate after syncing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    pub versions: Vec<LockedVersion>,
+}
+
+/// A locked Go version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedVersion {
+    /// Version number (e.g. "1.22.3")
+    pub version: String,
+    pub archives: Vec<LockedArchive>,
+}
+
+/// The archive of a locked version for one platform
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedArchive {
+    pub os: String,
+    pub arch: String,
+    pub filename: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl LockedVersion {
+    /// Locks the archives of a release, for all platforms or only the given ones
+    pub fn from_release(release: &GoRelease, platforms: &[(String, String)]) -> Self {
+        let archives = release
+            .files
+            .iter()
+            .filter(|file| file.kind == "archive")
+            .filter(|file| {
+                platforms.is_empty()
+                    || platforms.iter().any(|(os, arch)| *os == file.os && *arch == file.arch)
+            })
+            .map(|file| LockedArchive {
+                os: file.os.clone(),
+                arch: file.arch.clone(),
+                filename: file.filename.clone(),
+                sha256: file.sha256.clone(),
+                size: file.size,
+            })
+            .collect();
+        Self { version: release.version_number().to_string(), archives }
+    }
+
+    /// Finds the locked archive for a platform
+    pub fn archive_for(&self, os: &str, arch: &str) -> Option<&LockedArchive> {
+        self.archives.iter().find(|a| a.os == os && a.arch == arch)
+    }
+}
+
+impl Lockfile {
+    /// Creates a lockfile in the current format
+    pub fn new(versions: Vec<LockedVersion>, default: Option<String>) -> Self {
+        Self { lockfile_version: LOCKFILE_VERSION, default, versions }
+    }
+
+    /// Reads a lockfile
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed, or was written in a newer
+    /// format.
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(GvmError::io("read", path))?;
+        let lockfile: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid lockfile {}", path.display()))?;
+        if lockfile.lockfile_version > LOCKFILE_VERSION {
+            return Err(GvmError::Usage(format!(
+                "{} uses lockfile version {}, but this gvm supports up to {LOCKFILE_VERSION}",
+                path.display(),
+                lockfile.lockfile_version
+            ))
+            .into());
+        }
+        Ok(lockfile)
+    }
+
+    /// Writes the lockfile
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, format!("{content}\n")).map_err(GvmError::io("write", path))?;
+        Ok(())
+    }
+
+    /// Finds a locked version
+    pub fn find(&self, version: &str) -> Option<&LockedVersion> {
+        self.versions.iter().find(|v| v.version == version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::releases::GoFile;
+
+    fn file(os: &str, arch: &str, kind: &str) -> GoFile {
+        GoFile {
+            filename: format!("go1.22.3.{os}-{arch}.tar.gz"),
+            os: os.into(),
+            arch: arch.into(),
+            version: "go1.22.3".into(),
+            sha256: format!("{os}-{arch}-sum"),
+            size: 1,
+            kind: kind.into(),
+        }
+    }
+
+    #[test]
+    fn test_lockfile_roundtrip() {
+        let release = GoRelease {
+            version: "go1.22.3".into(),
+            stable: true,
+            files: vec![
+                file("linux", "amd64", "archive"),
+                file("linux", "arm64", "archive"),
+                file("darwin", "arm64", "installer"),
+            ],
+        };
+        let locked = LockedVersion::from_release(&release, &[]);
+        assert_eq!(locked.version, "1.22.3");
+        assert_eq!(locked.archives.len(), 2);
+        assert_eq!(locked.archive_for("linux", "arm64").unwrap().sha256, "linux-arm64-sum");
+
+        let only_arm = [("linux".to_string(), "arm64".to_string())];
+        assert_eq!(LockedVersion::from_release(&release, &only_arm).archives.len(), 1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LOCKFILE_NAME);
+        let lockfile = Lockfile::new(vec![locked], Some("1.22.3".into()));
+        lockfile.write(&path).unwrap();
+        assert_eq!(Lockfile::read(&path).unwrap(), lockfile);
+
+        std::fs::write(&path, r#"{"lockfile_version": 2, "versions": []}"#).unwrap();
+        assert!(Lockfile::read(&path).is_err());
+    }
+}
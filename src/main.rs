@@ -1,16 +1,18 @@
 use clap::Parser;
-use tidepool_gvm::cli::Cli;
+use std::process::ExitCode;
+use tidepool_gvm::{cli::Cli, ErrorUtils};
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    // 初始化日志
-    env_logger::init();
-
+async fn main() -> ExitCode {
     // 解析命令行参数
     let cli = Cli::parse();
 
-    // 执行命令
-    cli.run().await?;
-
-    Ok(())
+    // 执行命令，失败时按错误类别返回退出码
+    match cli.run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            cli.output.report_error(&e);
+            ExitCode::from(ErrorUtils::failure_kind(&e).exit_code())
+        }
+    }
 }
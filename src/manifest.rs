@@ -0,0 +1,265 @@
+//! Installation manifest module
+//!
+//! Every installed version directory carries a `.gvm-install.json` file that records
+//! where the version came from and how it was installed.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Manifest filename inside an installed version directory
+pub const MANIFEST_FILE: &str = ".gvm-install.json";
+
+/// How a version was installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallMethod {
+    /// Extracted from an official binary archive
+    Binary,
+    /// Built locally from a source tree
+    Source,
+    /// Copied from a Go installation made outside of gvm
+    Imported,
+    /// A Go installation outside of gvm, referenced in place through a link
+    External,
+}
+
+/// Details of a source build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    /// Source specification as given by the user (version, git ref or path)
+    pub source: String,
+    /// Git remote the source was fetched from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_remote: Option<String>,
+    /// Git commit that was built
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+    /// Toolchain used as `GOROOT_BOOTSTRAP`
+    pub bootstrap: PathBuf,
+    /// First line of the `VERSION` file of the built tree
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub go_version: Option<String>,
+}
+
+/// Result of running the installed toolchain after installation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Verification {
+    /// Output of `go version`
+    pub go_version: String,
+    /// `GOROOT` reported by `go env`
+    pub goroot: String,
+    /// `GOOS` reported by `go env`
+    pub goos: String,
+    /// `GOARCH` reported by `go env`
+    pub goarch: String,
+    /// Verification time
+    pub verified_at: DateTime<Utc>,
+}
+
+/// Installation manifest stored alongside each installed version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallManifest {
+    /// Installed version name (directory name)
+    pub version: String,
+    /// Installation method
+    pub install_method: InstallMethod,
+    /// Installation time
+    pub installed_at: DateTime<Utc>,
+    /// Version of gvm that performed the installation
+    pub gvm_version: String,
+    /// Target operating system (Go naming, e.g. "linux", "darwin")
+    #[serde(default)]
+    pub os: String,
+    /// Target architecture (Go naming, e.g. "amd64", "arm64")
+    #[serde(default)]
+    pub arch: String,
+    /// Archive filename the version was installed from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// URL the archive was downloaded from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
+    /// Mirror base URL used for the download
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<String>,
+    /// SHA256 checksum of the archive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Archive size in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// Build details for source installs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<BuildInfo>,
+    /// Post-install verification results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<Verification>,
+    /// Go root an imported or external version comes from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imported_from: Option<PathBuf>,
+}
+
+impl InstallManifest {
+    /// Creates a manifest stamped with the current time, gvm version and host platform
+    pub fn new(version: &str, install_method: InstallMethod) -> Self {
+        let platform = crate::platform::PlatformInfo::detect();
+        Self {
+            version: version.to_string(),
+            install_method,
+            installed_at: Utc::now(),
+            gvm_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: platform.os.clone(),
+            arch: platform.goarch().to_string(),
+            filename: None,
+            source_url: None,
+            mirror: None,
+            sha256: None,
+            size: None,
+            build: None,
+            verification: None,
+            imported_from: None,
+        }
+    }
+
+    /// Gets the manifest path for a version directory
+    ///
+    /// External versions are links to directories gvm does not own, so their manifest is
+    /// kept next to the link as `.<version>.gvm-install.json`.
+    pub fn path(version_dir: &Path) -> PathBuf {
+        if crate::symlink::is_symlink(version_dir) {
+            let name = version_dir.file_name().unwrap_or_default().to_string_lossy();
+            version_dir.with_file_name(format!(".{name}{MANIFEST_FILE}"))
+        } else {
+            version_dir.join(MANIFEST_FILE)
+        }
+    }
+
+    /// Reads the manifest of a version directory, if present
+    ///
+    /// # Errors
+    /// Returns an error if the manifest exists but cannot be read or parsed.
+    pub fn read(version_dir: &Path) -> Result<Option<Self>> {
+        let path = Self::path(version_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let manifest = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid install manifest {}", path.display()))?;
+        Ok(Some(manifest))
+    }
+
+    /// Records the archive a version was installed from
+    ///
+    /// # Errors
+    /// Returns an error if the archive cannot be read for checksumming.
+    pub fn set_archive(
+        &mut self,
+        archive_path: &Path,
+        source_url: &str,
+        mirror: &str,
+    ) -> Result<()> {
+        self.filename = archive_path.file_name().map(|name| name.to_string_lossy().to_string());
+        self.source_url = Some(source_url.to_string());
+        self.mirror = Some(mirror.to_string());
+        self.sha256 = Some(
+            crate::downloader::sha256_file(archive_path)
+                .with_context(|| format!("Failed to checksum {}", archive_path.display()))?,
+        );
+        self.size = Some(std::fs::metadata(archive_path)?.len());
+        Ok(())
+    }
+
+    /// Writes the manifest into a version directory
+    ///
+    /// # Errors
+    /// Returns an error if the manifest cannot be serialized or written.
+    pub fn write(&self, version_dir: &Path) -> Result<()> {
+        let path = Self::path(version_dir);
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(InstallManifest::read(dir.path()).unwrap().is_none());
+
+        let mut manifest = InstallManifest::new("1.22.3", InstallMethod::Source);
+        manifest.build = Some(BuildInfo {
+            source: "go1.22.3".to_string(),
+            git_remote: Some("https://go.googlesource.com/go".to_string()),
+            git_commit: Some("abcdef0".to_string()),
+            bootstrap: PathBuf::from("/opt/go"),
+            go_version: Some("go1.22.3".to_string()),
+        });
+        manifest.write(dir.path()).unwrap();
+
+        let read = InstallManifest::read(dir.path()).unwrap().unwrap();
+        assert_eq!(read, manifest);
+    }
+
+    #[test]
+    fn test_manifest_archive_details() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("go1.22.3.linux-amd64.tar.gz");
+        std::fs::write(&archive, b"archive").unwrap();
+
+        let mut manifest = InstallManifest::new("1.22.3", InstallMethod::Binary);
+        manifest
+            .set_archive(
+                &archive,
+                "https://go.dev/dl/go1.22.3.linux-amd64.tar.gz",
+                "https://go.dev/dl",
+            )
+            .unwrap();
+
+        assert_eq!(manifest.filename.as_deref(), Some("go1.22.3.linux-amd64.tar.gz"));
+        assert_eq!(manifest.size, Some(7));
+        assert_eq!(manifest.sha256.as_ref().map(String::len), Some(64));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_manifest_is_kept_next_to_link() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("system-go");
+        std::fs::create_dir_all(&target).unwrap();
+        let link = dir.path().join("versions").join("1.21.3");
+        std::fs::create_dir_all(link.parent().unwrap()).unwrap();
+        crate::symlink::create_symlink(&target, &link).unwrap();
+
+        let mut manifest = InstallManifest::new("1.21.3", InstallMethod::External);
+        manifest.imported_from = Some(target.clone());
+        manifest.write(&link).unwrap();
+
+        assert!(dir.path().join("versions").join(".1.21.3.gvm-install.json").exists());
+        assert!(!target.join(MANIFEST_FILE).exists());
+        assert_eq!(InstallManifest::read(&link).unwrap().unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_manifest_tolerates_missing_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            InstallManifest::path(dir.path()),
+            r#"{"version":"1.22.3","install_method":"binary","installed_at":"2024-05-07T00:00:00Z","gvm_version":"0.2.1"}"#,
+        )
+        .unwrap();
+
+        let manifest = InstallManifest::read(dir.path()).unwrap().unwrap();
+        assert_eq!(manifest.install_method, InstallMethod::Binary);
+        assert!(manifest.os.is_empty());
+        assert!(manifest.sha256.is_none());
+    }
+}
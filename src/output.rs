@@ -0,0 +1,446 @@
+//! Machine-readable output module
+//!
+//! With `--output json`, commands print a single JSON document to stdout instead of
+//! human-readable text, and failures are printed as a JSON error document to stderr.
+//! Both documents carry `schema_version`, which is bumped on incompatible changes.
+
+use crate::audit::VersionAudit;
+use crate::cache::{ArchiveCheck, CachedArchive};
+use crate::dedup::DiskUsage;
+use crate::doctor::Finding;
+use crate::error::{ErrorUtils, FailureKind};
+use crate::history::ReleaseKind;
+use crate::import::SystemInstall;
+use crate::lockfile::LockedVersion;
+use crate::releases::GoFile;
+use crate::reporter::{SilentReporter, TerminalReporter};
+use crate::ui_flat::SimpleUI;
+use crate::{GoManager, GoVersionInfo};
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Version of the JSON document schema
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Output format selected with `--output`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// A single JSON document
+    Json,
+}
+
+impl OutputFormat {
+    /// Checks whether this is a machine-readable format
+    pub fn is_structured(self) -> bool {
+        self != Self::Text
+    }
+
+    /// Creates the UI for this format, which is silent for machine-readable formats
+    pub fn ui(self) -> SimpleUI {
+        if self.is_structured() {
+            SimpleUI::silent()
+        } else {
+            SimpleUI::new()
+        }
+    }
+
+    /// Creates a manager whose progress is shown on the terminal, or dropped for
+    /// machine-readable formats
+    pub fn manager(self) -> GoManager {
+        if self.is_structured() {
+            GoManager::with_reporter(Arc::new(SilentReporter))
+        } else {
+            GoManager::with_reporter(Arc::new(TerminalReporter::new()))
+        }
+    }
+
+    /// Prints the result document of a command; does nothing for text output
+    ///
+    /// # Errors
+    /// Returns an error if the data cannot be serialized.
+    pub fn emit<T: Serialize>(self, command: &str, data: &T) -> Result<()> {
+        if self == Self::Json {
+            let document = Document { schema_version: SCHEMA_VERSION, command, data };
+            println!("{}", serde_json::to_string_pretty(&document)?);
+        }
+        Ok(())
+    }
+
+    /// Prints a command failure in this format
+    pub fn report_error(self, err: &anyhow::Error) {
+        match self {
+            Self::Text => ErrorUtils::report(&SimpleUI::new(), err),
+            Self::Json => {
+                let document =
+                    ErrorDocument { schema_version: SCHEMA_VERSION, error: ErrorInfo::new(err) };
+                match serde_json::to_string_pretty(&document) {
+                    Ok(json) => eprintln!("{json}"),
+                    Err(_) => eprintln!("{}", ErrorUtils::message(err)),
+                }
+            }
+        }
+    }
+}
+
+/// Envelope of a command result
+#[derive(Serialize)]
+struct Document<'a, T> {
+    schema_version: u32,
+    command: &'a str,
+    data: &'a T,
+}
+
+/// Envelope of a command failure
+#[derive(Serialize)]
+struct ErrorDocument {
+    schema_version: u32,
+    error: ErrorInfo,
+}
+
+/// Structured description of an error
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorInfo {
+    /// Stable error code, if the error is a known gvm error
+    pub code: Option<&'static str>,
+    pub kind: FailureKind,
+    pub exit_code: u8,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl ErrorInfo {
+    /// Describes an error
+    pub fn new(err: &anyhow::Error) -> Self {
+        let kind = ErrorUtils::failure_kind(err);
+        Self {
+            code: ErrorUtils::code(err),
+            kind,
+            exit_code: kind.exit_code(),
+            message: ErrorUtils::message(err),
+            hint: ErrorUtils::hint(err),
+        }
+    }
+}
+
+/// Result of `install` and `upgrade`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InstallReport {
+    pub installed: Vec<GoVersionInfo>,
+    pub failed: Vec<FailedInstall>,
+}
+
+/// A version that failed to install
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedInstall {
+    pub version: String,
+    pub error: ErrorInfo,
+}
+
+/// Result of `upgrade`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpgradeReport {
+    pub installed: Vec<GoVersionInfo>,
+    /// Planned (or performed) release upgrades
+    pub upgrades: Vec<SeriesUpgrade>,
+    pub dry_run: bool,
+}
+
+/// An upgrade to the newest release of a minor series
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesUpgrade {
+    pub from: String,
+    pub to: String,
+    /// Whether the new version is activated in place of the old one
+    pub switched: bool,
+    /// `.go-version` files updated to the new version
+    pub updated_pins: Vec<PathBuf>,
+    /// Whether the old version is uninstalled
+    pub removed: bool,
+}
+
+/// Result of `uninstall`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UninstallReport {
+    /// Versions removed (or that would be removed)
+    pub uninstalled: Vec<String>,
+    /// Selected versions that were kept
+    pub kept: Vec<KeptVersion>,
+    /// Cached archives of the removed versions that were deleted
+    pub purged_archives: Vec<String>,
+    /// Disk space freed (or that would be freed)
+    pub reclaimed: u64,
+    pub dry_run: bool,
+}
+
+/// Result of `dedup`
+#[derive(Debug, Clone, Serialize)]
+pub struct DedupReport {
+    pub store: PathBuf,
+    pub versions: Vec<String>,
+    /// Files that were compared
+    pub files: u64,
+    /// Files that were replaced with links to the store
+    pub linked: u64,
+    /// Disk space freed, in bytes
+    pub reclaimed: u64,
+    /// Store entries removed because no version links to them
+    pub pruned: usize,
+}
+
+/// Result of `du`
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskUsageReport {
+    pub root: PathBuf,
+    pub versions: Vec<VersionDiskUsage>,
+    /// On-disk size of the files that several versions link to
+    pub shared: u64,
+    pub cache: DiskUsage,
+    /// Everything under the gvm root, the versions and the cache
+    pub total: DiskUsage,
+}
+
+/// Disk usage of an installed version
+///
+/// `on_disk` is the space that uninstalling the version frees.
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionDiskUsage {
+    pub version: String,
+    pub is_current: bool,
+    #[serde(flatten)]
+    pub usage: DiskUsage,
+}
+
+/// Result of `use`
+#[derive(Debug, Clone, Serialize)]
+pub struct SwitchReport {
+    pub version: String,
+    pub global: bool,
+}
+
+/// Result of `import`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    /// Installations found outside of gvm (when discovering)
+    pub discovered: Vec<SystemInstall>,
+    pub imported: Vec<GoVersionInfo>,
+    pub skipped: Vec<SkippedImport>,
+}
+
+/// Result of `export`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExportReport {
+    pub path: PathBuf,
+    /// Locked versions
+    pub versions: Vec<String>,
+    /// Installed versions that are not releases and were left out
+    pub skipped: Vec<String>,
+    pub default: Option<String>,
+}
+
+/// Result of `bundle create`
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleCreateReport {
+    pub path: PathBuf,
+    /// Bundled versions with their archives
+    pub versions: Vec<LockedVersion>,
+    /// Bundle file size in bytes
+    pub size: u64,
+}
+
+/// Result of `bundle install`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BundleInstallReport {
+    pub installed: Vec<GoVersionInfo>,
+    /// Versions that were already installed
+    pub skipped: Vec<String>,
+    /// Whether the bundled release index replaced the cached one
+    pub index_updated: bool,
+}
+
+/// Result of `sync`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncReport {
+    pub installed: Vec<String>,
+    /// Already installed versions whose checksum matched the lockfile
+    pub verified: Vec<String>,
+    /// Versions not in the lockfile that were uninstalled
+    pub removed: Vec<String>,
+    pub default: Option<String>,
+}
+
+/// An installation that was not imported
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedImport {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Result of `doctor`
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub findings: Vec<Finding>,
+    /// Problems that remain after fixing
+    pub problems: usize,
+}
+
+/// Result of `cache list`
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheListReport {
+    pub archives: Vec<CachedArchive>,
+    pub total_size: u64,
+}
+
+/// Result of `cache size`
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheSizeReport {
+    pub path: PathBuf,
+    pub archive_count: usize,
+    pub archive_size: u64,
+    /// Size of everything in the cache, including the release index
+    pub total_size: u64,
+    /// Size budget of the cache policy
+    pub max_size: Option<u64>,
+}
+
+/// Result of `cache verify`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CacheVerifyReport {
+    pub archives: Vec<VerifiedArchive>,
+    /// Corrupt archives that were not removed
+    pub corrupt: usize,
+}
+
+/// A checked cache archive
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifiedArchive {
+    pub filename: String,
+    #[serde(flatten)]
+    pub check: ArchiveCheck,
+    pub removed: bool,
+}
+
+/// Result of `cache clean`
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheCleanReport {
+    pub removed: Vec<CachedArchive>,
+    pub freed: u64,
+    pub dry_run: bool,
+}
+
+/// Result of `audit`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AuditReport {
+    /// Supported minor series, newest first
+    pub supported_series: Vec<String>,
+    pub versions: Vec<VersionAudit>,
+    pub vulnerable: usize,
+    pub unsupported: usize,
+}
+
+/// Result of `info`
+#[derive(Debug, Clone, Serialize)]
+pub struct InfoReport {
+    #[serde(flatten)]
+    pub version: GoVersionInfo,
+    /// Details from the release index and history, if the version is a known release
+    pub release: Option<ReleaseDetails>,
+}
+
+/// A release as listed in the release index and history
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseDetails {
+    pub stable: bool,
+    pub date: Option<NaiveDate>,
+    pub kind: Option<ReleaseKind>,
+    /// Release notes summary
+    pub summary: Option<String>,
+    pub files: Vec<GoFile>,
+}
+
+/// Result of `list --all`
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseListReport {
+    /// Platform whose archives are shown, as "os/arch"
+    pub platform: String,
+    pub versions: Vec<ListedRelease>,
+    pub total_count: usize,
+}
+
+/// A release listed by `list --all`
+#[derive(Debug, Clone, Serialize)]
+pub struct ListedRelease {
+    pub version: String,
+    pub stable: bool,
+    /// Whether the series is older than the supported ones
+    pub archived: bool,
+    /// Archive for the listed platform, if one is published
+    pub archive: Option<GoFile>,
+    pub is_installed: bool,
+    pub is_cached: bool,
+    pub is_current: bool,
+}
+
+/// Result of `diff`
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffReport {
+    pub from: String,
+    pub to: String,
+    /// Releases after `from` up to and including `to`
+    pub releases: Vec<ReleaseSummary>,
+}
+
+/// A release listed by `diff`
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseSummary {
+    pub version: String,
+    pub date: Option<NaiveDate>,
+    pub kind: Option<ReleaseKind>,
+    pub summary: Option<String>,
+}
+
+/// Result of `prune`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PruneReport {
+    pub removed: Vec<PrunedVersion>,
+    /// Superseded versions that were kept
+    pub kept: Vec<KeptVersion>,
+    /// Disk space freed (or that would be freed)
+    pub reclaimed: u64,
+    pub dry_run: bool,
+}
+
+/// A version removed by `prune`
+#[derive(Debug, Clone, Serialize)]
+pub struct PrunedVersion {
+    pub version: String,
+    pub size: u64,
+}
+
+/// A superseded version kept by `prune`
+#[derive(Debug, Clone, Serialize)]
+pub struct KeptVersion {
+    pub version: String,
+    pub reason: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::GvmError;
+
+    #[test]
+    fn test_error_info() {
+        let err = anyhow::Error::new(GvmError::VersionNotInstalled("9.9.9".to_string()));
+        let json = serde_json::to_value(ErrorInfo::new(&err)).unwrap();
+        assert_eq!(json["code"], "version-not-installed");
+        assert_eq!(json["kind"], "not_installed");
+        assert_eq!(json["exit_code"], 3);
+        assert_eq!(json["message"], "Go version 9.9.9 is not installed");
+    }
+}
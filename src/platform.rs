@@ -24,6 +24,16 @@ impl PlatformInfo {
         format!("go{}.{}-{}.{}", version, self.os, self.arch, self.extension)
     }
 
+    /// Gets the `GOARCH` value for this platform
+    ///
+    /// Archive names use "armv6l" where Go itself reports "arm".
+    pub fn goarch(&self) -> &str {
+        match self.arch.as_str() {
+            "armv6l" => "arm",
+            arch => arch,
+        }
+    }
+
     /// Gets the Go executable filename (including platform-specific extension)
     pub fn go_executable_name() -> &'static str {
         if cfg!(target_os = "windows") {
@@ -69,6 +79,19 @@ fn detect_archive_extension() -> &'static str {
     }
 }
 
+/// Parses a platform such as "linux/arm64" into its OS and architecture (for clap)
+///
+/// # Errors
+/// Returns an error if the value is not of the form `os/arch`.
+pub fn parse_platform(value: &str) -> Result<(String, String), String> {
+    match value.split_once('/') {
+        Some((os, arch)) if !os.is_empty() && !arch.is_empty() && !arch.contains('/') => {
+            Ok((os.to_string(), arch.to_string()))
+        }
+        _ => Err(format!("invalid platform '{value}' (expected os/arch, e.g. linux/arm64)")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,6 +114,15 @@ mod tests {
         assert!(filename.ends_with(&platform.extension));
     }
 
+    #[test]
+    fn test_goarch() {
+        let mut platform = PlatformInfo::detect();
+        platform.arch = "armv6l".to_string();
+        assert_eq!(platform.goarch(), "arm");
+        platform.arch = "arm64".to_string();
+        assert_eq!(platform.goarch(), "arm64");
+    }
+
     #[test]
     fn test_go_executable_name() {
         let exe_name = PlatformInfo::go_executable_name();
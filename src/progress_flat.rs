@@ -1,43 +1,56 @@
 //! Basic progress display system
 //!
 //! Inspired by the simple progress display style of tools like Scoop.
+//! Progress is written to stderr: redrawn in place on a terminal, as periodic plain lines
+//! otherwise (see [`crate::terminal::progress_style`]).
 
+use crate::terminal::{progress_style, use_colors, ProgressStyle, Stream, PROGRESS_LINE_INTERVAL};
+use crate::ui_flat::{verbosity, Verbosity};
 use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Basic progress indicator
 #[derive(Clone)]
 pub struct BasicProgress {
     label: String,
     use_colors: bool,
+    style: ProgressStyle,
+    /// Time of the last line printed in line mode
+    last_line: Arc<Mutex<Option<Instant>>>,
 }
 
 impl BasicProgress {
     /// Creates a new progress indicator
     pub fn new(label: String) -> Self {
-        let use_colors = Self::should_use_colors();
-        Self { label, use_colors }
-    }
-
-    /// Detects if colors should be used
-    fn should_use_colors() -> bool {
-        std::env::var("NO_COLOR").is_err() && std::env::var("TERM").unwrap_or_default() != "dumb"
+        Self {
+            label,
+            use_colors: use_colors(Stream::Stderr),
+            style: progress_style(),
+            last_line: Arc::new(Mutex::new(None)),
+        }
     }
 
     /// Displays progress (Scoop style)
     pub fn show(&self, percent: f64, info: Option<&str>) {
-        let bar_width = 50;
-        let filled = (percent * bar_width as f64) as usize;
-        let empty = bar_width - filled;
-
-        // Build progress bar with simple characters
-        let bar = format!("{}{}", "=".repeat(filled), " ".repeat(empty));
-
         let info_text = info.unwrap_or("");
-
-        // Both branches are the same, merged into one
-        print!("\r{} [{}] {:.0}% {}", self.label, bar, percent * 100.0, info_text);
-
-        io::stdout().flush().ok();
+        match self.style {
+            ProgressStyle::Hidden => {}
+            ProgressStyle::Interactive => {
+                let bar = render_bar(percent, 50);
+                eprint!("\r{} [{}] {:.0}% {}", self.label, bar, percent * 100.0, info_text);
+                io::stderr().flush().ok();
+            }
+            ProgressStyle::Lines => {
+                let Ok(mut last_line) = self.last_line.lock() else {
+                    return;
+                };
+                if last_line.map_or(true, |t| t.elapsed() >= PROGRESS_LINE_INTERVAL) {
+                    eprintln!("{} {:.0}% {}", self.label, percent * 100.0, info_text);
+                    *last_line = Some(Instant::now());
+                }
+            }
+        }
     }
 
     /// Displays download progress
@@ -53,21 +66,31 @@ impl BasicProgress {
 
     /// Finalizes the progress display as done
     pub fn done(&self, message: &str) {
-        println!();
+        if self.style == ProgressStyle::Hidden {
+            return;
+        }
+        self.end_line();
         if self.use_colors {
-            println!("{message} ... \x1b[32mdone\x1b[0m.");
+            eprintln!("{message} ... \x1b[32mdone\x1b[0m.");
         } else {
-            println!("{message} ... done.");
+            eprintln!("{message} ... done.");
         }
     }
 
     /// Finalizes the progress display as failed
     pub fn failed(&self, message: &str) {
-        println!();
+        self.end_line();
         if self.use_colors {
-            println!("{message} ... \x1b[31mfailed\x1b[0m.");
+            eprintln!("{message} ... \x1b[31mfailed\x1b[0m.");
         } else {
-            println!("{message} ... failed.");
+            eprintln!("{message} ... failed.");
+        }
+    }
+
+    /// Ends the line of an in-place progress bar
+    fn end_line(&self) {
+        if self.style == ProgressStyle::Interactive {
+            eprintln!();
         }
     }
 }
@@ -75,6 +98,7 @@ impl BasicProgress {
 /// Installation steps manager
 pub struct InstallSteps {
     use_colors: bool,
+    quiet: bool,
 }
 
 impl Default for InstallSteps {
@@ -85,46 +109,166 @@ impl Default for InstallSteps {
 
 impl InstallSteps {
     pub fn new() -> Self {
-        let use_colors = std::env::var("NO_COLOR").is_err()
-            && std::env::var("TERM").unwrap_or_default() != "dumb";
-        Self { use_colors }
+        Self { use_colors: use_colors(Stream::Stderr), quiet: verbosity() == Verbosity::Quiet }
     }
 
     /// Displays the start of the installation
     pub fn start(&self, version: &str) {
+        if self.quiet {
+            return;
+        }
         if self.use_colors {
-            println!("\x1b[36mInstalling 'go' ({version}) [64bit] from 'workspace'\x1b[0m");
+            eprintln!("\x1b[36mInstalling 'go' ({version}) [64bit] from 'workspace'\x1b[0m");
         } else {
-            println!("Installing 'go' ({version}) [64bit] from 'workspace'");
+            eprintln!("Installing 'go' ({version}) [64bit] from 'workspace'");
         }
     }
 
     /// Displays step information
     pub fn info(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
         if self.use_colors {
-            println!("\x1b[90m{message}\x1b[0m");
+            eprintln!("\x1b[90m{message}\x1b[0m");
         } else {
-            println!("{message}");
+            eprintln!("{message}");
         }
     }
 
     /// Displays a warning message
     pub fn warn(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
         if self.use_colors {
-            println!("\x1b[33mWARN\x1b[0m {message}");
+            eprintln!("\x1b[33mWARN\x1b[0m {message}");
         } else {
-            println!("WARN {message}");
+            eprintln!("WARN {message}");
         }
     }
 
     /// Displays the completion message
     pub fn complete(&self, version: &str) {
+        if self.quiet {
+            return;
+        }
         if self.use_colors {
-            println!("\x1b[32m'go' ({version}) was installed successfully!\x1b[0m");
+            eprintln!("\x1b[32m'go' ({version}) was installed successfully!\x1b[0m");
         } else {
-            println!("'go' ({version}) was installed successfully!");
+            eprintln!("'go' ({version}) was installed successfully!");
+        }
+    }
+}
+
+/// Multi-line progress display for concurrent operations
+///
+/// Each operation owns one line, redrawn in place as its status changes.
+#[derive(Clone)]
+pub struct MultiProgress {
+    state: Arc<Mutex<MultiProgressState>>,
+    style: ProgressStyle,
+}
+
+struct MultiProgressState {
+    lines: Vec<(String, String)>,
+    drawn: usize,
+    last_draw: Option<Instant>,
+}
+
+impl MultiProgress {
+    /// Minimum interval between redraws for download updates
+    const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Creates a display with one line per label
+    pub fn new(labels: &[String]) -> Self {
+        let lines = labels.iter().map(|label| (label.clone(), "waiting".to_string())).collect();
+        let progress = Self {
+            state: Arc::new(Mutex::new(MultiProgressState { lines, drawn: 0, last_draw: None })),
+            style: progress_style(),
+        };
+        if progress.style == ProgressStyle::Interactive {
+            progress.redraw(true);
         }
+        progress
     }
+
+    /// Sets the status text of a line
+    pub fn set(&self, index: usize, status: &str) {
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(line) = state.lines.get_mut(index) {
+                line.1 = status.to_string();
+            }
+        }
+        self.update(index, true);
+    }
+
+    /// Shows download progress on a line
+    pub fn set_download(&self, index: usize, downloaded: u64, total: u64) {
+        let percent = if total > 0 { downloaded as f64 / total as f64 } else { 0.0 };
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(line) = state.lines.get_mut(index) {
+                line.1 = format!(
+                    "[{}] {:>3.0}% {}/{}",
+                    render_bar(percent, 30),
+                    percent * 100.0,
+                    format_size(downloaded),
+                    format_size(total)
+                );
+            }
+        }
+        self.update(index, downloaded >= total);
+    }
+
+    /// Shows a changed line, redrawing everything on a terminal or printing just that line
+    fn update(&self, index: usize, force: bool) {
+        match self.style {
+            ProgressStyle::Hidden => {}
+            ProgressStyle::Interactive => self.redraw(force),
+            ProgressStyle::Lines => {
+                let Ok(mut state) = self.state.lock() else {
+                    return;
+                };
+                if !force && state.last_draw.is_some_and(|t| t.elapsed() < PROGRESS_LINE_INTERVAL) {
+                    return;
+                }
+                if let Some((label, status)) = state.lines.get(index) {
+                    eprintln!("{label}  {status}");
+                }
+                state.last_draw = Some(Instant::now());
+            }
+        }
+    }
+
+    /// Redraws all lines, moving the cursor back over the previous drawing
+    fn redraw(&self, force: bool) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        if !force && state.last_draw.is_some_and(|t| t.elapsed() < Self::REDRAW_INTERVAL) {
+            return;
+        }
+
+        let width = state.lines.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        let mut out = String::new();
+        if state.drawn > 0 {
+            out.push_str(&format!("\x1b[{}A", state.drawn));
+        }
+        for (label, status) in &state.lines {
+            out.push_str(&format!("\r\x1b[2K{label:<width$}  {status}\n"));
+        }
+
+        eprint!("{out}");
+        io::stderr().flush().ok();
+        state.drawn = state.lines.len();
+        state.last_draw = Some(Instant::now());
+    }
+}
+
+/// Renders a bar of `width` characters filled to `percent`
+fn render_bar(percent: f64, width: usize) -> String {
+    let filled = ((percent.clamp(0.0, 1.0) * width as f64) as usize).min(width);
+    format!("{}{}", "=".repeat(filled), " ".repeat(width - filled))
 }
 
 /// Formats a file size (simplified version)
@@ -156,6 +300,12 @@ mod tests {
         assert_eq!(format_size(500), "500 B");
     }
 
+    #[test]
+    fn test_render_bar() {
+        assert_eq!(render_bar(0.5, 10), "=====     ");
+        assert_eq!(render_bar(1.5, 4), "====");
+    }
+
     #[test]
     fn test_basic_progress_creation() {
         let progress = BasicProgress::new("Testing".to_string());
@@ -0,0 +1,95 @@
+//! Project pin module
+//!
+//! Finds the Go versions that projects pin: `.go-version` files and the `toolchain`
+//! directive of `go.mod`. Pinned versions are kept by `gvm prune` and updated by
+//! `gvm upgrade`.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// File pinning an exact Go version
+pub const GO_VERSION_FILE: &str = ".go-version";
+
+/// Go module file, whose `toolchain` directive pins a version
+pub const GO_MOD_FILE: &str = "go.mod";
+
+/// A Go version pinned by a project file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Pin {
+    pub file: PathBuf,
+    /// Pinned version name (e.g. "1.21.3")
+    pub version: String,
+}
+
+/// Finds the pins in `dir` and each of its parent directories
+pub fn find_pins(dir: &Path) -> Vec<Pin> {
+    dir.ancestors().flat_map(read_pins).collect()
+}
+
+/// Reads the pins of a single directory
+pub fn read_pins(dir: &Path) -> Vec<Pin> {
+    let mut pins = Vec::new();
+    let go_version = dir.join(GO_VERSION_FILE);
+    if let Some(version) = read_file(&go_version).as_deref().and_then(parse_go_version_file) {
+        pins.push(Pin { file: go_version, version });
+    }
+    let go_mod = dir.join(GO_MOD_FILE);
+    if let Some(version) = read_file(&go_mod).as_deref().and_then(parse_go_mod_toolchain) {
+        pins.push(Pin { file: go_mod, version });
+    }
+    pins
+}
+
+/// Replaces the version pinned by a `.go-version` file
+///
+/// # Errors
+/// Returns an error if the file cannot be written.
+pub fn write_go_version_file(path: &Path, version: &str) -> std::io::Result<()> {
+    std::fs::write(path, format!("{version}\n"))
+}
+
+fn read_file(path: &Path) -> Option<String> {
+    path.is_file().then(|| std::fs::read_to_string(path).ok()).flatten()
+}
+
+/// Parses a `.go-version` file: the first non-empty line, with or without the "go" prefix
+fn parse_go_version_file(content: &str) -> Option<String> {
+    let line = content.lines().map(str::trim).find(|line| !line.is_empty())?;
+    Some(line.strip_prefix("go").unwrap_or(line).to_string())
+}
+
+/// Parses the `toolchain go1.21.3` directive of a `go.mod` file
+fn parse_go_mod_toolchain(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let toolchain = line.trim().strip_prefix("toolchain")?.trim();
+        let toolchain = toolchain.split("//").next()?.trim();
+        toolchain.strip_prefix("go").map(String::from)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_pins() {
+        let temp = tempfile::tempdir().unwrap();
+        let project = temp.path().join("project");
+        let module = project.join("module");
+        std::fs::create_dir_all(&module).unwrap();
+        std::fs::write(project.join(GO_VERSION_FILE), "\ngo1.21.3\n").unwrap();
+        std::fs::write(
+            module.join(GO_MOD_FILE),
+            "module example.com/m\n\ngo 1.21\n\ntoolchain go1.22.5 // pinned\n",
+        )
+        .unwrap();
+
+        let pins = find_pins(&module);
+        let versions: Vec<_> = pins.iter().map(|p| p.version.as_str()).collect();
+        assert_eq!(versions, ["1.22.5", "1.21.3"]);
+        assert_eq!(pins[1].file, project.join(GO_VERSION_FILE));
+
+        std::fs::write(module.join(GO_MOD_FILE), "module example.com/m\n\ngo 1.21\n").unwrap();
+        assert!(read_pins(&module).is_empty());
+    }
+}
@@ -0,0 +1,483 @@
+//! Go release index module
+//!
+//! Fetches the official release index (`/dl/?mode=json&include=all`) from the configured
+//! mirror, caches it in the cache directory and resolves version specifications such as
+//! "latest" or "1.21" against it.
+
+use crate::error::GvmError;
+use crate::version::GoVersion;
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Filename of the cached release index in the cache directory
+pub const INDEX_CACHE_FILE: &str = "releases.json";
+
+/// Cached index age after which it is refreshed
+const INDEX_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// A downloadable file of a Go release
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoFile {
+    pub filename: String,
+    pub os: String,
+    pub arch: String,
+    pub version: String,
+    pub sha256: String,
+    pub size: u64,
+    /// "archive", "installer" or "source"
+    pub kind: String,
+}
+
+/// A Go release as listed in the release index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoRelease {
+    /// Version with "go" prefix (e.g. "go1.22.3")
+    pub version: String,
+    pub stable: bool,
+    #[serde(default)]
+    pub files: Vec<GoFile>,
+}
+
+impl GoRelease {
+    /// Gets the version number without the "go" prefix
+    pub fn version_number(&self) -> &str {
+        self.version.strip_prefix("go").unwrap_or(&self.version)
+    }
+
+    /// Finds the archive for a platform
+    pub fn archive_for(&self, os: &str, arch: &str) -> Option<&GoFile> {
+        self.files.iter().find(|f| f.kind == "archive" && f.os == os && f.arch == arch)
+    }
+
+    /// Checks whether the release belongs to a series older than the oldest supported one
+    pub fn is_archived(&self, oldest_supported: Option<(u32, u32)>) -> bool {
+        let series = GoVersion::parse(self.version_number()).map(|v| v.series());
+        series.zip(oldest_supported).is_some_and(|(series, oldest)| series < oldest)
+    }
+}
+
+/// The Go release index
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReleaseIndex {
+    /// Releases, newest first
+    pub releases: Vec<GoRelease>,
+}
+
+impl ReleaseIndex {
+    /// Creates an index from releases, sorting them newest first
+    pub fn new(mut releases: Vec<GoRelease>) -> Self {
+        releases.sort_by(|a, b| {
+            crate::version::compare_versions(b.version_number(), a.version_number())
+        });
+        Self { releases }
+    }
+
+    /// Builds the index URL for a mirror
+    pub fn url(mirror: &str) -> String {
+        format!("{}/?mode=json&include=all", mirror.trim_end_matches('/'))
+    }
+
+    /// Loads the index, preferring a fresh cached copy and falling back to a stale one
+    /// when the mirror cannot be reached
+    ///
+    /// # Errors
+    /// Returns an error if the index can neither be fetched nor read from the cache.
+    pub async fn load(mirror: &str, cache_dir: &Path) -> Result<Self> {
+        let cache_file = Self::cache_file(cache_dir);
+        let cache_age = std::fs::metadata(&cache_file)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok());
+
+        if cache_age.is_some_and(|age| age < INDEX_MAX_AGE) {
+            if let Ok(index) = Self::read_cache(&cache_file) {
+                debug!("Using cached release index {}", cache_file.display());
+                return Ok(index);
+            }
+        }
+
+        match Self::fetch(mirror).await {
+            Ok(index) => {
+                if let Err(e) = index.write_cache(&cache_file) {
+                    warn!("Failed to cache release index: {e}");
+                }
+                Ok(index)
+            }
+            Err(e) if cache_file.exists() => {
+                warn!("Failed to fetch release index, using cached copy: {e}");
+                Self::read_cache(&cache_file)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Fetches the index from a mirror
+    ///
+    /// # Errors
+    /// Returns an error if the request fails or the response is not a valid index.
+    pub async fn fetch(mirror: &str) -> Result<Self> {
+        let url = Self::url(mirror);
+        let content = crate::downloader::fetch_text(&url, "release index").await?;
+        let releases: Vec<GoRelease> = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid release index from {url}"))?;
+        Ok(Self::new(releases))
+    }
+
+    /// Gets the cache file path for a cache directory
+    pub fn cache_file(cache_dir: &Path) -> PathBuf {
+        cache_dir.join(INDEX_CACHE_FILE)
+    }
+
+    /// Reads the cached index without contacting the mirror, however old it is
+    ///
+    /// # Errors
+    /// Returns an error if there is no cached index or it cannot be parsed.
+    pub fn read_cached(cache_dir: &Path) -> Result<Self> {
+        Self::read_cache(&Self::cache_file(cache_dir))
+    }
+
+    /// Finds the release file with the given filename
+    pub fn find_file(&self, filename: &str) -> Option<&GoFile> {
+        self.releases.iter().flat_map(|r| &r.files).find(|f| f.filename == filename)
+    }
+
+    fn read_cache(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(Self::new(serde_json::from_str(&content)?))
+    }
+
+    fn write_cache(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(&self.releases)?)?;
+        Ok(())
+    }
+
+    /// Finds a release by version number (with or without "go" prefix)
+    pub fn find(&self, version: &str) -> Option<&GoRelease> {
+        let version = version.strip_prefix("go").unwrap_or(version);
+        self.releases.iter().find(|r| r.version_number() == version)
+    }
+
+    /// Gets the oldest supported minor series; newer pre-release series are not archived
+    pub fn oldest_supported_series(&self) -> Option<(u32, u32)> {
+        crate::audit::supported_series(self).last().copied()
+    }
+
+    /// Gets the newest stable release
+    pub fn latest_stable(&self) -> Option<&GoRelease> {
+        self.releases.iter().find(|r| r.stable)
+    }
+
+    /// Gets the newest release of a minor series, preferring stable releases
+    pub fn latest_in_series(&self, major: u32, minor: u32) -> Option<&GoRelease> {
+        let in_series = |r: &&GoRelease| {
+            GoVersion::parse(r.version_number()).is_some_and(|v| v.series() == (major, minor))
+        };
+        self.releases
+            .iter()
+            .filter(in_series)
+            .find(|r| r.stable)
+            .or_else(|| self.releases.iter().find(in_series))
+    }
+
+    /// Plans upgrades of the installed releases to the newest release of their series
+    ///
+    /// Only the newest installed version of each series is upgraded; with `series`, only
+    /// that minor series is considered.
+    pub fn upgrades(&self, installed: &[String], series: Option<(u32, u32)>) -> Vec<Upgrade> {
+        let mut newest: Vec<GoVersion> = Vec::new();
+        for version in installed.iter().filter_map(|name| GoVersion::parse(name)) {
+            if series.is_some_and(|series| series != version.series()) {
+                continue;
+            }
+            match newest.iter_mut().find(|v| v.series() == version.series()) {
+                Some(existing) if *existing < version => *existing = version,
+                Some(_) => {}
+                None => newest.push(version),
+            }
+        }
+        newest.sort();
+
+        newest
+            .into_iter()
+            .filter_map(|from| {
+                let latest = self.latest_in_series(from.major, from.minor)?;
+                let to = GoVersion::parse(latest.version_number())?;
+                (to > from).then(|| Upgrade { from: from.to_string(), to: to.to_string() })
+            })
+            .collect()
+    }
+
+    /// Gets the stable releases after `from` up to and including `to`, oldest first
+    pub fn releases_between(&self, from: &GoVersion, to: &GoVersion) -> Vec<&GoRelease> {
+        let mut between: Vec<(GoVersion, &GoRelease)> = self
+            .releases
+            .iter()
+            .filter(|r| r.stable)
+            .filter_map(|r| Some((GoVersion::parse(r.version_number())?, r)))
+            .filter(|(version, _)| version > from && version <= to)
+            .collect();
+        between.sort_by(|a, b| a.0.cmp(&b.0));
+        between.into_iter().map(|(_, release)| release).collect()
+    }
+
+    /// Resolves a version specification to a concrete version number
+    ///
+    /// # Errors
+    /// Returns an error if no release matches the specification.
+    pub fn resolve(&self, spec: &str) -> Result<String> {
+        let release = match VersionSpec::parse(spec)? {
+            VersionSpec::Latest => self.latest_stable(),
+            VersionSpec::Series(major, minor) => self.latest_in_series(major, minor),
+            VersionSpec::Exact(version) => self.find(&version),
+        };
+        release
+            .map(|r| r.version_number().to_string())
+            .ok_or_else(|| GvmError::ReleaseNotFound(spec.to_string()).into())
+    }
+}
+
+/// A planned upgrade within a minor series
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Upgrade {
+    /// Newest installed version of the series
+    pub from: String,
+    /// Newest release of the series
+    pub to: String,
+}
+
+/// Stability of a release
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stability {
+    Stable,
+    /// Release candidates and betas
+    Unstable,
+}
+
+/// Selects releases of the index for `gvm list --all`
+///
+/// All given conditions must hold; `latest_per_series` then keeps the newest of the
+/// selected releases of each minor series.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReleaseFilter {
+    pub stability: Option<Stability>,
+    /// Only releases of series older than the supported ones
+    pub archived: bool,
+    pub series: Option<(u32, u32)>,
+    pub latest_per_series: bool,
+    /// Only releases with an archive for this platform (os, arch)
+    pub platform: Option<(String, String)>,
+    /// Only installed (`true`) or not installed (`false`) releases
+    pub installed: Option<bool>,
+}
+
+impl ReleaseFilter {
+    /// Selects the matching releases, newest first
+    pub fn select<'a>(&self, index: &'a ReleaseIndex, installed: &[String]) -> Vec<&'a GoRelease> {
+        let oldest_supported = index.oldest_supported_series();
+        let mut seen_series = Vec::new();
+        index
+            .releases
+            .iter()
+            .filter(|release| {
+                let version = GoVersion::parse(release.version_number());
+                let series = version.as_ref().map(GoVersion::series);
+                let stable = match self.stability {
+                    Some(Stability::Stable) => release.stable,
+                    Some(Stability::Unstable) => !release.stable,
+                    None => true,
+                };
+                let archived = !self.archived || release.is_archived(oldest_supported);
+                let in_series = self.series.is_none() || series == self.series;
+                let on_platform = self
+                    .platform
+                    .as_ref()
+                    .map_or(true, |(os, arch)| release.archive_for(os, arch).is_some());
+                let is_installed = installed.iter().any(|v| v == release.version_number());
+                let installed = self.installed.map_or(true, |wanted| wanted == is_installed);
+                stable && archived && in_series && on_platform && installed
+            })
+            .filter(|release| {
+                if !self.latest_per_series {
+                    return true;
+                }
+                // Releases are sorted newest first, so the first of a series is its newest
+                let series = GoVersion::parse(release.version_number()).map(|v| v.series());
+                if seen_series.contains(&series) {
+                    false
+                } else {
+                    seen_series.push(series);
+                    true
+                }
+            })
+            .collect()
+    }
+}
+
+/// Parses a minor series such as "1.21" (for clap)
+///
+/// # Errors
+/// Returns an error if the value is not a minor series.
+pub fn parse_series(value: &str) -> std::result::Result<(u32, u32), String> {
+    match VersionSpec::parse(value) {
+        Ok(VersionSpec::Series(major, minor)) => Ok((major, minor)),
+        _ => Err(format!("invalid series '{value}' (expected e.g. 1.21)")),
+    }
+}
+
+/// A version requested on the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionSpec {
+    /// The newest stable release
+    Latest,
+    /// The newest release of a minor series (e.g. "1.21")
+    Series(u32, u32),
+    /// An exact version (e.g. "1.21.3", "1.22rc1")
+    Exact(String),
+}
+
+impl VersionSpec {
+    /// Parses a version specification
+    ///
+    /// # Errors
+    /// Returns an error if the specification is not a valid Go version.
+    pub fn parse(spec: &str) -> Result<Self> {
+        if spec.eq_ignore_ascii_case("latest") {
+            return Ok(Self::Latest);
+        }
+
+        let version =
+            GoVersion::parse(spec).ok_or_else(|| GvmError::InvalidVersion(spec.to_string()))?;
+        if version.patch.is_none() && version.pre.is_none() {
+            Ok(Self::Series(version.major, version.minor))
+        } else {
+            Ok(Self::Exact(version.to_string()))
+        }
+    }
+
+    /// Checks whether resolving this specification needs the release index
+    pub fn needs_index(&self) -> bool {
+        !matches!(self, Self::Exact(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(version: &str, stable: bool) -> GoRelease {
+        GoRelease { version: format!("go{version}"), stable, files: Vec::new() }
+    }
+
+    fn index() -> ReleaseIndex {
+        ReleaseIndex::new(vec![
+            release("1.20.14", true),
+            release("1.22rc1", false),
+            release("1.21.13", true),
+            release("1.20", true),
+            release("1.21.12", true),
+        ])
+    }
+
+    #[test]
+    fn test_resolve() {
+        let index = index();
+        assert_eq!(index.resolve("latest").unwrap(), "1.21.13");
+        assert_eq!(index.resolve("1.20").unwrap(), "1.20.14");
+        assert_eq!(index.resolve("1.22").unwrap(), "1.22rc1");
+        assert_eq!(index.resolve("go1.21.12").unwrap(), "1.21.12");
+        assert!(index.resolve("1.19").is_err());
+        assert!(index.resolve("banana").is_err());
+    }
+
+    #[test]
+    fn test_upgrades() {
+        let index = index();
+        let installed: Vec<String> = ["1.20", "1.20.14", "1.21.3", "1.21.12", "tip-abc1234"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        let upgrade = |from: &str, to: &str| Upgrade { from: from.into(), to: to.into() };
+
+        assert_eq!(index.upgrades(&installed, None), [upgrade("1.21.12", "1.21.13")]);
+        assert!(index.upgrades(&installed, Some((1, 20))).is_empty());
+        assert_eq!(
+            index.upgrades(&["1.20.1".to_string(), "1.21.3".to_string()], Some((1, 20))),
+            [upgrade("1.20.1", "1.20.14")]
+        );
+    }
+
+    #[test]
+    fn test_releases_between() {
+        let index = index();
+        let between = |from: &str, to: &str| -> Vec<String> {
+            let (from, to) = (GoVersion::parse(from).unwrap(), GoVersion::parse(to).unwrap());
+            index.releases_between(&from, &to).iter().map(|r| r.version.clone()).collect()
+        };
+        assert_eq!(between("1.20.14", "1.21.13"), ["go1.21.12", "go1.21.13"]);
+        assert_eq!(between("1.20", "1.21.12"), ["go1.20.14", "go1.21.12"]);
+        assert!(between("1.21.13", "1.21.12").is_empty());
+    }
+
+    #[test]
+    fn test_release_filter() {
+        let mut index = index();
+        index.releases.push(release("1.19.13", true));
+        index.releases[0].files.push(GoFile {
+            filename: "go1.22rc1.linux-arm64.tar.gz".into(),
+            os: "linux".into(),
+            arch: "arm64".into(),
+            version: "go1.22rc1".into(),
+            sha256: String::new(),
+            size: 1,
+            kind: "archive".into(),
+        });
+        let index = ReleaseIndex::new(index.releases);
+        let installed = ["1.21.12".to_string()];
+        let select = |filter: ReleaseFilter| -> Vec<&str> {
+            filter.select(&index, &installed).iter().map(|r| r.version_number()).collect()
+        };
+
+        assert_eq!(select(ReleaseFilter::default()).len(), 6);
+        let stable = ReleaseFilter { stability: Some(Stability::Stable), ..Default::default() };
+        let latest_stable = ReleaseFilter { latest_per_series: true, ..stable.clone() };
+        assert_eq!(select(latest_stable), ["1.21.13", "1.20.14", "1.19.13"]);
+        assert_eq!(
+            select(ReleaseFilter { stability: Some(Stability::Unstable), ..Default::default() }),
+            ["1.22rc1"]
+        );
+        assert_eq!(select(ReleaseFilter { archived: true, ..Default::default() }), ["1.19.13"]);
+        assert_eq!(
+            select(ReleaseFilter { series: Some((1, 20)), ..Default::default() }),
+            ["1.20.14", "1.20"]
+        );
+        let platform = Some(("linux".to_string(), "arm64".to_string()));
+        assert_eq!(select(ReleaseFilter { platform, ..Default::default() }), ["1.22rc1"]);
+        assert_eq!(select(ReleaseFilter { installed: Some(true), ..stable }), ["1.21.12"]);
+
+        assert_eq!(parse_series("1.21"), Ok((1, 21)));
+        assert!(parse_series("1.21.3").is_err());
+    }
+
+    #[test]
+    fn test_version_spec() {
+        assert_eq!(VersionSpec::parse("latest").unwrap(), VersionSpec::Latest);
+        assert_eq!(VersionSpec::parse("1.21").unwrap(), VersionSpec::Series(1, 21));
+        assert_eq!(VersionSpec::parse("1.21.3").unwrap(), VersionSpec::Exact("1.21.3".into()));
+        assert!(!VersionSpec::parse("1.21rc1").unwrap().needs_index());
+    }
+
+    #[test]
+    fn test_index_cache_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = index();
+        let cache_file = ReleaseIndex::cache_file(dir.path());
+        index.write_cache(&cache_file).unwrap();
+        assert_eq!(ReleaseIndex::read_cache(&cache_file).unwrap(), index);
+    }
+}
@@ -0,0 +1,244 @@
+//! Event reporting module
+//!
+//! [`GoManager`](crate::GoManager) reports what it is doing as [`Event`]s to a [`Reporter`]
+//! instead of writing to the terminal, so the library can run headless. Events are dropped
+//! with [`SilentReporter`] unless another reporter is given: the CLI passes
+//! [`TerminalReporter`], and embedders can log events with [`JsonLinesReporter`].
+
+use crate::progress_flat::{BasicProgress, MultiProgress};
+use crate::ui_flat::SimpleUI;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Something that happened during an operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// An installation has started
+    InstallStarted { version: String },
+    /// Bytes of an archive downloaded so far
+    DownloadProgress { version: String, downloaded: u64, total: u64 },
+    /// Bytes of an archive extracted so far
+    ExtractProgress { version: String, extracted: u64, total: u64 },
+    /// A toolchain passed verification
+    Verified { version: String, go_version: String },
+    /// A version has been installed
+    Installed { version: String, path: PathBuf },
+    /// The active version has changed
+    Switched { version: String },
+    /// A problem that does not stop the operation
+    Warning { message: String },
+}
+
+/// Receiver of [`Event`]s
+pub trait Reporter: Send + Sync {
+    /// Handles an event
+    fn report(&self, event: &Event);
+}
+
+/// Drops all events
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SilentReporter;
+
+impl Reporter for SilentReporter {
+    fn report(&self, _event: &Event) {}
+}
+
+/// Writes each event as a line of JSON
+pub struct JsonLinesReporter<W: Write + Send> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> JsonLinesReporter<W> {
+    /// Creates a reporter writing to `writer`
+    pub fn new(writer: W) -> Self {
+        Self { writer: Mutex::new(writer) }
+    }
+
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl JsonLinesReporter<std::io::Stderr> {
+    /// Creates a reporter writing to stderr
+    pub fn stderr() -> Self {
+        Self::new(std::io::stderr())
+    }
+}
+
+impl<W: Write + Send> Reporter for JsonLinesReporter<W> {
+    fn report(&self, event: &Event) {
+        let Ok(line) = serde_json::to_string(event) else {
+            return;
+        };
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writeln!(writer, "{line}");
+            let _ = writer.flush();
+        }
+    }
+}
+
+/// Shows events as progress bars and messages on stderr
+///
+/// Install and switch events are left to the commands, which print their own summary.
+pub struct TerminalReporter {
+    ui: SimpleUI,
+    /// Progress bar of the current download or extraction, keyed by its label
+    bar: Mutex<Option<(String, BasicProgress)>>,
+}
+
+impl Default for TerminalReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TerminalReporter {
+    pub fn new() -> Self {
+        Self { ui: SimpleUI::new(), bar: Mutex::new(None) }
+    }
+
+    /// Updates the bar labelled `label`, finishing it once `done` reaches `total`
+    fn show(&self, label: String, done: u64, total: u64, finished: &str) {
+        let Ok(mut bar) = self.bar.lock() else {
+            return;
+        };
+        if bar.as_ref().map_or(true, |(current, _)| *current != label) {
+            *bar = Some((label.clone(), BasicProgress::new(label)));
+        }
+        if let Some((_, progress)) = bar.as_ref() {
+            if total > 0 {
+                progress.show_download(done, total);
+            }
+            if total > 0 && done >= total {
+                progress.done(finished);
+                *bar = None;
+            }
+        }
+    }
+}
+
+impl Reporter for TerminalReporter {
+    fn report(&self, event: &Event) {
+        match event {
+            Event::DownloadProgress { version, downloaded, total } => self.show(
+                format!("Downloading go{version}"),
+                *downloaded,
+                *total,
+                &format!("Downloaded go{version}"),
+            ),
+            Event::ExtractProgress { version, extracted, total } => self.show(
+                format!("Extracting go{version}"),
+                *extracted,
+                *total,
+                &format!("Extracted go{version}"),
+            ),
+            Event::Verified { go_version, .. } => self.ui.info(&format!("Verified {go_version}")),
+            Event::Warning { message } => self.ui.warning(message),
+            Event::InstallStarted { .. } | Event::Installed { .. } | Event::Switched { .. } => {}
+        }
+    }
+}
+
+/// Shows the events of concurrent installations on a [`MultiProgress`], one line per version
+pub struct MultiProgressReporter {
+    progress: MultiProgress,
+    lines: HashMap<String, usize>,
+}
+
+impl MultiProgressReporter {
+    /// Creates a reporter for `versions`, in display order
+    pub fn new(versions: &[String]) -> Self {
+        let labels: Vec<String> = versions.iter().map(|version| format!("go{version}")).collect();
+        let lines = versions.iter().enumerate().map(|(i, v)| (v.clone(), i)).collect();
+        Self { progress: MultiProgress::new(&labels), lines }
+    }
+
+    /// Sets the status text of a version
+    pub fn set(&self, version: &str, status: &str) {
+        if let Some(&index) = self.lines.get(version) {
+            self.progress.set(index, status);
+        }
+    }
+}
+
+impl Reporter for MultiProgressReporter {
+    fn report(&self, event: &Event) {
+        match event {
+            Event::InstallStarted { version } => self.set(version, "installing"),
+            Event::DownloadProgress { version, downloaded, total } => {
+                if let Some(&index) = self.lines.get(version) {
+                    self.progress.set_download(index, *downloaded, *total);
+                }
+            }
+            Event::ExtractProgress { version, extracted: 0, .. } => {
+                self.set(version, "extracting");
+            }
+            Event::Verified { version, .. } => self.set(version, "verified"),
+            Event::Installed { version, .. } => self.set(version, "done"),
+            Event::ExtractProgress { .. } | Event::Switched { .. } | Event::Warning { .. } => {}
+        }
+    }
+}
+
+/// Limits progress events to whole-percent steps
+///
+/// Downloads and extraction report every chunk; forwarding each one would flood line-based
+/// reporters. The final 100% step is always reported exactly once.
+#[derive(Debug)]
+pub(crate) struct ProgressThrottle {
+    last_percent: AtomicU64,
+}
+
+impl ProgressThrottle {
+    pub(crate) fn new() -> Self {
+        Self { last_percent: AtomicU64::new(u64::MAX) }
+    }
+
+    /// Checks whether progress at `done` of `total` should be reported
+    pub(crate) fn should_report(&self, done: u64, total: u64) -> bool {
+        let percent = done.saturating_mul(100).checked_div(total).unwrap_or(0);
+        self.last_percent.swap(percent, Ordering::Relaxed) != percent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_lines_reporter() {
+        let reporter = JsonLinesReporter::new(Vec::new());
+        reporter.report(&Event::InstallStarted { version: "1.21.0".to_string() });
+        reporter.report(&Event::DownloadProgress {
+            version: "1.21.0".to_string(),
+            downloaded: 10,
+            total: 20,
+        });
+
+        let output = String::from_utf8(reporter.into_inner()).unwrap();
+        let lines: Vec<serde_json::Value> =
+            output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["event"], "install_started");
+        assert_eq!(lines[0]["version"], "1.21.0");
+        assert_eq!(lines[1]["event"], "download_progress");
+        assert_eq!(lines[1]["downloaded"], 10);
+    }
+
+    #[test]
+    fn test_progress_throttle() {
+        let throttle = ProgressThrottle::new();
+        assert!(throttle.should_report(0, 1000));
+        assert!(!throttle.should_report(5, 1000));
+        assert!(throttle.should_report(10, 1000));
+        assert!(throttle.should_report(1000, 1000));
+        assert!(!throttle.should_report(1000, 1000));
+    }
+}
@@ -0,0 +1,493 @@
+//! LAN mirror module
+//!
+//! Serves the download cache over HTTP in the layout of `https://go.dev/dl`, so that other
+//! gvm instances can use it as their mirror:
+//!
+//! - `GET /?mode=json&include=all` returns a release index of the served archives
+//! - `GET /<filename>` returns an archive, honouring single `Range` requests so that
+//!   chunked downloads work
+//!
+//! Paths may also carry the `/dl` prefix of go.dev. With an upstream mirror, archives that
+//! are not cached are downloaded (and verified) on first request, and the index lists all
+//! upstream releases.
+
+use crate::cache::{is_archive_name, parse_archive_filename, Cache};
+use crate::downloader::Downloader;
+use crate::releases::{GoFile, GoRelease, ReleaseIndex};
+use crate::reporter::SilentReporter;
+use crate::version::GoVersion;
+use crate::GoManager;
+use anyhow::Result;
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Longest request head that is accepted
+const MAX_HEAD_SIZE: u64 = 16 * 1024;
+
+/// Time a client has to send its request head
+const HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Memoized archive checksums, keyed by filename, size and modification time
+type ChecksumCache = HashMap<(String, u64, SystemTime), String>;
+
+/// A parsed request
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    range: Option<String>,
+}
+
+/// What a request path refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Route {
+    Index { include_all: bool },
+    Archive(String),
+    NotFound,
+}
+
+/// A response head with an optional file body
+struct Response {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Body,
+}
+
+enum Body {
+    Bytes(Vec<u8>),
+    /// `len` bytes of a file starting at `start`
+    File {
+        path: PathBuf,
+        start: u64,
+        len: u64,
+    },
+}
+
+/// The cache mirror server
+pub struct Server {
+    cache_dir: PathBuf,
+    /// Mirror to pull uncached archives and the index from
+    upstream: Option<String>,
+    checksums: Mutex<ChecksumCache>,
+    /// Serializes pull-through downloads so that concurrent requests download once
+    pull_lock: tokio::sync::Mutex<()>,
+}
+
+impl Server {
+    pub fn new(cache_dir: impl Into<PathBuf>, upstream: Option<String>) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+            upstream,
+            checksums: Mutex::new(HashMap::new()),
+            pull_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Serves connections until the listener fails
+    ///
+    /// # Errors
+    /// Returns an error if accepting connections fails.
+    pub async fn run(self: Arc<Self>, listener: TcpListener) -> Result<()> {
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let server = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server.handle(stream).await {
+                    debug!("Connection from {peer} failed: {e}");
+                }
+            });
+        }
+    }
+
+    async fn handle(&self, mut stream: TcpStream) -> Result<()> {
+        let peer = stream.peer_addr()?;
+        let Ok(request) = tokio::time::timeout(HEAD_TIMEOUT, read_request(&mut stream)).await
+        else {
+            debug!("{peer} sent no request within {}s", HEAD_TIMEOUT.as_secs());
+            return Ok(());
+        };
+        let Some(request) = request? else {
+            return write_response(&mut stream, &status_response(400), false).await;
+        };
+
+        let response = match request.method.as_str() {
+            "GET" | "HEAD" => self.respond(&request).await,
+            _ => {
+                let mut response = status_response(405);
+                response.headers.push(("Allow", "GET, HEAD".to_string()));
+                response
+            }
+        };
+        info!("{peer} \"{} {}\" {}", request.method, request.path, response.status);
+        write_response(&mut stream, &response, request.method == "HEAD").await
+    }
+
+    async fn respond(&self, request: &Request) -> Response {
+        match route(&request.path, &request.query) {
+            Route::Index { include_all } => match self.index(include_all).await {
+                Ok(releases) => match serde_json::to_vec_pretty(&releases) {
+                    Ok(json) => Response {
+                        status: 200,
+                        headers: vec![("Content-Type", "application/json".to_string())],
+                        body: Body::Bytes(json),
+                    },
+                    Err(_) => status_response(500),
+                },
+                Err(e) => {
+                    warn!("Failed to build the release index: {e}");
+                    status_response(502)
+                }
+            },
+            Route::Archive(filename) => match self.archive(&filename).await {
+                Ok(Some(path)) => file_response(path, request.range.as_deref()),
+                Ok(None) => status_response(404),
+                Err(e) => {
+                    warn!("Failed to pull {filename} from upstream: {e}");
+                    status_response(502)
+                }
+            },
+            Route::NotFound => status_response(404),
+        }
+    }
+
+    /// Gets the releases to list: the upstream index when pulling through, otherwise the
+    /// cached archives
+    async fn index(&self, include_all: bool) -> Result<Vec<GoRelease>> {
+        let index = match &self.upstream {
+            Some(upstream) => ReleaseIndex::load(upstream, &self.cache_dir).await?,
+            None => self.local_index()?,
+        };
+        if include_all {
+            return Ok(index.releases);
+        }
+        // Like go.dev, only the stable releases of the supported series by default
+        let oldest_supported = index.oldest_supported_series();
+        Ok(index
+            .releases
+            .iter()
+            .filter(|r| r.stable && !r.is_archived(oldest_supported))
+            .cloned()
+            .collect())
+    }
+
+    /// Builds an index of the cached archives, taking checksums from the cached upstream
+    /// index where possible
+    fn local_index(&self) -> Result<ReleaseIndex> {
+        let upstream = ReleaseIndex::read_cached(&self.cache_dir).ok();
+        let mut releases: Vec<GoRelease> = Vec::new();
+        for archive in Cache::new(&self.cache_dir).archives()? {
+            let Some((version, platform)) = parse_archive_filename(&archive.filename) else {
+                continue;
+            };
+            let published = upstream.as_ref().and_then(|index| index.find_file(&archive.filename));
+            let sha256 = match published.filter(|file| file.size == archive.size) {
+                Some(file) => file.sha256.clone(),
+                None => self.checksum(&archive.path, &archive.filename, archive.size)?,
+            };
+            let (os, arch) = platform.unwrap_or_default();
+            let file = GoFile {
+                filename: archive.filename.clone(),
+                kind: if os.is_empty() { "source" } else { "archive" }.to_string(),
+                os,
+                arch,
+                version: format!("go{version}"),
+                sha256,
+                size: archive.size,
+            };
+
+            match releases.iter_mut().find(|r| r.version_number() == version) {
+                Some(release) => release.files.push(file),
+                None => {
+                    let stable = match upstream.as_ref().and_then(|index| index.find(&version)) {
+                        Some(release) => release.stable,
+                        None => GoVersion::parse(&version).is_some_and(|v| v.is_stable()),
+                    };
+                    releases.push(GoRelease {
+                        version: format!("go{version}"),
+                        stable,
+                        files: vec![file],
+                    });
+                }
+            }
+        }
+        Ok(ReleaseIndex::new(releases))
+    }
+
+    fn checksum(&self, path: &Path, filename: &str, size: u64) -> Result<String> {
+        let modified = std::fs::metadata(path)?.modified()?;
+        let key = (filename.to_string(), size, modified);
+        if let Some(sha256) = self.checksums.lock().ok().and_then(|c| c.get(&key).cloned()) {
+            return Ok(sha256);
+        }
+        let sha256 = crate::downloader::sha256_file(path)?;
+        if let Ok(mut checksums) = self.checksums.lock() {
+            checksums.insert(key, sha256.clone());
+        }
+        Ok(sha256)
+    }
+
+    /// Finds a cached archive, pulling it from upstream when configured
+    async fn archive(&self, filename: &str) -> Result<Option<PathBuf>> {
+        let path = self.cache_dir.join(filename);
+        if path.is_file() {
+            return Ok(Some(path));
+        }
+        let Some(upstream) = &self.upstream else {
+            return Ok(None);
+        };
+
+        let _pull = self.pull_lock.lock().await;
+        if path.is_file() {
+            return Ok(Some(path));
+        }
+        let index = ReleaseIndex::load(upstream, &self.cache_dir).await?;
+        let Some(file) = index.find_file(filename) else {
+            return Ok(None);
+        };
+        info!("Pulling {filename} from {upstream}");
+        let manager = GoManager::with_reporter(Arc::new(SilentReporter));
+        let path =
+            manager.fetch_archive(file, upstream, &self.cache_dir, &Downloader::new()).await?;
+        Ok(Some(path))
+    }
+}
+
+/// Reads and parses the request head
+///
+/// Heads longer than [`MAX_HEAD_SIZE`] or cut short are rejected.
+async fn read_request(stream: &mut TcpStream) -> Result<Option<Request>> {
+    let mut reader = BufReader::new(stream.take(MAX_HEAD_SIZE));
+    let mut head = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        // Reading stops at the size limit, so an unterminated line ends the head
+        if reader.read_line(&mut line).await? == 0 || !line.ends_with('\n') {
+            return Ok(None);
+        }
+        if line == "\r\n" || line == "\n" {
+            break;
+        }
+        head.push(line.trim_end().to_string());
+    }
+    Ok(parse_request(&head))
+}
+
+fn parse_request(head: &[String]) -> Option<Request> {
+    let mut parts = head.first()?.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?;
+    parts.next().filter(|version| version.starts_with("HTTP/"))?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let range = head.iter().skip(1).find_map(|header| {
+        let (name, value) = header.split_once(':')?;
+        name.trim().eq_ignore_ascii_case("range").then(|| value.trim().to_string())
+    });
+    Some(Request { method, path: path.to_string(), query: query.to_string(), range })
+}
+
+/// Maps a request path to what it refers to, accepting the `/dl` prefix of go.dev
+fn route(path: &str, query: &str) -> Route {
+    let path =
+        path.strip_prefix("/dl").filter(|p| p.is_empty() || p.starts_with('/')).unwrap_or(path);
+    let name = path.trim_start_matches('/');
+    if name.is_empty() {
+        let params: Vec<&str> = query.split('&').collect();
+        return if params.contains(&"mode=json") {
+            Route::Index { include_all: params.contains(&"include=all") }
+        } else {
+            Route::NotFound
+        };
+    }
+    if name.contains('/') || name.contains("..") || !is_archive_name(name) {
+        return Route::NotFound;
+    }
+    Route::Archive(name.to_string())
+}
+
+/// Parses a single `bytes=` range into inclusive bounds
+///
+/// Returns `None` for ranges that are ignored (other units or several ranges) and
+/// `Some(Err(()))` for unsatisfiable ones.
+fn parse_range(header: &str, len: u64) -> Option<Result<(u64, u64), ()>> {
+    let spec = header.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+    let bounds = if start.is_empty() {
+        // Suffix range: the last N bytes
+        let suffix: u64 = end.parse().ok()?;
+        (suffix > 0 && len > 0).then(|| (len.saturating_sub(suffix), len - 1))
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = if end.is_empty() { len.saturating_sub(1) } else { end.parse().ok()? };
+        (start < len && start <= end).then(|| (start, end.min(len - 1)))
+    };
+    Some(bounds.ok_or(()))
+}
+
+fn file_response(path: PathBuf, range: Option<&str>) -> Response {
+    let Ok(len) = std::fs::metadata(&path).map(|m| m.len()) else {
+        return status_response(404);
+    };
+    let mut headers = vec![
+        ("Content-Type", "application/octet-stream".to_string()),
+        ("Accept-Ranges", "bytes".to_string()),
+    ];
+    match range.and_then(|range| parse_range(range, len)) {
+        Some(Ok((start, end))) => {
+            headers.push(("Content-Range", format!("bytes {start}-{end}/{len}")));
+            Response {
+                status: 206,
+                headers,
+                body: Body::File { path, start, len: end - start + 1 },
+            }
+        }
+        Some(Err(())) => {
+            let mut response = status_response(416);
+            response.headers.push(("Content-Range", format!("bytes */{len}")));
+            response
+        }
+        None => Response { status: 200, headers, body: Body::File { path, start: 0, len } },
+    }
+}
+
+fn status_response(status: u16) -> Response {
+    Response {
+        status,
+        headers: vec![("Content-Type", "text/plain".to_string())],
+        body: Body::Bytes(format!("{status} {}\n", reason(status)).into_bytes()),
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        206 => "Partial Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        416 => "Range Not Satisfiable",
+        502 => "Bad Gateway",
+        _ => "Internal Server Error",
+    }
+}
+
+async fn write_response(
+    stream: &mut TcpStream,
+    response: &Response,
+    head_only: bool,
+) -> Result<()> {
+    let content_length = match &response.body {
+        Body::Bytes(bytes) => bytes.len() as u64,
+        Body::File { len, .. } => *len,
+    };
+    let mut head = format!("HTTP/1.1 {} {}\r\n", response.status, reason(response.status));
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str(&format!("Content-Length: {content_length}\r\nConnection: close\r\n\r\n"));
+    stream.write_all(head.as_bytes()).await?;
+
+    if !head_only {
+        match &response.body {
+            Body::Bytes(bytes) => stream.write_all(bytes).await?,
+            Body::File { path, start, len } => {
+                let mut file = tokio::fs::File::open(path).await?;
+                file.seek(SeekFrom::Start(*start)).await?;
+                tokio::io::copy(&mut file.take(*len), stream).await?;
+            }
+        }
+    }
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::downloader::DownloadConfig;
+
+    #[test]
+    fn test_route_and_range() {
+        assert_eq!(route("/", "mode=json&include=all"), Route::Index { include_all: true });
+        assert_eq!(route("/dl/", "mode=json"), Route::Index { include_all: false });
+        assert_eq!(
+            route("/dl/go1.22.5.linux-amd64.tar.gz", ""),
+            Route::Archive("go1.22.5.linux-amd64.tar.gz".into())
+        );
+        assert_eq!(route("/../secret.tar.gz", ""), Route::NotFound);
+        assert_eq!(route("/releases.json", ""), Route::NotFound);
+        assert_eq!(route("/dlx/go1.22.5.zip", ""), Route::NotFound);
+
+        assert_eq!(parse_range("bytes=0-99", 1000), Some(Ok((0, 99))));
+        assert_eq!(parse_range("bytes=900-", 1000), Some(Ok((900, 999))));
+        assert_eq!(parse_range("bytes=-100", 1000), Some(Ok((900, 999))));
+        assert_eq!(parse_range("bytes=500-5000", 1000), Some(Ok((500, 999))));
+        assert_eq!(parse_range("bytes=1000-", 1000), Some(Err(())));
+        assert_eq!(parse_range("bytes=0-1,5-6", 1000), None);
+        assert_eq!(parse_range("items=0-1", 1000), None);
+    }
+
+    #[tokio::test]
+    async fn test_reject_oversized_head() {
+        let cache = tempfile::tempdir().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(Arc::new(Server::new(cache.path(), None)).run(listener));
+
+        // A request line that never ends is cut off at the size limit
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let mut head = b"GET /".to_vec();
+        head.resize(MAX_HEAD_SIZE as usize, b'a');
+        stream.write_all(&head).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 400"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn test_serve_cache() {
+        let cache = tempfile::tempdir().unwrap();
+        let filename = "go1.22.5.linux-amd64.tar.gz";
+        let content: Vec<u8> = (0..=255).collect();
+        std::fs::write(cache.path().join(filename), &content).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mirror = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(Arc::new(Server::new(cache.path(), None)).run(listener));
+
+        let index: Vec<GoRelease> =
+            reqwest::get(ReleaseIndex::url(&mirror)).await.unwrap().json().await.unwrap();
+        assert_eq!(index.len(), 1);
+        let file = index[0].archive_for("linux", "amd64").unwrap();
+        assert_eq!(
+            file.sha256,
+            crate::downloader::sha256_file(cache.path().join(filename)).unwrap()
+        );
+
+        let client = reqwest::Client::new();
+        let url = format!("{mirror}/dl/{filename}");
+        let response = client.get(&url).header("Range", "bytes=10-19").send().await.unwrap();
+        assert_eq!(response.status(), 206);
+        assert_eq!(response.bytes().await.unwrap().as_ref(), &content[10..20]);
+
+        // Chunked downloads rely on HEAD sizes and ranges
+        let config = DownloadConfig { min_chunk_size: 64, ..DownloadConfig::default() };
+        let dest = cache.path().join("download").join(filename);
+        Downloader::with_config(config).download(&url, &dest).await.unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), content);
+        let response = client.get(format!("{mirror}/go1.21.0.linux-amd64.tar.gz")).send().await;
+        assert_eq!(response.unwrap().status(), 404);
+    }
+}
@@ -0,0 +1,287 @@
+//! Source build module
+//!
+//! Builds Go from a source tree with `make.bash` (`make.bat` on Windows), using an
+//! already-installed version as `GOROOT_BOOTSTRAP`. Source trees can come from an
+//! official source release, a local directory or archive, or a git ref.
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, info};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Default git remote for source builds
+pub const DEFAULT_SOURCE_REPO: &str = "https://go.googlesource.com/go";
+
+/// Number of output lines kept in build error messages
+const ERROR_TAIL_LINES: usize = 20;
+
+/// Where the source tree of a build comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceSpec {
+    /// Official source release (e.g. "1.22.3"), fetched as `go1.22.3.src.tar.gz`
+    Release(String),
+    /// Local source directory
+    Directory(PathBuf),
+    /// Local source archive
+    Archive(PathBuf),
+    /// Branch, tag or commit in the configured git remote
+    GitRef(String),
+}
+
+impl SourceSpec {
+    /// Parses a user-supplied source specification
+    ///
+    /// Existing paths take precedence, then release versions; anything else is a git ref.
+    pub fn parse(spec: &str) -> Self {
+        let path = Path::new(spec);
+        if path.is_dir() {
+            Self::Directory(path.to_path_buf())
+        } else if path.is_file() {
+            Self::Archive(path.to_path_buf())
+        } else if is_release_version(spec) {
+            Self::Release(spec.trim_start_matches("go").to_string())
+        } else {
+            Self::GitRef(spec.to_string())
+        }
+    }
+
+    /// Gets the version name implied by the specification, if any
+    pub fn implied_version(&self) -> Option<&str> {
+        match self {
+            Self::Release(version) => Some(version),
+            _ => None,
+        }
+    }
+}
+
+/// Checks whether a string looks like a Go release version (e.g. "1.22.3", "go1.23rc1")
+pub fn is_release_version(spec: &str) -> bool {
+    let version = spec.strip_prefix("go").unwrap_or(spec);
+    version.starts_with(|c: char| c.is_ascii_digit())
+        && version.contains('.')
+        && version.chars().all(|c| c.is_ascii_alphanumeric() || c == '.')
+}
+
+/// Generates the official source archive filename for a release
+pub fn source_archive_filename(version: &str) -> String {
+    format!("go{version}.src.tar.gz")
+}
+
+/// Derives an installed version name from a `VERSION` file line
+///
+/// Release trees contain e.g. "go1.22.3"; development trees ("devel ...") have no release name.
+pub fn version_name_from_go_version(line: &str) -> Option<String> {
+    let version = line.trim().strip_prefix("go")?;
+    is_release_version(version).then(|| version.to_string())
+}
+
+/// Reads the first line of the `VERSION` file of a source tree
+pub fn read_version_file(goroot: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(goroot.join("VERSION")).ok()?;
+    content.lines().next().map(|line| line.trim().to_string()).filter(|line| !line.is_empty())
+}
+
+/// Locates the Go root inside an extracted source archive
+///
+/// Official archives contain a single `go` directory; other archives may use any single
+/// top-level directory.
+pub fn find_source_root(extract_dir: &Path) -> Option<PathBuf> {
+    let go_dir = extract_dir.join("go");
+    if go_dir.join("src").is_dir() {
+        return Some(go_dir);
+    }
+
+    let mut dirs = std::fs::read_dir(extract_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir());
+    let first = dirs.next()?;
+    (dirs.next().is_none() && first.join("src").is_dir()).then_some(first)
+}
+
+/// Recursively copies a directory tree
+///
+/// # Errors
+/// Returns an error if any file or directory cannot be copied.
+pub fn copy_dir_all(from: &Path, to: &Path) -> Result<()> {
+    std::fs::create_dir_all(to)
+        .with_context(|| format!("Failed to create directory {}", to.display()))?;
+
+    for entry in std::fs::read_dir(from)
+        .with_context(|| format!("Failed to read directory {}", from.display()))?
+    {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = to.join(entry.file_name());
+
+        if file_type.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)
+                .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> Result<()> {
+    let target = std::fs::read_link(from)?;
+    std::os::unix::fs::symlink(target, to)
+        .with_context(|| format!("Failed to copy link {}", from.display()))
+}
+
+#[cfg(windows)]
+fn copy_symlink(from: &Path, to: &Path) -> Result<()> {
+    if from.is_dir() {
+        copy_dir_all(from, to)
+    } else {
+        std::fs::copy(from, to)
+            .map(|_| ())
+            .with_context(|| format!("Failed to copy {}", from.display()))
+    }
+}
+
+/// Fetches a git ref into a new working tree and returns the checked out commit
+///
+/// Uses a shallow fetch so branches, tags and full commit hashes all work.
+///
+/// # Errors
+/// Returns an error if git is unavailable or the ref cannot be fetched.
+pub fn git_fetch(remote: &str, reference: &str, dest: &Path) -> Result<String> {
+    std::fs::create_dir_all(dest)
+        .with_context(|| format!("Failed to create directory {}", dest.display()))?;
+
+    info!("Fetching {reference} from {remote}");
+    run_command(Command::new("git").arg("init").arg("-q").current_dir(dest), "git init")?;
+    run_command(
+        Command::new("git")
+            .args(["fetch", "-q", "--depth", "1", remote, reference])
+            .current_dir(dest),
+        "git fetch",
+    )?;
+    run_command(
+        Command::new("git").args(["checkout", "-q", "--detach", "FETCH_HEAD"]).current_dir(dest),
+        "git checkout",
+    )?;
+
+    git_head(dest).ok_or_else(|| anyhow!("Failed to resolve fetched commit for {reference}"))
+}
+
+/// Resolves a branch or tag of a remote to a commit without fetching it
+///
+/// Returns `None` when the ref is not advertised by the remote (e.g. a commit hash).
+///
+/// # Errors
+/// Returns an error if git is unavailable or the remote cannot be queried.
+pub fn git_ls_remote(remote: &str, reference: &str) -> Result<Option<String>> {
+    let output =
+        run_command(Command::new("git").args(["ls-remote", remote, reference]), "git ls-remote")?;
+    Ok(output
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .find(|(_, name)| {
+            *name == reference
+                || *name == format!("refs/heads/{reference}")
+                || *name == format!("refs/tags/{reference}")
+        })
+        .map(|(commit, _)| commit.to_string()))
+}
+
+/// Gets the commit checked out in a git working tree
+pub fn git_head(dir: &Path) -> Option<String> {
+    if !dir.join(".git").exists() {
+        return None;
+    }
+
+    run_command(Command::new("git").args(["rev-parse", "HEAD"]).current_dir(dir), "git rev-parse")
+        .ok()
+        .map(|output| output.trim().to_string())
+        .filter(|commit| !commit.is_empty())
+}
+
+/// Runs `make.bash` (or `make.bat`) in a Go source tree
+///
+/// # Errors
+/// Returns an error if the build script is missing or fails.
+pub fn run_make(goroot: &Path, bootstrap: &Path) -> Result<()> {
+    let src_dir = goroot.join("src");
+    let script = if cfg!(target_os = "windows") { "make.bat" } else { "make.bash" };
+    if !src_dir.join(script).exists() {
+        return Err(anyhow!("{} not found in {}", script, src_dir.display()));
+    }
+
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", script]);
+        command
+    } else {
+        let mut command = Command::new("bash");
+        command.arg(script);
+        command
+    };
+    command
+        .current_dir(&src_dir)
+        .env("GOROOT_BOOTSTRAP", bootstrap)
+        .env_remove("GOROOT")
+        .env_remove("GOBIN");
+
+    info!("Building Go in {} with GOROOT_BOOTSTRAP={}", goroot.display(), bootstrap.display());
+    run_command(&mut command, script).map(|_| ())
+}
+
+/// Runs a command, returning its stdout or an error with the tail of its output
+fn run_command(command: &mut Command, what: &str) -> Result<String> {
+    debug!("Running {command:?}");
+    let output = command.output().with_context(|| format!("Failed to run {what}"))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+
+    if output.status.success() {
+        return Ok(stdout);
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let combined = format!("{stdout}{stderr}");
+    let lines: Vec<&str> = combined.lines().collect();
+    let tail = lines[lines.len().saturating_sub(ERROR_TAIL_LINES)..].join("\n");
+    Err(anyhow!("{} failed ({}):\n{}", what, output.status, tail))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_release_version() {
+        assert!(is_release_version("1.22.3"));
+        assert!(is_release_version("go1.23rc1"));
+        assert!(!is_release_version("master"));
+        assert!(!is_release_version("release-branch.go1.22"));
+        assert!(!is_release_version("abc1234"));
+    }
+
+    #[test]
+    fn test_source_spec_parse() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("go.src.tar.gz");
+        std::fs::write(&archive, b"").unwrap();
+
+        assert_eq!(SourceSpec::parse("go1.22.3"), SourceSpec::Release("1.22.3".to_string()));
+        assert_eq!(SourceSpec::parse("master"), SourceSpec::GitRef("master".to_string()));
+        assert_eq!(
+            SourceSpec::parse(dir.path().to_str().unwrap()),
+            SourceSpec::Directory(dir.path().to_path_buf())
+        );
+        assert_eq!(SourceSpec::parse(archive.to_str().unwrap()), SourceSpec::Archive(archive));
+    }
+
+    #[test]
+    fn test_version_name_from_go_version() {
+        assert_eq!(version_name_from_go_version("go1.22.3"), Some("1.22.3".to_string()));
+        assert_eq!(version_name_from_go_version("devel go1.24-abc123 +0000"), None);
+    }
+}
@@ -0,0 +1,87 @@
+//! Terminal output module
+//!
+//! Data (version lists, key/value details, JSON documents) goes to stdout; messages and
+//! progress go to stderr. Colors follow `--color`, and progress is redrawn in place only
+//! when stderr is a terminal; otherwise it is reported as periodic plain lines.
+
+use crate::ui_flat::{verbosity, Verbosity};
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Interval between line-based progress updates when stderr is not a terminal
+pub const PROGRESS_LINE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Color mode selected with `--color`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when writing to a terminal, unless `NO_COLOR` is set or `TERM` is "dumb"
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Output stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    /// Command data
+    Stdout,
+    /// Messages and progress
+    Stderr,
+}
+
+impl Stream {
+    /// Checks whether the stream is attached to a terminal
+    pub fn is_terminal(self) -> bool {
+        match self {
+            Self::Stdout => std::io::stdout().is_terminal(),
+            Self::Stderr => std::io::stderr().is_terminal(),
+        }
+    }
+}
+
+/// How progress is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressStyle {
+    /// Redrawn in place
+    Interactive,
+    /// Periodic plain lines
+    Lines,
+    /// Not shown
+    Hidden,
+}
+
+static COLOR_CHOICE: OnceLock<ColorChoice> = OnceLock::new();
+
+/// Sets the process-wide color mode; only the first call takes effect
+pub fn set_color_choice(choice: ColorChoice) {
+    let _ = COLOR_CHOICE.set(choice);
+    // Styling is only applied where `use_colors` allows it, so `colored` must not second-guess
+    // it with its own stdout check
+    colored::control::set_override(true);
+}
+
+/// Checks whether output to a stream should be colored
+pub fn use_colors(stream: Stream) -> bool {
+    match COLOR_CHOICE.get().copied().unwrap_or_default() {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            std::env::var_os("NO_COLOR").is_none()
+                && std::env::var("TERM").unwrap_or_default() != "dumb"
+                && stream.is_terminal()
+        }
+    }
+}
+
+/// Gets how progress should be rendered on stderr
+pub fn progress_style() -> ProgressStyle {
+    if verbosity() == Verbosity::Quiet {
+        ProgressStyle::Hidden
+    } else if Stream::Stderr.is_terminal() {
+        ProgressStyle::Interactive
+    } else {
+        ProgressStyle::Lines
+    }
+}
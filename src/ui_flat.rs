@@ -2,75 +2,130 @@
 //!
 //! Provides a clean, cross-platform compatible user interface,
 //! using the colored crate for cross-platform color support.
+//! Messages go to stderr and data (lists, details) to stdout.
 
+use crate::terminal::{progress_style, use_colors, ProgressStyle, Stream, PROGRESS_LINE_INTERVAL};
 use colored::*;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+/// How much the UI prints, selected with `--quiet` and `--verbose`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only errors
+    Quiet,
+    #[default]
+    Normal,
+    /// Normal output plus detailed logs
+    Verbose,
+}
+
+static VERBOSITY: OnceLock<Verbosity> = OnceLock::new();
+
+/// Sets the process-wide verbosity; only the first call takes effect
+pub fn set_verbosity(verbosity: Verbosity) {
+    let _ = VERBOSITY.set(verbosity);
+}
+
+/// Gets the process-wide verbosity
+pub fn verbosity() -> Verbosity {
+    VERBOSITY.get().copied().unwrap_or_default()
+}
 
 /// Simplified UI manager
 pub struct SimpleUI {
+    /// Color messages on stderr
     use_colors: bool,
+    /// Color data on stdout
+    data_colors: bool,
+    /// Print only errors among the messages; data is still printed
+    quiet: bool,
+    /// Print nothing at all
+    silent: bool,
 }
 
 impl SimpleUI {
     /// Creates a new UI instance
     pub fn new() -> Self {
-        let use_colors = Self::should_use_colors();
-        Self { use_colors }
+        Self {
+            use_colors: use_colors(Stream::Stderr),
+            data_colors: use_colors(Stream::Stdout),
+            quiet: verbosity() == Verbosity::Quiet,
+            silent: false,
+        }
     }
 
-    /// Detects if colors should be used
-    fn should_use_colors() -> bool {
-        // colored crate handles this automatically, but we can add custom logic
-        std::env::var("NO_COLOR").is_err() && std::env::var("TERM").unwrap_or_default() != "dumb"
+    /// Creates a UI instance that prints nothing, for machine-readable output modes
+    pub fn silent() -> Self {
+        Self { use_colors: false, data_colors: false, quiet: true, silent: true }
     }
 
     /// Displays a success message
     pub fn success(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
         if self.use_colors {
-            println!("{} {}", "[OK]".green(), message);
+            eprintln!("{} {}", "[OK]".green(), message);
         } else {
-            println!("[OK] {message}");
+            eprintln!("[OK] {message}");
         }
     }
 
     /// Displays an error message
     pub fn error(&self, message: &str) {
+        if self.silent {
+            return;
+        }
         if self.use_colors {
-            println!("{} {}", "[ERROR]".red(), message);
+            eprintln!("{} {}", "[ERROR]".red(), message);
         } else {
-            println!("[ERROR] {message}");
+            eprintln!("[ERROR] {message}");
         }
     }
 
     /// Displays a warning message
     pub fn warning(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
         if self.use_colors {
-            println!("{} {}", "[WARN]".yellow(), message);
+            eprintln!("{} {}", "[WARN]".yellow(), message);
         } else {
-            println!("[WARN] {message}");
+            eprintln!("[WARN] {message}");
         }
     }
 
     /// Displays an informational message
     pub fn info(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
         if self.use_colors {
-            println!("{} {}", "[INFO]".blue(), message);
+            eprintln!("{} {}", "[INFO]".blue(), message);
         } else {
-            println!("[INFO] {message}");
+            eprintln!("[INFO] {message}");
         }
     }
 
     /// Displays a hint message
     pub fn hint(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
         if self.use_colors {
-            println!("{} {}", "[TIP]".cyan(), message);
+            eprintln!("{} {}", "[TIP]".cyan(), message);
         } else {
-            println!("[TIP] {message}");
+            eprintln!("[TIP] {message}");
         }
     }
 
     /// Displays a title
     pub fn title(&self, text: &str) {
+        if self.silent {
+            return;
+        }
         println!();
         println!("{}", "=".repeat(60));
         println!("{text}");
@@ -80,8 +135,11 @@ impl SimpleUI {
 
     /// Displays a section header
     pub fn section(&self, text: &str) {
+        if self.silent {
+            return;
+        }
         println!();
-        if self.use_colors {
+        if self.data_colors {
             println!("{}", format!("> {text}").cyan());
         } else {
             println!("> {text}");
@@ -91,8 +149,11 @@ impl SimpleUI {
 
     /// Displays a list item
     pub fn list_item(&self, text: &str, is_current: bool) {
+        if self.silent {
+            return;
+        }
         if is_current {
-            if self.use_colors {
+            if self.data_colors {
                 println!("  {} {}", format!("* {text}").green(), "(active)".dimmed());
             } else {
                 println!("  * {text} (active)");
@@ -102,9 +163,45 @@ impl SimpleUI {
         }
     }
 
+    /// Displays rows as aligned columns under a header, marking the current rows
+    pub fn table(&self, headers: &[&str], rows: &[(Vec<String>, bool)]) {
+        if self.silent {
+            return;
+        }
+        let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+        for (cells, _) in rows {
+            for (width, cell) in widths.iter_mut().zip(cells) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let format_row = |cells: &mut dyn Iterator<Item = &str>| -> String {
+            let cells: Vec<String> =
+                cells.zip(&widths).map(|(cell, width)| format!("{cell:<width$}")).collect();
+            cells.join("  ").trim_end().to_string()
+        };
+
+        let header = format_row(&mut headers.iter().copied());
+        if self.data_colors {
+            println!("    {}", header.dimmed());
+        } else {
+            println!("    {header}");
+        }
+        for (cells, is_current) in rows {
+            let line = format_row(&mut cells.iter().map(String::as_str));
+            match (is_current, self.data_colors) {
+                (true, true) => println!("  {}", format!("* {line}").green()),
+                (true, false) => println!("  * {line}"),
+                (false, _) => println!("    {line}"),
+            }
+        }
+    }
+
     /// Displays a key-value pair
     pub fn key_value(&self, key: &str, value: &str) {
-        if self.use_colors {
+        if self.silent {
+            return;
+        }
+        if self.data_colors {
             println!("  {}: {}", key.dimmed(), value);
         } else {
             println!("  {key}: {value}");
@@ -113,7 +210,10 @@ impl SimpleUI {
 
     /// Displays a colored key-value pair
     pub fn key_value_colored(&self, key: &str, value: &str, color: &str) {
-        if self.use_colors {
+        if self.silent {
+            return;
+        }
+        if self.data_colors {
             let colored_value = match color {
                 "green" => value.green().to_string(),
                 "red" => value.red().to_string(),
@@ -131,43 +231,77 @@ impl SimpleUI {
 
     /// Displays progress information
     pub fn progress(&self, current: usize, total: usize, description: &str) {
+        if self.quiet {
+            return;
+        }
         if self.use_colors {
-            println!(
+            eprintln!(
                 "[{}/{}] {}",
                 current.to_string().cyan(),
                 total.to_string().cyan(),
                 description
             );
         } else {
-            println!("[{current}/{total}] {description}");
+            eprintln!("[{current}/{total}] {description}");
         }
     }
 
     /// Displays a concise status message
     pub fn status(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
         if self.use_colors {
-            println!("{}", message.dimmed());
+            eprintln!("{}", message.dimmed());
         } else {
-            println!("{message}");
+            eprintln!("{message}");
         }
     }
 
     /// Displays a separator line
     pub fn separator(&self) {
+        if self.silent {
+            return;
+        }
         println!("{}", "-".repeat(50));
     }
 
     /// Displays a newline
     pub fn newline(&self) {
+        if self.silent {
+            return;
+        }
         println!();
     }
 
+    /// Asks a yes/no question on stderr and reads the answer from stdin
+    ///
+    /// Returns `None` when nobody can answer: stdin is not a terminal or the UI is silent.
+    pub fn confirm(&self, question: &str) -> Option<bool> {
+        if self.silent || !io::stdin().is_terminal() {
+            return None;
+        }
+        if self.use_colors {
+            eprint!("{} {} [y/N] ", "[?]".yellow(), question);
+        } else {
+            eprint!("[?] {question} [y/N] ");
+        }
+        let _ = io::stderr().flush();
+
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer).ok()?;
+        Some(matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes"))
+    }
+
     /// Displays a suggestion
     pub fn suggest(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
         if self.use_colors {
-            println!("{} Suggestion: {}", "->".cyan(), message);
+            eprintln!("{} Suggestion: {}", "->".cyan(), message);
         } else {
-            println!("-> Suggestion: {message}");
+            eprintln!("-> Suggestion: {message}");
         }
     }
 }
@@ -179,63 +313,102 @@ impl Default for SimpleUI {
 }
 
 /// A simple progress bar
+///
+/// Like the other progress displays it is redrawn in place on a terminal, printed as periodic
+/// plain lines otherwise, and hidden with `--quiet` (see [`progress_style`]).
 pub struct SimpleProgressBar {
     label: String,
     use_colors: bool,
+    style: ProgressStyle,
+    /// Time of the last line printed in line mode
+    last_line: Mutex<Option<Instant>>,
 }
 
 impl SimpleProgressBar {
     pub fn new(label: String) -> Self {
-        let use_colors = SimpleUI::should_use_colors();
-        Self { label, use_colors }
+        Self {
+            label,
+            use_colors: use_colors(Stream::Stderr),
+            style: progress_style(),
+            last_line: Mutex::new(None),
+        }
     }
 
     /// Updates the progress
     pub fn update(&self, percent: f64, message: Option<&str>) {
-        let bar_width = 30;
-        let filled = (percent * bar_width as f64) as usize;
-        let empty = bar_width - filled;
-
-        let bar = if self.use_colors {
-            format!("{}{}", "=".repeat(filled).green(), " ".repeat(empty))
-        } else {
-            format!("{}{}", "=".repeat(filled), " ".repeat(empty))
-        };
-
         let display_message = message.unwrap_or("");
-
-        if self.use_colors {
-            print!(
-                "\r{} [{}] {} {}",
-                self.label.cyan(),
-                bar,
-                format!("{:.1}%", percent * 100.0).bold(),
-                display_message
-            );
-        } else {
-            print!("\r{} [{}] {:.1}% {}", self.label, bar, percent * 100.0, display_message);
+        match self.style {
+            ProgressStyle::Hidden => {}
+            ProgressStyle::Interactive => {
+                let bar_width = 30;
+                let filled = (percent * bar_width as f64) as usize;
+                let empty = bar_width - filled;
+
+                let bar = if self.use_colors {
+                    format!("{}{}", "=".repeat(filled).green(), " ".repeat(empty))
+                } else {
+                    format!("{}{}", "=".repeat(filled), " ".repeat(empty))
+                };
+
+                if self.use_colors {
+                    eprint!(
+                        "\r{} [{}] {} {}",
+                        self.label.cyan(),
+                        bar,
+                        format!("{:.1}%", percent * 100.0).bold(),
+                        display_message
+                    );
+                } else {
+                    eprint!(
+                        "\r{} [{}] {:.1}% {}",
+                        self.label,
+                        bar,
+                        percent * 100.0,
+                        display_message
+                    );
+                }
+
+                io::stderr().flush().ok();
+            }
+            ProgressStyle::Lines => {
+                let Ok(mut last_line) = self.last_line.lock() else {
+                    return;
+                };
+                if last_line.map_or(true, |t| t.elapsed() >= PROGRESS_LINE_INTERVAL) {
+                    eprintln!("{} {:.1}% {}", self.label, percent * 100.0, display_message);
+                    *last_line = Some(Instant::now());
+                }
+            }
         }
-
-        io::stdout().flush().ok();
     }
 
     /// Finishes the progress
     pub fn finish(&self, message: &str) {
-        println!();
+        if self.style == ProgressStyle::Hidden {
+            return;
+        }
+        self.end_line();
         if self.use_colors {
-            println!("{} {}", "[OK]".green(), message);
+            eprintln!("{} {}", "[OK]".green(), message);
         } else {
-            println!("[OK] {message}");
+            eprintln!("[OK] {message}");
         }
     }
 
     /// Fails the progress
     pub fn fail(&self, message: &str) {
-        println!();
+        self.end_line();
         if self.use_colors {
-            println!("{} {}", "[ERROR]".red(), message);
+            eprintln!("{} {}", "[ERROR]".red(), message);
         } else {
-            println!("[ERROR] {message}");
+            eprintln!("[ERROR] {message}");
+        }
+    }
+
+    /// Ends the line of an in-place progress bar
+    fn end_line(&self) {
+        if self.style == ProgressStyle::Interactive {
+            eprintln!();
         }
     }
 }
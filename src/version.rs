@@ -0,0 +1,286 @@
+//! Go version number module
+//!
+//! Parses and orders Go version numbers such as "1.21.3", "1.20", "1.22rc1" and "1.21beta2".
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Pre-release kind, ordered as Go orders them (beta before rc)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PreRelease {
+    Beta(u32),
+    Rc(u32),
+}
+
+/// Parsed Go version number
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GoVersion {
+    pub major: u32,
+    pub minor: u32,
+    /// Patch number; `None` for pre-1.21 first releases ("1.20") and pre-releases
+    pub patch: Option<u32>,
+    pub pre: Option<PreRelease>,
+}
+
+impl GoVersion {
+    /// Parses a version number, with or without the "go" prefix
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.trim().strip_prefix("go").unwrap_or(version.trim());
+        let (major, rest) = version.split_once('.')?;
+        let major = major.parse().ok()?;
+
+        let minor_len = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let minor = rest[..minor_len].parse().ok()?;
+        let rest = &rest[minor_len..];
+
+        let (patch, pre) = if rest.is_empty() {
+            (None, None)
+        } else if let Some(patch) = rest.strip_prefix('.') {
+            (Some(patch.parse().ok()?), None)
+        } else if let Some(num) = rest.strip_prefix("rc") {
+            (None, Some(PreRelease::Rc(num.parse().ok()?)))
+        } else if let Some(num) = rest.strip_prefix("beta") {
+            (None, Some(PreRelease::Beta(num.parse().ok()?)))
+        } else {
+            return None;
+        };
+
+        Some(Self { major, minor, patch, pre })
+    }
+
+    /// Checks whether this is a stable (non pre-release) version
+    pub fn is_stable(&self) -> bool {
+        self.pre.is_none()
+    }
+
+    /// Gets the minor series, e.g. (1, 21) for 1.21.3
+    pub fn series(&self) -> (u32, u32) {
+        (self.major, self.minor)
+    }
+
+    /// Gets the series name, e.g. "1.21" for 1.21.3
+    pub fn series_name(&self) -> String {
+        format!("{}.{}", self.major, self.minor)
+    }
+}
+
+impl Ord for GoVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Pre-releases come before the first release of their series
+        self.series()
+            .cmp(&other.series())
+            .then_with(|| match (&self.pre, &other.pre) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+            .then_with(|| self.patch.unwrap_or(0).cmp(&other.patch.unwrap_or(0)))
+    }
+}
+
+impl PartialOrd for GoVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for GoVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        match (self.patch, self.pre) {
+            (Some(patch), _) => write!(f, ".{patch}"),
+            (None, Some(PreRelease::Rc(n))) => write!(f, "rc{n}"),
+            (None, Some(PreRelease::Beta(n))) => write!(f, "beta{n}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Compares two version names, ordering unparsable names (e.g. tip builds) after releases
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (GoVersion::parse(a), GoVersion::parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// A selection of installed versions: an exact name ("1.21.3", "tip-abc1234"), a minor
+/// series ("1.21") or comparisons that must all hold (">=1.19,<1.21")
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionSelector {
+    Exact(String),
+    Series(u32, u32),
+    Range(Vec<(Ordering, bool, GoVersion)>),
+}
+
+impl VersionSelector {
+    /// Parses a version selector
+    ///
+    /// # Errors
+    /// Returns an error if a range holds an invalid operator or version.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            return Err("empty version".to_string());
+        }
+        if !spec.starts_with(['<', '>', '=']) {
+            return Ok(match GoVersion::parse(spec) {
+                Some(v) if v.patch.is_none() && v.pre.is_none() => Self::Series(v.major, v.minor),
+                _ => Self::Exact(spec.to_string()),
+            });
+        }
+
+        let mut comparisons = Vec::new();
+        for part in spec.split(',').map(str::trim) {
+            // (operator, ordering, whether equality also matches)
+            let (op, ordering, or_equal) = [
+                (">=", Ordering::Greater, true),
+                ("<=", Ordering::Less, true),
+                (">", Ordering::Greater, false),
+                ("<", Ordering::Less, false),
+                ("=", Ordering::Equal, true),
+            ]
+            .into_iter()
+            .find(|(op, ..)| part.starts_with(op))
+            .ok_or_else(|| format!("'{part}' in '{spec}' needs one of <, <=, >, >=, ="))?;
+            let version = part[op.len()..].trim();
+            let version = GoVersion::parse(version)
+                .ok_or_else(|| format!("invalid version '{version}' in '{spec}'"))?;
+            comparisons.push((ordering, or_equal, version));
+        }
+        Ok(Self::Range(comparisons))
+    }
+
+    /// Checks whether an installed version name is selected; series and ranges only select
+    /// release versions
+    pub fn matches(&self, name: &str) -> bool {
+        if let Self::Exact(exact) = self {
+            return exact == name;
+        }
+        let Some(version) = GoVersion::parse(name) else {
+            return false;
+        };
+        match self {
+            Self::Series(major, minor) => version.series() == (*major, *minor),
+            Self::Range(comparisons) => comparisons.iter().all(|(ordering, or_equal, bound)| {
+                let actual = version.cmp(bound);
+                actual == *ordering || (*or_equal && actual == Ordering::Equal)
+            }),
+            Self::Exact(_) => unreachable!(),
+        }
+    }
+}
+
+impl fmt::Display for VersionSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exact(name) => write!(f, "{name}"),
+            Self::Series(major, minor) => write!(f, "{major}.{minor}"),
+            Self::Range(comparisons) => {
+                for (i, (ordering, or_equal, version)) in comparisons.iter().enumerate() {
+                    let op = match (ordering, or_equal) {
+                        (Ordering::Greater, true) => ">=",
+                        (Ordering::Greater, false) => ">",
+                        (Ordering::Less, true) => "<=",
+                        (Ordering::Less, false) => "<",
+                        (Ordering::Equal, _) => "=",
+                    };
+                    write!(f, "{}{op}{version}", if i == 0 { "" } else { "," })?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Finds the releases superseded within their minor series, keeping the `keep` newest of
+/// each series
+///
+/// Names that are not release versions (e.g. tip builds) are never superseded.
+pub fn superseded(versions: &[String], keep: usize) -> Vec<String> {
+    let mut releases: Vec<(GoVersion, &String)> =
+        versions.iter().filter_map(|name| Some((GoVersion::parse(name)?, name))).collect();
+    releases.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut superseded = Vec::new();
+    let mut series = None;
+    let mut kept = 0;
+    for (version, name) in releases {
+        if series != Some(version.series()) {
+            series = Some(version.series());
+            kept = 0;
+        }
+        if kept < keep {
+            kept += 1;
+        } else {
+            superseded.push(name.clone());
+        }
+    }
+    superseded.sort_by(|a, b| compare_versions(a, b));
+    superseded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let v = GoVersion::parse("go1.21.3").unwrap();
+        assert_eq!((v.major, v.minor, v.patch, v.pre), (1, 21, Some(3), None));
+        assert_eq!(GoVersion::parse("1.20").unwrap().patch, None);
+        assert_eq!(GoVersion::parse("1.22rc1").unwrap().pre, Some(PreRelease::Rc(1)));
+        assert_eq!(GoVersion::parse("1.21beta2").unwrap().pre, Some(PreRelease::Beta(2)));
+        assert!(GoVersion::parse("tip-abc1234").is_none());
+        assert!(GoVersion::parse("1.21.x").is_none());
+
+        for version in ["1.21.3", "1.20", "1.22rc1", "1.21beta2"] {
+            assert_eq!(GoVersion::parse(version).unwrap().to_string(), version);
+        }
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut versions =
+            vec!["1.21.0", "1.9", "1.21rc2", "tip-abc", "1.20", "1.21beta1", "1.20.14"];
+        versions.sort_by(|a, b| compare_versions(a, b));
+        assert_eq!(
+            versions,
+            ["1.9", "1.20", "1.20.14", "1.21beta1", "1.21rc2", "1.21.0", "tip-abc"]
+        );
+    }
+
+    #[test]
+    fn test_version_selector() {
+        let selected = |spec: &str| -> Vec<&str> {
+            let spec = VersionSelector::parse(spec).unwrap();
+            ["1.19.13", "1.20", "1.20.3", "1.21rc2", "1.21.0", "1.22.5", "tip-abc"]
+                .into_iter()
+                .filter(|name| spec.matches(name))
+                .collect()
+        };
+        assert_eq!(selected("1.20.3"), ["1.20.3"]);
+        assert_eq!(selected("1.20"), ["1.20", "1.20.3"]);
+        assert_eq!(selected("tip-abc"), ["tip-abc"]);
+        assert_eq!(selected("<1.21"), ["1.19.13", "1.20", "1.20.3", "1.21rc2"]);
+        assert_eq!(selected(">=1.20.3, <=1.21.0"), ["1.20.3", "1.21rc2", "1.21.0"]);
+        assert_eq!(selected(">1.21"), ["1.22.5"]);
+        assert_eq!(VersionSelector::parse(">=1.19,<1.21").unwrap().to_string(), ">=1.19,<1.21");
+        assert!(VersionSelector::parse("<banana").is_err());
+        assert!(VersionSelector::parse("=>1.21").is_err());
+    }
+
+    #[test]
+    fn test_superseded() {
+        let installed: Vec<String> =
+            ["1.20.14", "1.21.0", "1.21.13", "1.21.2", "1.22rc1", "1.22.0", "tip-abc1234"]
+                .iter()
+                .map(|v| v.to_string())
+                .collect();
+        assert_eq!(superseded(&installed, 1), ["1.21.0", "1.21.2", "1.22rc1"]);
+        assert_eq!(superseded(&installed, 2), ["1.21.0"]);
+    }
+}
@@ -0,0 +1,269 @@
+//! Exit code tests running the `gvm` binary against a temporary GVM root
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn gvm(root: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_gvm"))
+        .args(args)
+        .env("GVM_ROOT_PATH", root)
+        .env_remove("GVM_VERSIONS_PATH")
+        .env_remove("GVM_CACHE_PATH")
+        // Nothing listens on the discard port, so downloads fail fast
+        .env("GVM_MIRROR", "http://127.0.0.1:9")
+        .output()
+        .expect("failed to run gvm")
+}
+
+fn exit_code(root: &Path, args: &[&str]) -> i32 {
+    gvm(root, args).status.code().expect("gvm was terminated by a signal")
+}
+
+fn fake_installed_version(root: &Path, version: &str) {
+    std::fs::create_dir_all(root.join("versions").join(version).join("bin")).unwrap();
+}
+
+#[test]
+fn test_success_exits_zero() {
+    let root = tempfile::tempdir().unwrap();
+    fake_installed_version(root.path(), "1.21.0");
+
+    assert_eq!(exit_code(root.path(), &["list"]), 0);
+    assert_eq!(exit_code(root.path(), &["status"]), 0);
+    assert_eq!(exit_code(root.path(), &["use", "1.21.0"]), 0);
+    assert_eq!(exit_code(root.path(), &["info", "1.21.0"]), 0);
+}
+
+#[test]
+fn test_usage_errors() {
+    let root = tempfile::tempdir().unwrap();
+
+    assert_eq!(exit_code(root.path(), &["frobnicate"]), 2);
+    assert_eq!(exit_code(root.path(), &["install", "banana"]), 2);
+    assert_eq!(exit_code(root.path(), &["upgrade", "banana"]), 2);
+    assert_eq!(exit_code(root.path(), &["install", "--from-source", "a", "b"]), 2);
+    assert_eq!(exit_code(root.path(), &["install", "1.22.0", "--commit", "abc"]), 2);
+    assert_eq!(exit_code(root.path(), &["install", "1.22.0", "--bootstrap", "1.21.0"]), 2);
+    assert_eq!(exit_code(root.path(), &["install", "--from-source", "a", "--commit", "abc"]), 2);
+}
+
+#[test]
+fn test_not_installed() {
+    let root = tempfile::tempdir().unwrap();
+
+    let output = gvm(root.path(), &["use", "9.9.9"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Go version 9.9.9 is not installed"));
+
+    assert_eq!(exit_code(root.path(), &["uninstall", "9.9.9"]), 3);
+    assert_eq!(exit_code(root.path(), &["upgrade", "1.21"]), 3);
+}
+
+#[test]
+fn test_conflicts() {
+    let root = tempfile::tempdir().unwrap();
+    fake_installed_version(root.path(), "1.21.0");
+
+    assert_eq!(exit_code(root.path(), &["install", "1.21.0"]), 7);
+    assert_eq!(exit_code(root.path(), &["use", "1.21.0"]), 0);
+    assert_eq!(exit_code(root.path(), &["uninstall", "1.21.0"]), 7);
+    assert!(root.path().join("versions").join("1.21.0").exists());
+}
+
+#[test]
+fn test_leftover_work_dirs_are_not_versions() {
+    let root = tempfile::tempdir().unwrap();
+    for name in ["1.21.0", "1.22.0_temp", "1.21.0_previous"] {
+        fake_installed_version(root.path(), name);
+    }
+
+    let output = gvm(root.path(), &["-q", "list"]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "  - 1.21.0\n");
+    assert_eq!(exit_code(root.path(), &["uninstall", "1.22.0_temp"]), 3);
+    assert!(root.path().join("versions").join("1.22.0_temp").exists());
+}
+
+#[test]
+fn test_uninstall_several() {
+    let root = tempfile::tempdir().unwrap();
+    for version in ["1.20.3", "1.21.0", "1.21.5", "1.22.0"] {
+        fake_installed_version(root.path(), version);
+    }
+    let versions = root.path().join("versions");
+
+    assert_eq!(exit_code(root.path(), &["use", "1.21.5"]), 0);
+    assert_eq!(exit_code(root.path(), &["uninstall", "<1.22", "--dry-run"]), 0);
+    // Removing several versions needs a terminal or --yes
+    assert_eq!(exit_code(root.path(), &["uninstall", "<1.22"]), 2);
+    assert!(versions.join("1.20.3").exists());
+
+    assert_eq!(exit_code(root.path(), &["uninstall", "<1.22", "--yes"]), 0);
+    assert!(!versions.join("1.20.3").exists() && !versions.join("1.21.0").exists());
+    assert!(versions.join("1.21.5").exists(), "the active version is kept");
+    assert_eq!(exit_code(root.path(), &["uninstall", "--all-except-current"]), 0);
+    assert!(!versions.join("1.22.0").exists());
+}
+
+#[test]
+fn test_bundle_create() {
+    use sha2::{Digest, Sha256};
+
+    let root = tempfile::tempdir().unwrap();
+    let cache = root.path().join("cache");
+    std::fs::create_dir_all(&cache).unwrap();
+    // Cached archives matching the cached index, so that nothing is downloaded
+    let releases: Vec<serde_json::Value> = ["1.21.13", "1.22.5"]
+        .iter()
+        .map(|version| {
+            let files: Vec<serde_json::Value> = ["amd64", "arm64"]
+                .iter()
+                .map(|arch| {
+                    let filename = format!("go{version}.linux-{arch}.tar.gz");
+                    let content = filename.as_bytes();
+                    std::fs::write(cache.join(&filename), content).unwrap();
+                    serde_json::json!({
+                        "filename": filename, "os": "linux", "arch": arch,
+                        "version": format!("go{version}"), "kind": "archive",
+                        "sha256": format!("{:x}", Sha256::digest(content)),
+                        "size": content.len(),
+                    })
+                })
+                .collect();
+            serde_json::json!({ "version": format!("go{version}"), "stable": true, "files": files })
+        })
+        .collect();
+    std::fs::write(cache.join("releases.json"), serde_json::json!(releases).to_string()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_gvm"))
+        .args(["bundle", "create", "--versions", "1.21.13,1.22.5"])
+        .args(["--platforms", "linux/amd64,linux/arm64", "-o", "go-bundle.tar"])
+        .current_dir(root.path())
+        .env("GVM_ROOT_PATH", root.path())
+        .env_remove("GVM_VERSIONS_PATH")
+        .env_remove("GVM_CACHE_PATH")
+        .env("GVM_MIRROR", "http://127.0.0.1:9")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(root.path().join("go-bundle.tar").is_file());
+}
+
+#[test]
+fn test_network_failure() {
+    let root = tempfile::tempdir().unwrap();
+
+    assert_eq!(exit_code(root.path(), &["install", "1.21.0"]), 4);
+    assert_eq!(exit_code(root.path(), &["install", "latest"]), 4);
+}
+
+#[test]
+fn test_lockfile_mismatch() {
+    let root = tempfile::tempdir().unwrap();
+    let platforms = ["linux", "darwin", "windows"]
+        .iter()
+        .flat_map(|os| ["amd64", "arm64", "386", "armv6l"].map(|arch| (*os, arch)));
+    let files = |sha256: &str| -> Vec<serde_json::Value> {
+        platforms
+            .clone()
+            .map(|(os, arch)| {
+                serde_json::json!({
+                    "filename": format!("go1.21.0.{os}-{arch}.tar.gz"),
+                    "os": os, "arch": arch, "version": "go1.21.0",
+                    "sha256": sha256, "size": 1, "kind": "archive",
+                })
+            })
+            .collect()
+    };
+    let index = serde_json::json!([{ "version": "go1.21.0", "stable": true, "files": files("a") }]);
+    std::fs::create_dir_all(root.path().join("cache")).unwrap();
+    std::fs::write(root.path().join("cache").join("releases.json"), index.to_string()).unwrap();
+    let lockfile = serde_json::json!({
+        "lockfile_version": 1,
+        "versions": [{ "version": "1.21.0", "archives": files("b") }],
+    });
+    let lockfile_path = root.path().join("gvm.lock");
+    std::fs::write(&lockfile_path, lockfile.to_string()).unwrap();
+
+    let output = gvm(root.path(), &["sync", lockfile_path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[lockfile-mismatch]"));
+    assert!(!root.path().join("versions").join("1.21.0").exists());
+}
+
+/// Serves `body` for every request on a local port, returning the mirror URL
+#[cfg(unix)]
+fn serve_forever(body: Vec<u8>) -> String {
+    use std::io::{BufRead, BufReader, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let mirror = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            let mut line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                line.clear();
+            }
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(head.as_bytes());
+            if !request_line.starts_with("HEAD") {
+                let _ = stream.write_all(&body);
+            }
+        }
+    });
+    mirror
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sync_rejects_diverging_archive() {
+    let root = tempfile::tempdir().unwrap();
+    // A valid Go archive, but not the locked one
+    let tree = root.path().join("tree");
+    std::fs::create_dir_all(tree.join("go").join("bin")).unwrap();
+    std::fs::write(tree.join("go").join("bin").join("go"), "#!/bin/sh\n").unwrap();
+    let mut builder =
+        tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast()));
+    builder.append_dir_all("go", tree.join("go")).unwrap();
+    let archive = builder.into_inner().unwrap().finish().unwrap();
+    let mirror = serve_forever(archive);
+
+    let files: Vec<serde_json::Value> = ["linux", "darwin"]
+        .iter()
+        .flat_map(|os| ["amd64", "arm64", "386", "armv6l"].map(|arch| (*os, arch)))
+        .map(|(os, arch)| {
+            serde_json::json!({
+                "filename": format!("go1.21.0.{os}-{arch}.tar.gz"),
+                "os": os, "arch": arch, "version": "go1.21.0",
+                "sha256": "c".repeat(64), "size": 1, "kind": "archive",
+            })
+        })
+        .collect();
+    let index = serde_json::json!([{ "version": "go1.21.0", "stable": true, "files": files }]);
+    std::fs::create_dir_all(root.path().join("cache")).unwrap();
+    std::fs::write(root.path().join("cache").join("releases.json"), index.to_string()).unwrap();
+    let lockfile = serde_json::json!({
+        "lockfile_version": 1,
+        "versions": [{ "version": "1.21.0", "archives": files }],
+    });
+    let lockfile_path = root.path().join("gvm.lock");
+    std::fs::write(&lockfile_path, lockfile.to_string()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_gvm"))
+        .args(["sync", lockfile_path.to_str().unwrap()])
+        .env("GVM_ROOT_PATH", root.path())
+        .env_remove("GVM_VERSIONS_PATH")
+        .env_remove("GVM_CACHE_PATH")
+        .env("GVM_MIRROR", &mirror)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(5), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[checksum-mismatch]"));
+    let versions = root.path().join("versions");
+    assert!(!versions.exists() || std::fs::read_dir(&versions).unwrap().next().is_none());
+}
@@ -0,0 +1,61 @@
+//! `--output json` tests running the `gvm` binary against a temporary GVM root
+
+use serde_json::Value;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn gvm(root: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_gvm"))
+        .args(["--output", "json"])
+        .args(args)
+        .env("GVM_ROOT_PATH", root)
+        .env_remove("GVM_VERSIONS_PATH")
+        .env_remove("GVM_CACHE_PATH")
+        .env("GVM_MIRROR", "http://127.0.0.1:9")
+        .output()
+        .expect("failed to run gvm")
+}
+
+fn parse(bytes: &[u8]) -> Value {
+    serde_json::from_slice(bytes).unwrap_or_else(|e| {
+        panic!("invalid JSON ({e}): {}", String::from_utf8_lossy(bytes));
+    })
+}
+
+#[test]
+fn test_list_and_status_documents() {
+    let root = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(root.path().join("versions").join("1.21.0").join("bin")).unwrap();
+
+    let output = gvm(root.path(), &["use", "1.21.0"]);
+    assert!(output.status.success());
+    assert_eq!(parse(&output.stdout)["data"]["version"], "1.21.0");
+
+    let output = gvm(root.path(), &["list"]);
+    assert!(output.status.success());
+    let document = parse(&output.stdout);
+    assert_eq!(document["schema_version"], 1);
+    assert_eq!(document["command"], "list");
+    assert_eq!(document["data"]["total_count"], 1);
+    assert_eq!(document["data"]["versions"][0]["version"], "1.21.0");
+    assert_eq!(document["data"]["versions"][0]["is_current"], true);
+
+    let output = gvm(root.path(), &["status"]);
+    assert!(output.status.success());
+    assert_eq!(parse(&output.stdout)["data"]["current_version"], "1.21.0");
+}
+
+#[test]
+fn test_errors_are_json_on_stderr() {
+    let root = tempfile::tempdir().unwrap();
+
+    let output = gvm(root.path(), &["uninstall", "9.9.9"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(output.stdout.is_empty());
+
+    let document = parse(&output.stderr);
+    assert_eq!(document["schema_version"], 1);
+    assert_eq!(document["error"]["code"], "version-not-installed");
+    assert_eq!(document["error"]["kind"], "not_installed");
+    assert_eq!(document["error"]["exit_code"], 3);
+}
@@ -0,0 +1,63 @@
+//! Stream routing tests running the `gvm` binary with piped (non-terminal) output
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn gvm(root: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_gvm"))
+        .args(args)
+        .env("GVM_ROOT_PATH", root)
+        .env_remove("GVM_VERSIONS_PATH")
+        .env_remove("GVM_CACHE_PATH")
+        .env_remove("NO_COLOR")
+        .output()
+        .expect("failed to run gvm")
+}
+
+fn fake_installed_versions(root: &Path, versions: &[&str]) {
+    for version in versions {
+        std::fs::create_dir_all(root.join("versions").join(version).join("bin")).unwrap();
+    }
+}
+
+#[test]
+fn test_data_on_stdout_and_messages_on_stderr() {
+    let root = tempfile::tempdir().unwrap();
+    fake_installed_versions(root.path(), &["1.20.14", "1.21.0"]);
+
+    let output = gvm(root.path(), &["list"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stdout, "  - 1.20.14\n  - 1.21.0\n");
+    assert!(stderr.contains("[INFO] Total: 2 versions"));
+}
+
+#[test]
+fn test_quiet_keeps_data() {
+    let root = tempfile::tempdir().unwrap();
+    fake_installed_versions(root.path(), &["1.20.14", "1.21.0"]);
+
+    let output = gvm(root.path(), &["-q", "list"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "  - 1.20.14\n  - 1.21.0\n");
+    assert!(output.stderr.is_empty());
+
+    let output = gvm(root.path(), &["-q", "info", "1.21.0"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("1.21.0"));
+}
+
+#[test]
+fn test_colors_follow_color_option() {
+    let root = tempfile::tempdir().unwrap();
+    fake_installed_versions(root.path(), &["1.21.0"]);
+
+    // Piped output is not colored by default
+    let output = gvm(root.path(), &["use", "1.21.0"]);
+    assert!(!String::from_utf8_lossy(&output.stderr).contains('\x1b'));
+
+    let output = gvm(root.path(), &["--color", "always", "use", "1.21.0"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains('\x1b'));
+
+    let output = gvm(root.path(), &["--color", "never", "list"]);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains('\x1b'));
+}